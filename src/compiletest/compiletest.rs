@@ -72,7 +72,8 @@ pub fn parse_config(args: Vec<String> ) -> Config {
           reqopt("", "aux-base", "directory to find auxiliary test files", "PATH"),
           reqopt("", "stage-id", "the target-stage identifier", "stageN-TARGET"),
           reqopt("", "mode", "which sort of compile tests to run",
-                 "(compile-fail|parse-fail|run-fail|run-pass|run-pass-valgrind|pretty|debug-info)"),
+                 "(compile-fail|parse-fail|run-fail|run-pass|run-pass-valgrind|pretty|debug-info|\
+                  reproducible)"),
           optflag("", "ignored", "run tests marked as ignored"),
           optopt("", "runtool", "supervisor program to run tests under \
                                  (eg. emulator, valgrind)", "PROGRAM"),
@@ -260,12 +261,22 @@ pub fn test_opts(config: &Config) -> test::TestOpts {
             None => None,
             Some(ref filter) => Some(filter.clone()),
         },
+        filter_exact: false,
         run_ignored: config.run_ignored,
         logfile: config.logfile.clone(),
         run_tests: true,
         bench_benchmarks: true,
         nocapture: env::var("RUST_TEST_NOCAPTURE").is_ok(),
         color: test::AutoColor,
+        format: test::PrettyFormat,
+        junit_xml: None,
+        test_timeout: None,
+        isolate: false,
+        shard: None,
+        shuffle_seed: None,
+        save_baseline: None,
+        compare_baseline: None,
+        regression_threshold: test::DEFAULT_REGRESSION_THRESHOLD,
     }
 }
 
@@ -318,6 +329,7 @@ pub fn make_test(config: &Config, testfile: &Path) -> test::TestDescAndFn
             name: make_test_name(config, testfile),
             ignore: header::is_test_ignored(config, testfile),
             should_panic: test::ShouldPanic::No,
+            timeout: None,
         },
         testfn: make_test_closure(config, &testfile),
     }
@@ -12,14 +12,16 @@ use self::TargetLocation::*;
 
 use common::Config;
 use common::{CompileFail, ParseFail, Pretty, RunFail, RunPass, RunPassValgrind};
-use common::{Codegen, DebugInfoLldb, DebugInfoGdb, Rustdoc};
+use common::{Codegen, DebugInfoLldb, DebugInfoGdb, Rustdoc, Reproducible};
 use errors;
 use header::TestProps;
 use header;
 use procsrv;
 use util::logv;
 
+use std::cmp;
 use std::env;
+use std::ffi::OsString;
 use std::fmt;
 use std::fs::{self, File};
 use std::io::BufReader;
@@ -58,6 +60,7 @@ pub fn run(config: Config, testfile: &Path) {
         DebugInfoLldb => run_debuginfo_lldb_test(&config, &props, &testfile),
         Codegen => run_codegen_test(&config, &props, &testfile),
         Rustdoc => run_rustdoc_test(&config, &props, &testfile),
+        Reproducible => run_reproducible_test(&config, &props, &testfile),
     }
 }
 
@@ -1729,3 +1732,81 @@ fn run_rustdoc_test(config: &Config, props: &TestProps, testfile: &Path) {
         fatal_proc_rec("htmldocck failed!", &res);
     }
 }
+
+// reproducible tests (building twice with -Z deterministic)
+
+fn run_reproducible_test(config: &Config, props: &TestProps, testfile: &Path) {
+    // The two builds are written to different directories, so that output
+    // which depends on where it was built is caught as well.
+    let first = reproducible_output_dir(config, testfile, "first");
+    let second = reproducible_output_dir(config, testfile, "second");
+    for dir in &[&first, &second] {
+        let proc_res = compile_test_deterministically(config, props, testfile, dir);
+        if !proc_res.status.success() {
+            fatal_proc_rec("compilation failed!", &proc_res);
+        }
+    }
+
+    let outputs = sorted_file_names(&first);
+    if outputs != sorted_file_names(&second) {
+        fatal(&format!("the builds in {} and {} wrote different files",
+                       first.display(), second.display()));
+    }
+    for name in &outputs {
+        let a = read_all(&first.join(name));
+        let b = read_all(&second.join(name));
+        if a != b {
+            let offset = a.iter().zip(b.iter()).position(|(x, y)| x != y)
+                          .unwrap_or(cmp::min(a.len(), b.len()));
+            fatal(&format!("`{}` differs between the two builds, starting at byte {}",
+                           name.to_string_lossy(), offset));
+        }
+    }
+}
+
+fn reproducible_output_dir(config: &Config, testfile: &Path, build: &str) -> PathBuf {
+    let f = output_base_name(config, testfile);
+    let mut fname = f.file_name().unwrap().to_os_string();
+    fname.push(&format!(".{}", build));
+    let dir = f.with_file_name(&fname);
+    let _ = fs::remove_dir_all(&dir);
+    ensure_dir(&dir);
+    dir
+}
+
+fn compile_test_deterministically(config: &Config, props: &TestProps,
+                                  testfile: &Path, out_dir: &Path) -> ProcRes {
+    let aux_dir = aux_output_dir_name(config, testfile);
+    // FIXME (#9639): This needs to handle non-utf8 paths
+    let mut extra_args = vec!("-L".to_string(),
+                              aux_dir.to_str().unwrap().to_string(),
+                              "-Z".to_string(),
+                              "deterministic".to_string());
+    // Tests are libraries unless they say otherwise.
+    let flags = props.compile_flags.as_ref().map(|s| &s[..]).unwrap_or("");
+    if !flags.contains("--crate-type") {
+        extra_args.push("--crate-type=rlib".to_string());
+    }
+    let args = make_compile_args(config,
+                                 props,
+                                 extra_args,
+                                 |_, _| TargetLocation::ThisDirectory(out_dir.to_path_buf()),
+                                 testfile);
+    compose_and_run_compiler(config, props, testfile, args, None)
+}
+
+fn sorted_file_names(dir: &Path) -> Vec<OsString> {
+    let mut names: Vec<_> = fs::read_dir(dir).unwrap()
+                               .map(|entry| entry.unwrap().file_name())
+                               .collect();
+    names.sort();
+    names
+}
+
+fn read_all(path: &Path) -> Vec<u8> {
+    let mut contents = Vec::new();
+    File::open(path).and_then(|mut f| f.read_to_end(&mut contents))
+                    .unwrap_or_else(|e| fatal(&format!("failed to read {}: {}",
+                                                       path.display(), e)));
+    contents
+}
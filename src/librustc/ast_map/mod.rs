@@ -12,6 +12,8 @@ pub use self::Node::*;
 pub use self::PathElem::*;
 use self::MapEntry::*;
 
+use util::profiling;
+
 use syntax::abi;
 use syntax::ast::*;
 use syntax::ast_util;
@@ -932,6 +934,18 @@ pub fn map_crate<'ast, F: FoldOps>(forest: &'ast mut Forest, fold_ops: F) -> Map
               entries, vector_length, (entries as f64 / vector_length as f64) * 100.);
     }
 
+    if profiling::is_enabled() {
+        let items = map.iter().filter(|&x| {
+            match *x {
+                EntryItem(..) | EntryForeignItem(..) |
+                EntryTraitItem(..) | EntryImplItem(..) => true,
+                _ => false
+            }
+        }).count();
+        profiling::count("items", items);
+        profiling::count("AST map entries", map.len());
+    }
+
     Map {
         forest: forest,
         map: RefCell::new(map)
@@ -171,6 +171,7 @@ pub mod util {
     pub mod lev_distance;
     pub mod num;
     pub mod fs;
+    pub mod profiling;
 }
 
 pub mod lib {
@@ -231,6 +231,10 @@ pub struct Context<'a, 'tcx: 'a> {
     /// Level of lints for certain NodeIds, stored here because the body of
     /// the lint needs to run in trans.
     node_levels: RefCell<FnvHashMap<(ast::NodeId, LintId), LevelSource>>,
+
+    /// Number of warnings of each lint shown and left out, so that they can
+    /// be capped as set by `-Z lint-warning-limit`.
+    warning_counts: RefCell<FnvHashMap<LintId, (usize, usize)>>,
 }
 
 /// Convenience macro for calling a `LintPass` method on every pass in the context.
@@ -339,6 +343,7 @@ impl<'a, 'tcx> Context<'a, 'tcx> {
             lints: lint_store,
             level_stack: vec![],
             node_levels: RefCell::new(FnvHashMap()),
+            warning_counts: RefCell::new(FnvHashMap()),
         }
     }
 
@@ -353,9 +358,10 @@ impl<'a, 'tcx> Context<'a, 'tcx> {
         self.lints.levels.get(&LintId::of(lint)).map_or(Allow, |&(lvl, _)| lvl)
     }
 
-    fn lookup_and_emit(&self, lint: &'static Lint, span: Option<Span>, msg: &str) {
+    /// Emit a lint at the level in effect, returning whether it was emitted.
+    fn lookup_and_emit(&self, lint: &'static Lint, span: Option<Span>, msg: &str) -> bool {
         let (level, src) = match self.lints.levels.get(&LintId::of(lint)) {
-            None => return,
+            None => return false,
             Some(&(Warn, src)) => {
                 let lint_id = LintId::of(builtin::WARNINGS);
                 (self.lints.get_level_source(lint_id).0, src)
@@ -363,7 +369,27 @@ impl<'a, 'tcx> Context<'a, 'tcx> {
             Some(&pair) => pair,
         };
 
+        let limit = self.tcx.sess.opts.debugging_opts.lint_warning_limit;
+        if let (Warn, Some(limit)) = (level, limit) {
+            let id = LintId::of(lint);
+            let shown = self.warning_counts.borrow().get(&id).map_or(0, |counts| counts.0);
+            if shown >= limit {
+                self.warning_counts.borrow_mut().entry(id).or_insert((0, 0)).1 += 1;
+                return false
+            }
+            // Warnings the handler leaves out as duplicates don't count.
+            let handler = self.tcx.sess.diagnostic().handler();
+            let emitted = handler.warn_count();
+            raw_emit_lint(&self.tcx.sess, lint, (level, src), span, msg);
+            if handler.warn_count() == emitted {
+                return false
+            }
+            self.warning_counts.borrow_mut().entry(id).or_insert((0, 0)).0 += 1;
+            return true
+        }
+
         raw_emit_lint(&self.tcx.sess, lint, (level, src), span, msg);
+        level != Allow
     }
 
     /// Emit a lint at the appropriate level, with no associated span.
@@ -616,8 +642,15 @@ impl<'a, 'tcx> IdVisitingOperation for Context<'a, 'tcx> {
         match self.tcx.sess.lints.borrow_mut().remove(&id) {
             None => {}
             Some(lints) => {
-                for (lint_id, span, msg) in lints {
-                    self.span_lint(lint_id.lint, span, &msg[..])
+                for (lint_id, span, msg, suggestion) in lints {
+                    let emitted = self.lookup_and_emit(lint_id.lint, Some(span), &msg[..]);
+                    if let (true, Some(suggestion)) = (emitted, suggestion) {
+                        self.tcx.sess.span_suggestion_with_applicability(
+                            suggestion.span,
+                            &suggestion.msg[..],
+                            suggestion.replacement,
+                            suggestion.applicability);
+                    }
                 }
             }
         }
@@ -681,12 +714,22 @@ pub fn check_crate(tcx: &ty::ctxt,
     // If we missed any lints added to the session, then there's a bug somewhere
     // in the iteration code.
     for (id, v) in tcx.sess.lints.borrow().iter() {
-        for &(lint, span, ref msg) in v {
+        for &(lint, span, ref msg, _) in v {
             tcx.sess.span_bug(span,
                               &format!("unprocessed lint {} at {}: {}",
                                        lint.as_str(), tcx.map.node_to_string(*id), *msg))
         }
     }
 
+    // The warnings left out by `-Z lint-warning-limit` are summed up.
+    let mut capped: Vec<_> = cx.warning_counts.borrow().iter()
+                               .filter(|&(_, &(_, hidden))| hidden > 0)
+                               .map(|(lint, &(_, hidden))| (lint.as_str(), hidden))
+                               .collect();
+    capped.sort();
+    for (name, more) in capped {
+        tcx.sess.warn(&format!("...and {} more occurrences of the `{}` lint", more, name));
+    }
+
     *tcx.node_lint_levels.borrow_mut() = cx.node_levels.into_inner();
 }
@@ -273,6 +273,14 @@ pub struct ArchiveMetadata {
     data: *const [u8],
 }
 
+// The kinds of file that may hold a crate.
+enum CandidateKind {
+    Rlib,
+    /// Metadata only, from `--emit=metadata`. It is used as an rlib.
+    Rmeta,
+    Dylib,
+}
+
 pub struct CratePaths {
     pub ident: String,
     pub dylib: Option<PathBuf>,
@@ -406,14 +414,18 @@ impl<'a> Context<'a> {
                 None => return FileDoesntMatch,
                 Some(file) => file,
             };
-            let (hash, rlib) = if file.starts_with(&rlib_prefix[..]) &&
-                                  file.ends_with(".rlib") {
+            let (hash, kind_found) = if file.starts_with(&rlib_prefix[..]) &&
+                                        file.ends_with(".rlib") {
                 (&file[(rlib_prefix.len()) .. (file.len() - ".rlib".len())],
-                 true)
+                 CandidateKind::Rlib)
+            } else if file.starts_with(&rlib_prefix[..]) &&
+                      file.ends_with(".rmeta") {
+                (&file[(rlib_prefix.len()) .. (file.len() - ".rmeta".len())],
+                 CandidateKind::Rmeta)
             } else if file.starts_with(&dylib_prefix) &&
                       file.ends_with(&dypair.1) {
                 (&file[(dylib_prefix.len()) .. (file.len() - dypair.1.len())],
-                 false)
+                 CandidateKind::Dylib)
             } else {
                 if file.starts_with(&staticlib_prefix[..]) &&
                    file.ends_with(".a") {
@@ -428,13 +440,14 @@ impl<'a> Context<'a> {
 
             let hash_str = hash.to_string();
             let slot = candidates.entry(hash_str)
-                                 .or_insert_with(|| (HashMap::new(), HashMap::new()));
-            let (ref mut rlibs, ref mut dylibs) = *slot;
+                                 .or_insert_with(|| (HashMap::new(), HashMap::new(),
+                                                     HashMap::new()));
+            let (ref mut rlibs, ref mut rmetas, ref mut dylibs) = *slot;
             fs::canonicalize(path).map(|p| {
-                if rlib {
-                    rlibs.insert(p, kind);
-                } else {
-                    dylibs.insert(p, kind);
+                match kind_found {
+                    CandidateKind::Rlib => { rlibs.insert(p, kind); }
+                    CandidateKind::Rmeta => { rmetas.insert(p, kind); }
+                    CandidateKind::Dylib => { dylibs.insert(p, kind); }
                 }
                 FileMatches
             }).unwrap_or(FileDoesntMatch)
@@ -449,9 +462,13 @@ impl<'a> Context<'a> {
         // A Library candidate is created if the metadata for the set of
         // libraries corresponds to the crate id and hash criteria that this
         // search is being performed for.
+        //
+        // Metadata written by `--emit=metadata` stands in for an rlib, but a
+        // real rlib next to it is preferred.
         let mut libraries = Vec::new();
-        for (_hash, (rlibs, dylibs)) in candidates {
+        for (_hash, (rlibs, rmetas, dylibs)) in candidates {
             let mut metadata = None;
+            let rlibs = if rlibs.is_empty() { rmetas } else { rlibs };
             let rlib = self.extract_one(rlibs, "rlib", &mut metadata);
             let dylib = self.extract_one(dylibs, "dylib", &mut metadata);
             match metadata {
@@ -643,7 +660,8 @@ impl<'a> Context<'a> {
                         return false;
                     }
                 };
-                if file.starts_with("lib") && file.ends_with(".rlib") {
+                if file.starts_with("lib") &&
+                   (file.ends_with(".rlib") || file.ends_with(".rmeta")) {
                     return true
                 } else {
                     let (ref prefix, ref suffix) = dylibname;
@@ -660,7 +678,8 @@ impl<'a> Context<'a> {
             // Now that we have an iterator of good candidates, make sure
             // there's at most one rlib and at most one dylib.
             for loc in locs {
-                if loc.file_name().unwrap().to_str().unwrap().ends_with(".rlib") {
+                let file = loc.file_name().unwrap().to_str().unwrap();
+                if file.ends_with(".rlib") || file.ends_with(".rmeta") {
                     rlibs.insert(fs::canonicalize(&loc).unwrap(),
                                  PathKind::ExternFlag);
                 } else {
@@ -731,6 +750,9 @@ fn get_metadata_section_imp(target: &Target, filename: &Path)
     if !filename.exists() {
         return Err(format!("no such file: '{}'", filename.display()));
     }
+    if filename.file_name().unwrap().to_str().unwrap().ends_with(".rmeta") {
+        return get_rmeta_metadata(filename);
+    }
     if filename.file_name().unwrap().to_str().unwrap().ends_with(".rlib") {
         // Use ArchiveRO for speed here, it's backed by LLVM and uses mmap
         // internally to read the file. We also avoid even using a memcpy by
@@ -822,6 +844,24 @@ pub fn meta_section_name(target: &Target) -> &'static str {
     }
 }
 
+// Metadata written on its own by `--emit=metadata`: the encoding version
+// followed by the compressed metadata, as in the metadata section of a dylib.
+fn get_rmeta_metadata(filename: &Path) -> Result<MetadataBlob, String> {
+    let mut data = Vec::new();
+    if let Err(e) = fs::File::open(filename).and_then(|mut f| f.read_to_end(&mut data)) {
+        return Err(format!("error reading library: '{}': {}", filename.display(), e));
+    }
+    let vlen = encoder::metadata_encoding_version.len();
+    if data.len() < vlen || &data[..vlen] != encoder::metadata_encoding_version {
+        return Err(format!("incompatible metadata version found: '{}'",
+                           filename.display()));
+    }
+    match flate::inflate_bytes(&data[vlen..]) {
+        Ok(inflated) => Ok(MetadataVec(inflated)),
+        Err(_) => Err(format!("failed to decompress metadata: '{}'", filename.display())),
+    }
+}
+
 pub fn read_meta_section_name(target: &Target) -> &'static str {
     if target.options.is_like_osx {
         "__note.rustc"
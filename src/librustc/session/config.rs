@@ -71,6 +71,8 @@ pub enum OutputType {
     OutputTypeObject,
     OutputTypeExe,
     OutputTypeDepInfo,
+    /// Only the crate metadata, for type-checking dependent crates.
+    OutputTypeMetadata,
 }
 
 #[derive(Clone)]
@@ -109,9 +111,13 @@ pub struct Options {
     pub prints: Vec<PrintRequest>,
     pub cg: CodegenOptions,
     pub color: ColorConfig,
+    pub error_format: ErrorOutputType,
     pub show_span: Option<String>,
     pub externs: HashMap<String, Vec<String>>,
     pub crate_name: Option<String>,
+    /// Prefixes of source file paths to replace, as (from, to) pairs. Later
+    /// pairs take precedence.
+    pub remap_path_prefix: Vec<(String, String)>,
     /// An optional name to use as the crate for std during std injection,
     /// written `extern crate std = "name"`. Default to "std". Used by
     /// out-of-tree drivers.
@@ -120,11 +126,25 @@ pub struct Options {
     pub unstable_features: UnstableFeatures
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ErrorOutputType {
+    /// Diagnostics rendered for a human reader, possibly colored.
+    HumanReadable,
+    /// One JSON object per diagnostic, for tools consuming rustc's errors.
+    Json,
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub enum PrintRequest {
     FileNames,
     Sysroot,
     CrateName,
+    Cfg,
+    TargetList,
+    TargetSpec,
+    /// Printed while linking a static library, rather than instead of
+    /// compiling.
+    NativeStaticLibs,
 }
 
 pub enum Input {
@@ -169,6 +189,9 @@ impl OutputFilenames {
             OutputTypeLlvmAssembly => base.with_extension("ll"),
             OutputTypeObject => base.with_extension("o"),
             OutputTypeDepInfo => base.with_extension("d"),
+            OutputTypeMetadata => {
+                self.out_directory.join(&format!("lib{}.rmeta", self.filestem()))
+            }
             OutputTypeExe => base,
         }
     }
@@ -219,9 +242,11 @@ pub fn basic_options() -> Options {
         prints: Vec::new(),
         cg: basic_codegen_options(),
         color: Auto,
+        error_format: ErrorOutputType::HumanReadable,
         show_span: None,
         externs: HashMap::new(),
         crate_name: None,
+        remap_path_prefix: Vec::new(),
         alt_std_name: None,
         libs: Vec::new(),
         unstable_features: UnstableFeatures::Disallow,
@@ -549,6 +574,12 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "gather metadata statistics"),
     print_link_args: bool = (false, parse_bool,
         "Print the arguments passed to the linker"),
+    print_args: bool = (false, parse_bool,
+        "Print the arguments passed to the compiler, after expanding @path response files, \
+         one per line as quoted strings"),
+    deterministic: bool = (false, parse_bool,
+        "Produce the same output when building the same crate again (no timestamps \
+         or owners in archives)"),
     gc: bool = (false, parse_bool,
         "Garbage collect shared data (experimental)"),
     print_llvm_passes: bool = (false, parse_bool,
@@ -561,6 +592,8 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "List the symbols defined by a library crate"),
     save_analysis: bool = (false, parse_bool,
         "Write syntax and type analysis information in addition to normal output"),
+    save_analysis_json: bool = (false, parse_bool,
+        "Write syntax and type analysis information as JSON, in addition to normal output"),
     print_move_fragments: bool = (false, parse_bool,
         "Print out move-fragment data for every fn"),
     flowgraph_print_loans: bool = (false, parse_bool,
@@ -594,6 +627,16 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
           "Force drop flag checks on or off"),
     trace_macros: bool = (false, parse_bool,
           "For every macro invocation, print its name and arguments"),
+    apply_suggestions: bool = (false, parse_bool,
+          "Rewrite the source files with those of the compiler's suggested fixes \
+           which are known to be right"),
+    error_limit: Option<usize> = (None, parse_opt_uint,
+          "Stop after N errors"),
+    lint_warning_limit: Option<usize> = (None, parse_opt_uint,
+          "Show at most N warnings of each lint, and how many more there were"),
+    profile_output: Option<String> = (None, parse_opt_string,
+          "Write the time taken by each pass, memory use and other statistics to the \
+           given file, as a Chrome trace"),
 }
 
 pub fn default_lib_output() -> CrateType {
@@ -771,10 +814,11 @@ pub fn rustc_short_optgroups() -> Vec<RustcOptGroup> {
                "NAME"),
         opt::multi("", "emit", "Comma separated list of types of output for \
                               the compiler to emit",
-                 "[asm|llvm-bc|llvm-ir|obj|link|dep-info]"),
+                 "[asm|llvm-bc|llvm-ir|obj|link|dep-info|metadata]"),
         opt::multi("", "print", "Comma separated list of compiler information to \
                                print on stdout",
-                 "[crate-name|file-names|sysroot]"),
+                 "[crate-name|file-names|sysroot|cfg|target-list|target-spec-json|\
+                   native-static-libs]"),
         opt::flagmulti("g",  "",  "Equivalent to -C debuginfo=2"),
         opt::flagmulti("O", "", "Equivalent to -C opt-level=2"),
         opt::opt("o", "", "Write output to <filename>", "FILENAME"),
@@ -813,12 +857,21 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
             auto   = colorize, if output goes to a tty (default);
             always = always colorize output;
             never  = never colorize output", "auto|always|never"),
+        opt::opt_u("", "error-format", "How errors and other messages are produced:
+            human = human readable output (default);
+            json  = one JSON object per diagnostic", "human|json"),
+        opt::multi_u("", "remap-path-prefix", "Replace the prefix FROM of source file \
+                                               paths with TO in compiler messages, \
+                                               debuginfo, crate metadata and `file!()`",
+                     "FROM=TO"),
 
         opt::flagopt_u("", "pretty",
                    "Pretty-print the input instead of compiling;
                    valid types are: `normal` (un-annotated source),
                    `expanded` (crates expanded),
-                   `typed` (crates expanded, with type annotations), or
+                   `typed` (crates expanded, with type annotations),
+                   `typed,annotated` (crates expanded, with the inferred types of
+                   bindings and the methods that calls resolve to), or
                    `expanded,identified` (fully parenthesized, AST nodes with IDs).",
                  "TYPE"),
         opt::flagopt_u("", "xpretty",
@@ -828,6 +881,9 @@ pub fn rustc_optgroups() -> Vec<RustcOptGroup> {
                       `everybody_loops` (all function bodies replaced with `loop {}`).",
                      "TYPE"),
         opt::opt_u("", "show-span", "Show spans for compiler debugging", "expr|pat|ty"),
+        opt::flag_u("", "explain-all", "Print the explanation of every error code, \
+                                       noting those that have none"),
+        opt::opt_u("", "format", "Output format for --explain-all", "markdown|json"),
     ]);
     opts
 }
@@ -871,6 +927,13 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         unsafe { llvm::LLVMSetDebug(1); }
     }
 
+    if debugging_opts.error_limit == Some(0) {
+        early_error("-Z error-limit must be at least 1");
+    }
+    if debugging_opts.lint_warning_limit == Some(0) {
+        early_error("-Z lint-warning-limit must be at least 1");
+    }
+
     let mut output_types = Vec::new();
     if !debugging_opts.parse_only && !no_trans {
         let unparsed_output_types = matches.opt_strs("emit");
@@ -883,6 +946,7 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
                     "obj" => OutputTypeObject,
                     "link" => OutputTypeExe,
                     "dep-info" => OutputTypeDepInfo,
+                    "metadata" => OutputTypeMetadata,
                     _ => {
                         early_error(&format!("unknown emission type: `{}`",
                                             part))
@@ -975,6 +1039,10 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
             "crate-name" => PrintRequest::CrateName,
             "file-names" => PrintRequest::FileNames,
             "sysroot" => PrintRequest::Sysroot,
+            "cfg" => PrintRequest::Cfg,
+            "target-list" => PrintRequest::TargetList,
+            "target-spec-json" => PrintRequest::TargetSpec,
+            "native-static-libs" => PrintRequest::NativeStaticLibs,
             req => {
                 early_error(&format!("unknown print request `{}`", req))
             }
@@ -1000,6 +1068,19 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         }
     };
 
+    let error_format = match matches.opt_str("error-format").as_ref().map(|s| &s[..]) {
+        Some("human") => ErrorOutputType::HumanReadable,
+        Some("json") => ErrorOutputType::Json,
+
+        None => ErrorOutputType::HumanReadable,
+
+        Some(arg) => {
+            early_error(&format!("argument for --error-format must be human or \
+                                 json (instead was `{}`)",
+                                arg))
+        }
+    };
+
     let mut externs = HashMap::new();
     for arg in &matches.opt_strs("extern") {
         let mut parts = arg.splitn(2, '=');
@@ -1017,6 +1098,15 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
 
     let crate_name = matches.opt_str("crate-name");
 
+    let remap_path_prefix = matches.opt_strs("remap-path-prefix").iter().map(|arg| {
+        // The prefix being replaced may itself contain `=`, but the
+        // replacement may not.
+        match arg.rfind('=') {
+            Some(i) => (arg[..i].to_string(), arg[i + 1..].to_string()),
+            None => early_error("--remap-path-prefix value must be of the format `FROM=TO`"),
+        }
+    }).collect();
+
     Options {
         crate_types: crate_types,
         gc: gc,
@@ -1039,9 +1129,11 @@ pub fn build_session_options(matches: &getopts::Matches) -> Options {
         prints: prints,
         cg: cg,
         color: color,
+        error_format: error_format,
         show_span: None,
         externs: externs,
         crate_name: crate_name,
+        remap_path_prefix: remap_path_prefix,
         alt_std_name: None,
         libs: libs,
         unstable_features: get_unstable_features_setting(),
@@ -13,12 +13,14 @@ use metadata::cstore::CStore;
 use metadata::filesearch;
 use session::search_paths::PathKind;
 use util::nodemap::NodeMap;
+use util::profiling;
 
 use syntax::ast::NodeId;
 use syntax::codemap::Span;
 use syntax::diagnostic::{self, Emitter};
 use syntax::diagnostics;
 use syntax::feature_gate;
+use syntax::json;
 use syntax::parse;
 use syntax::parse::token;
 use syntax::parse::ParseSess;
@@ -30,6 +32,8 @@ use rustc_back::target::Target;
 use std::path::{Path, PathBuf};
 use std::cell::{Cell, RefCell};
 use std::env;
+use std::fs::File;
+use std::io::Write;
 
 pub mod config;
 pub mod search_paths;
@@ -53,7 +57,8 @@ pub struct Session {
     pub local_crate_source_file: Option<PathBuf>,
     pub working_dir: PathBuf,
     pub lint_store: RefCell<lint::LintStore>,
-    pub lints: RefCell<NodeMap<Vec<(lint::LintId, codemap::Span, String)>>>,
+    pub lints: RefCell<NodeMap<Vec<(lint::LintId, codemap::Span, String,
+                                    Option<diagnostic::CodeSuggestion>)>>>,
     pub plugin_llvm_passes: RefCell<Vec<String>>,
     pub plugin_attributes: RefCell<Vec<(String, AttributeType)>>,
     pub crate_types: RefCell<Vec<config::CrateType>>,
@@ -68,7 +73,8 @@ pub struct Session {
 
     pub can_print_warnings: bool,
 
-    next_node_id: Cell<ast::NodeId>
+    next_node_id: Cell<ast::NodeId>,
+    finished: Cell<bool>,
 }
 
 impl Session {
@@ -165,6 +171,10 @@ impl Session {
     pub fn span_suggestion(&self, sp: Span, msg: &str, suggestion: String) {
         self.diagnostic().span_suggestion(sp, msg, suggestion)
     }
+    pub fn span_suggestion_with_applicability(&self, sp: Span, msg: &str, suggestion: String,
+                                              applicability: diagnostic::Applicability) {
+        self.diagnostic().span_suggestion_with_applicability(sp, msg, suggestion, applicability)
+    }
     pub fn span_help(&self, sp: Span, msg: &str) {
         self.diagnostic().span_help(sp, msg)
     }
@@ -208,13 +218,31 @@ impl Session {
                     id: ast::NodeId,
                     sp: Span,
                     msg: String) {
+        self.add_lint_inner(lint, id, sp, msg, None)
+    }
+    /// Like `add_lint`, but the lint is followed by a suggested edit when
+    /// it is emitted.
+    pub fn add_lint_with_suggestion(&self,
+                                    lint: &'static lint::Lint,
+                                    id: ast::NodeId,
+                                    sp: Span,
+                                    msg: String,
+                                    suggestion: diagnostic::CodeSuggestion) {
+        self.add_lint_inner(lint, id, sp, msg, Some(suggestion))
+    }
+    fn add_lint_inner(&self,
+                      lint: &'static lint::Lint,
+                      id: ast::NodeId,
+                      sp: Span,
+                      msg: String,
+                      suggestion: Option<diagnostic::CodeSuggestion>) {
         let lint_id = lint::LintId::of(lint);
         let mut lints = self.lints.borrow_mut();
         match lints.get_mut(&id) {
-            Some(arr) => { arr.push((lint_id, sp, msg)); return; }
+            Some(arr) => { arr.push((lint_id, sp, msg, suggestion)); return; }
             None => {}
         }
-        lints.insert(id, vec!((lint_id, sp, msg)));
+        lints.insert(id, vec!((lint_id, sp, msg, suggestion)));
     }
     pub fn next_node_id(&self) -> ast::NodeId {
         self.reserve_node_ids(1)
@@ -292,6 +320,47 @@ impl Session {
             &self.opts.search_paths,
             kind)
     }
+
+    /// Does what has to be done however compilation stops: rewrites the
+    /// sources with the suggestions made, for `-Z apply-suggestions`, and
+    /// writes the profile, for `-Z profile-output`. Only the first call does
+    /// anything. Dropping the session calls it too, so that it also happens
+    /// when a pass aborts with errors.
+    pub fn finish(&self) {
+        if self.finished.get() {
+            return
+        }
+        self.finished.set(true);
+        if self.opts.debugging_opts.apply_suggestions {
+            self.apply_suggestions();
+        }
+        if let Some(ref path) = self.opts.debugging_opts.profile_output {
+            if let Err(e) = profiling::write(Path::new(path)) {
+                // Not `self.err`, which may panic, as it does with
+                // `-Z treat-err-as-bug`, while the session is being dropped.
+                self.diagnostic().handler().err(&format!("could not write profile to {}: {}",
+                                                         path, e));
+            }
+        }
+    }
+
+    /// Rewrites the source files with every machine-applicable suggestion
+    /// made so far applied.
+    fn apply_suggestions(&self) {
+        let suggestions = self.diagnostic().suggestions();
+        for (name, src) in diagnostic::apply_suggestions(self.codemap(), &suggestions) {
+            let res = File::create(&name).and_then(|mut f| f.write_all(src.as_bytes()));
+            if let Err(e) = res {
+                self.warn(&format!("failed to apply suggestions to `{}`: {}", name, e));
+            }
+        }
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        self.finish();
+    }
 }
 
 fn split_msg_into_multilines(msg: &str) -> Option<String> {
@@ -368,9 +437,17 @@ pub fn build_session(sopts: config::Options,
         .last()
         .unwrap_or(true);
 
-    let codemap = codemap::CodeMap::new();
-    let diagnostic_handler =
-        diagnostic::Handler::new(sopts.color, Some(registry), can_print_warnings);
+    let path_mapping = codemap::FilePathMapping::new(sopts.remap_path_prefix.clone());
+    let codemap = codemap::CodeMap::with_path_mapping(path_mapping);
+    let diagnostic_handler = match sopts.error_format {
+        config::ErrorOutputType::HumanReadable => {
+            diagnostic::Handler::new(sopts.color, Some(registry), can_print_warnings)
+        }
+        config::ErrorOutputType::Json => {
+            let emitter = Box::new(json::JsonEmitter::stderr(Some(registry)));
+            diagnostic::Handler::with_emitter(can_print_warnings, emitter)
+        }
+    };
     let span_diagnostic_handler =
         diagnostic::SpanHandler::new(diagnostic_handler, codemap);
 
@@ -389,6 +466,10 @@ pub fn build_session_(sopts: config::Options,
     }
     };
     let target_cfg = config::build_target_config(&sopts, &span_diagnostic);
+    span_diagnostic.handler().set_error_limit(sopts.debugging_opts.error_limit);
+    if sopts.debugging_opts.profile_output.is_some() {
+        profiling::start();
+    }
     let p_s = parse::ParseSess::with_span_handler(span_diagnostic);
     let default_sysroot = match sopts.maybe_sysroot {
         Some(_) => None,
@@ -434,7 +515,8 @@ pub fn build_session_(sopts: config::Options,
         features: RefCell::new(feature_gate::Features::new()),
         recursion_limit: Cell::new(64),
         can_print_warnings: can_print_warnings,
-        next_node_id: Cell::new(1)
+        next_node_id: Cell::new(1),
+        finished: Cell::new(false),
     };
 
     sess
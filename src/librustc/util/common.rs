@@ -20,6 +20,8 @@ use std::iter::repeat;
 use std::path::Path;
 use std::time::Duration;
 
+use util::profiling;
+
 use syntax::ast;
 use syntax::visit;
 use syntax::visit::Visitor;
@@ -36,7 +38,8 @@ pub fn time<T, U, F>(do_it: bool, what: &str, u: U, f: F) -> T where
     F: FnOnce(U) -> T,
 {
     thread_local!(static DEPTH: Cell<usize> = Cell::new(0));
-    if !do_it { return f(u); }
+    let profile = profiling::is_enabled();
+    if !do_it && !profile { return f(u); }
 
     let old = DEPTH.with(|slot| {
         let r = slot.get();
@@ -44,6 +47,7 @@ pub fn time<T, U, F>(do_it: bool, what: &str, u: U, f: F) -> T where
         r
     });
 
+    profiling::enter();
     let mut rv = None;
     let dur = {
         let ref mut rvp = rv;
@@ -53,22 +57,27 @@ pub fn time<T, U, F>(do_it: bool, what: &str, u: U, f: F) -> T where
         })
     };
     let rv = rv.unwrap();
-
-    // Hack up our own formatting for the duration to make it easier for scripts
-    // to parse (always use the same number of decimal places and the same unit).
-    const NANOS_PER_SEC: f64 = 1_000_000_000.0;
-    let secs = dur.secs() as f64;
-    let secs = secs + dur.extra_nanos() as f64 / NANOS_PER_SEC;
-
-    let mem_string = match get_resident() {
-        Some(n) => {
-            let mb = n as f64 / 1_000_000.0;
-            format!("; rss: {}MB", mb.round() as usize)
-        }
-        None => "".to_owned(),
-    };
-    println!("{}time: {:.3}{}\t{}", repeat("  ").take(old).collect::<String>(),
-             secs, mem_string, what);
+    let resident = get_resident();
+    profiling::exit(what, dur, resident);
+
+    if do_it {
+        // Hack up our own formatting for the duration to make it easier for
+        // scripts to parse (always use the same number of decimal places and
+        // the same unit).
+        const NANOS_PER_SEC: f64 = 1_000_000_000.0;
+        let secs = dur.secs() as f64;
+        let secs = secs + dur.extra_nanos() as f64 / NANOS_PER_SEC;
+
+        let mem_string = match resident {
+            Some(n) => {
+                let mb = n as f64 / 1_000_000.0;
+                format!("; rss: {}MB", mb.round() as usize)
+            }
+            None => "".to_owned(),
+        };
+        println!("{}time: {:.3}{}\t{}", repeat("  ").take(old).collect::<String>(),
+                 secs, mem_string, what);
+    }
 
     DEPTH.with(|slot| slot.set(old));
 
@@ -0,0 +1,192 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Self-profiling for `-Z profile-output`.
+//!
+//! While profiling is enabled on a thread, every pass timed there by
+//! `util::common::time` is recorded, whether or not `-Z time-passes` is
+//! given as well. Other statistics are recorded with `count`. The profile
+//! is written in the Chrome trace event format, which is plain JSON and can
+//! also be loaded into `chrome://tracing`:
+//!
+//! * Each pass is a complete (`"ph": "X"`) event, with `ts` and `dur` in
+//!   microseconds and, when known, the resident set size in bytes at its
+//!   end as `args.rss`.
+//! * Each statistic is a counter (`"ph": "C"`) event named after it, with
+//!   its value as `args.value`.
+//! * Events from the main thread have `tid` 0. Codegen units compiled on
+//!   worker threads have the number of the worker plus one.
+//!
+//! Timestamps are made up from the durations `util::common::time` measures:
+//! each pass starts where the pass before it on the thread ended, and passes
+//! within it start where it did. Time spent outside of timed passes doesn't
+//! show up as a gap, and is counted in the enclosing pass, if any. Worker
+//! threads start where the main thread was when they were spawned.
+
+use serialize::json::{self, Json};
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Duration;
+
+pub enum Event {
+    Pass {
+        name: String,
+        // Nanoseconds.
+        start: u64,
+        duration: u64,
+        rss: Option<usize>,
+        thread: usize,
+    },
+    Count {
+        name: String,
+        at: u64,
+        value: usize,
+        thread: usize,
+    },
+}
+
+struct Profile {
+    events: Vec<Event>,
+    // Where the last pass to end ended, in nanoseconds.
+    now: u64,
+    // Where each of the passes being timed started.
+    open: Vec<u64>,
+}
+
+thread_local!(static PROFILE: RefCell<Option<Profile>> = RefCell::new(None));
+
+/// Starts recording on this thread.
+pub fn start() {
+    start_at(0)
+}
+
+/// Starts recording on this thread at `origin`, as returned by `origin` on
+/// another thread.
+pub fn start_at(origin: u64) {
+    PROFILE.with(|p| {
+        *p.borrow_mut() = Some(Profile { events: vec![], now: origin, open: vec![] });
+    })
+}
+
+pub fn is_enabled() -> bool {
+    PROFILE.with(|p| p.borrow().is_some())
+}
+
+/// Where this thread's profile is at, for passing to `start_at` on another
+/// thread.
+pub fn origin() -> u64 {
+    PROFILE.with(|p| p.borrow().as_ref().map_or(0, |profile| profile.now))
+}
+
+/// Called when a timed pass begins.
+pub fn enter() {
+    PROFILE.with(|p| {
+        if let Some(ref mut profile) = *p.borrow_mut() {
+            let now = profile.now;
+            profile.open.push(now);
+        }
+    })
+}
+
+/// Called when the pass begun by the matching `enter` ends, having taken
+/// `duration`.
+pub fn exit(name: &str, duration: Duration, rss: Option<usize>) {
+    PROFILE.with(|p| {
+        if let Some(ref mut profile) = *p.borrow_mut() {
+            let start = profile.open.pop().unwrap();
+            let duration = duration.secs() * 1_000_000_000 + duration.extra_nanos() as u64;
+            profile.now = start + duration;
+            profile.events.push(Event::Pass {
+                name: name.to_string(),
+                start: start,
+                duration: duration,
+                rss: rss,
+                thread: 0,
+            });
+        }
+    })
+}
+
+/// Records a statistic, such as the number of items translated.
+pub fn count(name: &str, value: usize) {
+    PROFILE.with(|p| {
+        if let Some(ref mut profile) = *p.borrow_mut() {
+            let at = profile.now;
+            profile.events.push(Event::Count {
+                name: name.to_string(),
+                at: at,
+                value: value,
+                thread: 0,
+            });
+        }
+    })
+}
+
+/// Stops recording on this thread, and returns what was recorded.
+pub fn finish() -> Vec<Event> {
+    PROFILE.with(|p| p.borrow_mut().take().map(|profile| profile.events).unwrap_or(vec![]))
+}
+
+/// Adds events recorded by worker thread `thread` to this thread's profile.
+pub fn add_events(thread: usize, events: Vec<Event>) {
+    PROFILE.with(|p| {
+        if let Some(ref mut profile) = *p.borrow_mut() {
+            for mut event in events {
+                match event {
+                    Event::Pass { thread: ref mut t, .. } |
+                    Event::Count { thread: ref mut t, .. } => *t = thread,
+                }
+                profile.events.push(event);
+            }
+        }
+    })
+}
+
+/// Stops recording on this thread, and writes the profile to `path`.
+pub fn write(path: &Path) -> io::Result<()> {
+    let events = finish().iter().map(to_json).collect();
+    let mut trace = BTreeMap::new();
+    trace.insert("traceEvents".to_string(), Json::Array(events));
+    trace.insert("displayTimeUnit".to_string(), Json::String("ms".to_string()));
+
+    let mut file = try!(File::create(path));
+    write!(file, "{}", json::as_json(&Json::Object(trace)))
+}
+
+fn to_json(event: &Event) -> Json {
+    let mut obj = BTreeMap::new();
+    let mut args = BTreeMap::new();
+    let (name, thread) = match *event {
+        Event::Pass { ref name, start, duration, rss, thread } => {
+            obj.insert("ph".to_string(), Json::String("X".to_string()));
+            obj.insert("ts".to_string(), Json::F64(start as f64 / 1000.0));
+            obj.insert("dur".to_string(), Json::F64(duration as f64 / 1000.0));
+            if let Some(rss) = rss {
+                args.insert("rss".to_string(), Json::U64(rss as u64));
+            }
+            (name, thread)
+        }
+        Event::Count { ref name, at, value, thread } => {
+            obj.insert("ph".to_string(), Json::String("C".to_string()));
+            obj.insert("ts".to_string(), Json::F64(at as f64 / 1000.0));
+            args.insert("value".to_string(), Json::U64(value as u64));
+            (name, thread)
+        }
+    };
+    obj.insert("name".to_string(), Json::String(name.clone()));
+    obj.insert("pid".to_string(), Json::U64(0));
+    obj.insert("tid".to_string(), Json::U64(thread as u64));
+    obj.insert("args".to_string(), Json::Object(args));
+    Json::Object(obj)
+}
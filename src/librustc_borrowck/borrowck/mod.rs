@@ -39,6 +39,7 @@ use std::rc::Rc;
 use syntax::ast;
 use syntax::ast_util;
 use syntax::codemap::Span;
+use syntax::diagnostic::Applicability;
 use syntax::parse::token;
 use syntax::visit;
 use syntax::visit::{Visitor, FnKind};
@@ -779,6 +780,23 @@ impl<'a, 'tcx> BorrowckCtxt<'a, 'tcx> {
             "re-assignment of immutable variable `{}`",
             self.loan_path_to_string(lp));
         self.tcx.sess.span_note(assign.span, "prior assignment occurs here");
+        if let LpVar(id) = lp.kind {
+            self.suggest_mut_binding(id);
+        }
+    }
+
+    /// Suggests declaring the local variable `id` as `mut`, if it is a plain
+    /// by-value binding that can simply be prefixed.
+    fn suggest_mut_binding(&self, id: ast::NodeId) {
+        if let Some(ast_map::NodeLocal(pat)) = self.tcx.map.find(id) {
+            if let ast::PatIdent(ast::BindByValue(ast::MutImmutable), ref ident, None) = pat.node {
+                self.tcx.sess.span_suggestion_with_applicability(
+                    pat.span,
+                    "to make this binding mutable, declare it `mut`:",
+                    format!("mut {}", token::get_ident(ident.node)),
+                    Applicability::MachineApplicable);
+            }
+        }
     }
 
     pub fn span_err(&self, s: Span, m: &str) {
@@ -992,7 +1010,11 @@ impl<'a, 'tcx> BorrowckCtxt<'a, 'tcx> {
                                  self by mutable reference");
                         }
                     }
-                    _ => {}
+                    _ => {
+                        if let mc::cat_local(id) = err.cmt.cat {
+                            self.suggest_mut_binding(id);
+                        }
+                    }
                 }
             }
 
@@ -0,0 +1,157 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Expansion of `@path` response files on the command line.
+//!
+//! An argument of the form `@path` is replaced by the arguments read from
+//! the file at `path`, which must be UTF-8. The rules for splitting the
+//! file into arguments are the same on every platform:
+//!
+//! * Arguments are separated by any amount of whitespace, including
+//!   newlines.
+//! * Text between double quotes is part of one argument, whitespace
+//!   included. A backslash inside double quotes escapes only `"` and `\`.
+//! * Text between single quotes is taken literally.
+//! * Outside of quotes, a backslash takes the character after it literally.
+//! * A line whose first non-whitespace character is `#` is a comment.
+//!
+//! Arguments read from a response file are not expanded again, so a file
+//! cannot include another one. An argument which is only `@` is left alone.
+
+use std::fs::File;
+use std::io::Read;
+
+/// Replaces every `@path` argument with the contents of that file.
+pub fn expand(args: Vec<String>) -> Result<Vec<String>, String> {
+    let mut expanded = Vec::with_capacity(args.len());
+    for arg in args {
+        if arg.len() > 1 && arg.starts_with("@") {
+            let path = &arg[1..];
+            let mut contents = String::new();
+            if let Err(e) = File::open(path).and_then(|mut f| f.read_to_string(&mut contents)) {
+                return Err(format!("failed to read response file `{}`: {}", path, e));
+            }
+            match split(&contents) {
+                Ok(args) => expanded.extend(args),
+                Err(e) => return Err(format!("in response file `{}`: {}", path, e)),
+            }
+        } else {
+            expanded.push(arg);
+        }
+    }
+    Ok(expanded)
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Quote {
+    None,
+    Single,
+    Double,
+}
+
+/// Splits the contents of a response file into arguments.
+fn split(contents: &str) -> Result<Vec<String>, String> {
+    let mut args = vec![];
+    let mut arg = String::new();
+    // Whether `arg` has begun, as `""` is an empty argument.
+    let mut in_arg = false;
+    // Whether only whitespace has been seen since the last newline.
+    let mut line_start = true;
+    let mut quote = Quote::None;
+    let mut chars = contents.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if quote == Quote::None && c.is_whitespace() {
+            if in_arg {
+                args.push(arg);
+                arg = String::new();
+                in_arg = false;
+            }
+            if c == '\n' {
+                line_start = true;
+            }
+            continue
+        }
+        if quote == Quote::None && c == '#' && line_start {
+            while let Some(c) = chars.next() {
+                if c == '\n' {
+                    break
+                }
+            }
+            continue
+        }
+        line_start = false;
+        in_arg = true;
+
+        match (quote, c) {
+            (Quote::None, '\'') => quote = Quote::Single,
+            (Quote::None, '"') => quote = Quote::Double,
+            (Quote::None, '\\') => {
+                match chars.next() {
+                    Some(c) => arg.push(c),
+                    None => return Err("trailing backslash".to_string()),
+                }
+            }
+            (Quote::Single, '\'') | (Quote::Double, '"') => quote = Quote::None,
+            (Quote::Double, '\\') => {
+                match chars.peek() {
+                    Some(&c) if c == '"' || c == '\\' => {
+                        arg.push(c);
+                        chars.next();
+                    }
+                    _ => arg.push('\\'),
+                }
+            }
+            (_, c) => arg.push(c),
+        }
+    }
+
+    match quote {
+        Quote::None => {}
+        Quote::Single => return Err("unterminated single quote".to_string()),
+        Quote::Double => return Err("unterminated double quote".to_string()),
+    }
+    if in_arg {
+        args.push(arg);
+    }
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split;
+
+    fn args(s: &str) -> Vec<String> {
+        split(s).unwrap()
+    }
+
+    #[test]
+    fn whitespace() {
+        assert_eq!(args("  -L foo\n\t--cfg  bar \n"), ["-L", "foo", "--cfg", "bar"]);
+        assert!(args(" \n ").is_empty());
+    }
+
+    #[test]
+    fn quotes() {
+        assert_eq!(args(r#"--cfg 'feature="a b"' "x y" """#),
+                   ["--cfg", r#"feature="a b""#, "x y", ""]);
+        assert_eq!(args(r#""a \"b\" \\ \n""#), [r#"a "b" \ \n"#]);
+        assert_eq!(args(r"a\ b 'c\d'"), ["a b", r"c\d"]);
+        assert!(split("'a").is_err());
+        assert!(split("\"a").is_err());
+        assert!(split("a\\").is_err());
+    }
+
+    #[test]
+    fn comments() {
+        assert_eq!(args("# a comment\n-O\n  # indented\nfoo#bar"), ["-O", "foo#bar"]);
+        assert_eq!(args("'# not a comment'"), ["# not a comment"]);
+    }
+}
@@ -25,6 +25,7 @@ use rustc_borrowck as borrowck;
 use rustc_resolve as resolve;
 use rustc_trans::back::link;
 use rustc_trans::back::write;
+use rustc_trans::save;
 use rustc_trans::trans;
 use rustc_typeck as typeck;
 use rustc_privacy;
@@ -58,6 +59,7 @@ pub fn compile_input(sess: Session,
 
         $tsess.abort_if_errors();
         if control.$point.stop == Compilation::Stop {
+            finish_compilation(&$tsess);
             return;
         }
     })}
@@ -89,7 +91,10 @@ pub fn compile_input(sess: Session,
                                                      krate,
                                                      &id[..],
                                                      addl_plugins) {
-                    None => return,
+                    None => {
+                        finish_compilation(&sess);
+                        return;
+                    }
                     Some(k) => k
                 };
 
@@ -141,6 +146,15 @@ pub fn compile_input(sess: Session,
                 }
             }
 
+            // Nothing but the metadata is needed, so translation and LLVM are
+            // skipped. The `after_llvm` callback is not called either, since
+            // there is no `CrateTranslation` to give it.
+            if metadata_only(&tcx.sess) {
+                let metadata = phase_4_encode_metadata(tcx, analysis);
+                link::write_metadata_file(&tcx.sess, &metadata, &outputs);
+                return Err(());
+            }
+
             if log_enabled!(::log::INFO) {
                 println!("Pre-trans");
                 tcx.print_debug_stats();
@@ -162,9 +176,16 @@ pub fn compile_input(sess: Session,
     let (outputs, trans) = if let Ok(out) = result {
         out
     } else {
+        finish_compilation(&sess);
         return;
     };
 
+    if sess.opts.output_types.contains(&config::OutputTypeMetadata) {
+        time(sess.time_passes(), "writing metadata", (), |_|
+             link::write_metadata_file(&sess, &trans.metadata, &outputs));
+        sess.abort_if_errors();
+    }
+
     phase_5_run_llvm_passes(&sess, &trans, &outputs);
 
     controller_entry_point!(after_llvm,
@@ -175,6 +196,23 @@ pub fn compile_input(sess: Session,
                                                            &trans));
 
     phase_6_link_output(&sess, &trans, &outputs);
+    finish_compilation(&sess);
+}
+
+/// Whether `--emit` asks for nothing that needs the crate to be translated.
+fn metadata_only(sess: &Session) -> bool {
+    sess.opts.output_types.contains(&config::OutputTypeMetadata) &&
+        sess.opts.output_types.iter().all(|&t| {
+            t == config::OutputTypeMetadata || t == config::OutputTypeDepInfo
+        })
+}
+
+/// Finishes the session where compilation stops in an orderly way, so that
+/// failing to finish it fails compilation. Compilation stopped by errors is
+/// finished when the session is dropped.
+fn finish_compilation(sess: &Session) {
+    sess.finish();
+    sess.abort_if_errors();
 }
 
 /// The name used for source code that doesn't originate in a file
@@ -226,6 +264,15 @@ impl<'a> CompileController<'a> {
             make_glob_map: resolve::MakeGlobMap::No,
         }
     }
+
+    /// Calls `f` with the save-analysis data for the crate once analysis
+    /// has finished, replacing any other `after_analysis` callback.
+    pub fn save_analysis<F>(&mut self, f: F) where F: Fn(save::AnalysisIndex) + 'a {
+        self.after_analysis.callback = box move |state| {
+            f(save::analyze_crate(state.tcx.unwrap(), state.analysis.unwrap()))
+        };
+        self.make_glob_map = resolve::MakeGlobMap::Yes;
+    }
 }
 
 pub struct PhaseController<'a> {
@@ -747,6 +794,18 @@ pub fn phase_4_translate_to_llvm(tcx: &ty::ctxt, analysis: ty::CrateAnalysis)
          trans::trans_crate(tcx, analysis))
 }
 
+/// Encode the crate metadata without translating the crate, for
+/// `--emit=metadata`. The AST and analysis can be discarded afterwards.
+pub fn phase_4_encode_metadata(tcx: &ty::ctxt, analysis: ty::CrateAnalysis) -> Vec<u8> {
+    let time_passes = tcx.sess.time_passes();
+
+    time(time_passes, "resolving dependency formats", (), |_|
+         dependency_format::calculate(tcx));
+
+    time(time_passes, "metadata encoding", analysis, |analysis|
+         trans::encode_metadata_only(tcx, analysis))
+}
+
 /// Run LLVM itself, producing a bitcode file, assembly file or object file
 /// as a side effect.
 pub fn phase_5_run_llvm_passes(sess: &Session,
@@ -838,7 +897,7 @@ fn write_out_deps(sess: &Session,
                                    .iter()
                                    .filter(|fmap| fmap.is_real_file())
                                    .filter(|fmap| !fmap.is_imported())
-                                   .map(|fmap| escape_dep_filename(&fmap.name))
+                                   .map(|fmap| escape_dep_filename(&fmap.unmapped_name))
                                    .collect();
         let mut file = try!(fs::File::create(&deps_filename));
         for path in &out_filenames {
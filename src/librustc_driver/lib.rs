@@ -67,6 +67,8 @@ use rustc::lint;
 use rustc::metadata;
 use rustc::util::common::time;
 
+use serialize::json::{self, ToJson};
+
 use std::cmp::Ordering::Equal;
 use std::env;
 use std::io::{self, Read, Write};
@@ -87,6 +89,7 @@ use syntax::diagnostics;
 #[cfg(test)]
 pub mod test;
 
+pub mod args;
 pub mod driver;
 pub mod pretty;
 
@@ -122,6 +125,10 @@ pub fn run_compiler<'a>(args: &[String],
 
     let sopts = config::build_session_options(&matches);
 
+    if sopts.debugging_opts.print_args {
+        print_args(args);
+    }
+
     let (odir, ofile) = make_output(&matches);
     let (input, input_file_path) = match make_input(&matches.free) {
         Some((input, input_file_path)) => callbacks.some_input(input, input_file_path),
@@ -156,6 +163,20 @@ pub fn run_compiler<'a>(args: &[String],
     driver::compile_input(sess, cfg, &input, &odir, &ofile, Some(plugins), control);
 }
 
+// For `-Z print-args`: prints the arguments after the binary name, with @path
+// response files expanded, one per line. Each is quoted and escaped like a
+// Rust string literal, so that an argument spanning lines stays on one.
+fn print_args(args: &[String]) {
+    // Expanding already succeeded in `handle_options`.
+    let args = match args::expand(args[1..].to_vec()) {
+        Ok(args) => args,
+        Err(e) => early_error(&e),
+    };
+    for arg in &args {
+        println!("{:?}", arg);
+    }
+}
+
 // Extract output directory and file from matches.
 fn make_output(matches: &getopts::Matches) -> (Option<PathBuf>, Option<PathBuf>) {
     let odir = matches.opt_str("out-dir").map(|o| PathBuf::from(&o));
@@ -287,6 +308,17 @@ impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
             None => ()
         }
 
+        if matches.opt_present("explain-all") {
+            match matches.opt_str("format").as_ref().map(|s| &s[..]) {
+                None | Some("markdown") => print_explanations_markdown(descriptions),
+                Some("json") => print_explanations_json(descriptions),
+                Some(format) => {
+                    early_error(&format!("unknown format for --explain-all: `{}`", format));
+                }
+            }
+            return Compilation::Stop;
+        }
+
         return Compilation::Continue;
     }
 
@@ -374,13 +406,20 @@ impl<'a> CompilerCalls<'a> for RustcDefaultCalls {
             control.after_llvm.stop = Compilation::Stop;
         }
 
-        if sess.opts.debugging_opts.save_analysis {
-            control.after_analysis.callback = box |state| {
+        if sess.opts.debugging_opts.save_analysis ||
+           sess.opts.debugging_opts.save_analysis_json {
+            let format = if sess.opts.debugging_opts.save_analysis_json {
+                save::Format::Json
+            } else {
+                save::Format::Csv
+            };
+            control.after_analysis.callback = box move |state| {
                 time(state.session.time_passes(),
                      "save analysis", (),
                      |_| save::process_crate(state.tcx.unwrap(),
                                              state.analysis.unwrap(),
-                                             state.out_dir));
+                                             state.out_dir,
+                                             format));
             };
             control.make_glob_map = resolve::MakeGlobMap::Yes;
         }
@@ -421,7 +460,8 @@ impl RustcDefaultCalls {
                         odir: &Option<PathBuf>,
                         ofile: &Option<PathBuf>)
                         -> Compilation {
-        if sess.opts.prints.is_empty() {
+        // Native libraries are printed when a static library is linked.
+        if sess.opts.prints.iter().all(|req| *req == PrintRequest::NativeStaticLibs) {
             return Compilation::Continue;
         }
 
@@ -429,6 +469,28 @@ impl RustcDefaultCalls {
         for req in &sess.opts.prints {
             match *req {
                 PrintRequest::Sysroot => println!("{}", sess.sysroot().display()),
+                PrintRequest::TargetList => {
+                    for target in rustc_back::target::TARGETS {
+                        println!("{}", target);
+                    }
+                }
+                PrintRequest::TargetSpec => {
+                    println!("{}", json::as_pretty_json(&sess.target.target.to_json()));
+                }
+                PrintRequest::Cfg => {
+                    for cfg in &config::build_configuration(sess) {
+                        match cfg.node {
+                            ast::MetaWord(ref name) => println!("{}", name),
+                            ast::MetaNameValue(ref name, ref value) => {
+                                if let ast::LitStr(ref value, _) = value.node {
+                                    println!("{}=\"{}\"", name, value);
+                                }
+                            }
+                            ast::MetaList(..) => {}
+                        }
+                    }
+                }
+                PrintRequest::NativeStaticLibs => {}
                 PrintRequest::FileNames |
                 PrintRequest::CrateName => {
                     let input = match input {
@@ -519,6 +581,33 @@ Additional help:
               extra_help);
 }
 
+/// Prints every error code as a markdown section, followed by its explanation.
+fn print_explanations_markdown(descriptions: &diagnostics::registry::Registry) {
+    for (code, description) in descriptions.all_descriptions() {
+        println!("## {}\n", code);
+        match description {
+            Some(description) => println!("{}\n", description.trim()),
+            None => println!("*No extended information available.*\n"),
+        }
+    }
+}
+
+/// Prints every error code as a JSON array of `{"code", "explanation"}`
+/// objects; the explanation is `null` for codes which have none.
+fn print_explanations_json(descriptions: &diagnostics::registry::Registry) {
+    use serialize::json::Json;
+    use std::collections::BTreeMap;
+
+    let codes = descriptions.all_descriptions().into_iter().map(|(code, description)| {
+        let mut obj = BTreeMap::new();
+        obj.insert("code".to_string(), code.to_json());
+        obj.insert("explanation".to_string(),
+                   description.map(|d| d.trim().to_string()).to_json());
+        Json::Object(obj)
+    }).collect();
+    println!("{}", json::as_pretty_json(&Json::Array(codes)));
+}
+
 fn describe_lints(lint_store: &lint::LintStore, loaded_plugins: bool) {
     println!("
 Available lint options:
@@ -666,6 +755,11 @@ pub fn handle_options(mut args: Vec<String>) -> Option<getopts::Matches> {
     // Throw away the first argument, the name of the binary
     let _binary = args.remove(0);
 
+    let args = match args::expand(args) {
+        Ok(args) => args,
+        Err(e) => early_error(&e),
+    };
+
     if args.is_empty() {
         // user did not write `-v` nor `-Z unstable-options`, so do not
         // include that extra information.
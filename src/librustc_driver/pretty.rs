@@ -20,11 +20,15 @@ use rustc_trans::back::link;
 use driver;
 
 use rustc::ast_map::{self, blocks, NodePrinter};
+use rustc::middle::infer;
+use rustc::middle::traits;
 use rustc::middle::ty;
 use rustc::middle::cfg;
 use rustc::middle::cfg::graphviz::LabelledCFG;
+use rustc::middle::pat_util;
 use rustc::session::Session;
 use rustc::session::config::Input;
+use rustc::util::nodemap::NodeSet;
 use rustc_borrowck as borrowck;
 use rustc_borrowck::graphviz as borrowck_dot;
 use rustc_resolve as resolve;
@@ -35,6 +39,7 @@ use syntax::fold::{self, Folder};
 use syntax::print::{pp, pprust};
 use syntax::ptr::P;
 use syntax::util::small_vector::SmallVector;
+use syntax::visit::{self, Visitor};
 
 use graphviz as dot;
 
@@ -50,6 +55,7 @@ pub enum PpSourceMode {
     PpmEveryBodyLoops,
     PpmExpanded,
     PpmTyped,
+    PpmTypedAnnotated,
     PpmIdentified,
     PpmExpandedIdentified,
     PpmExpandedHygiene,
@@ -82,6 +88,7 @@ pub fn parse_pretty(sess: &Session,
         ("everybody_loops", true) => PpmSource(PpmEveryBodyLoops),
         ("expanded", _)     => PpmSource(PpmExpanded),
         ("typed", _)        => PpmSource(PpmTyped),
+        ("typed,annotated", _) => PpmSource(PpmTypedAnnotated),
         ("expanded,identified", _) => PpmSource(PpmExpandedIdentified),
         ("expanded,hygiene", _) => PpmSource(PpmExpandedHygiene),
         ("identified", _)   => PpmSource(PpmIdentified),
@@ -91,12 +98,13 @@ pub fn parse_pretty(sess: &Session,
             if extended {
                 sess.fatal(&format!(
                     "argument to `xpretty` must be one of `normal`, \
-                     `expanded`, `flowgraph[,unlabelled]=<nodeid>`, `typed`, `identified`, \
-                     `expanded,identified`, or `everybody_loops`; got {}", name));
+                     `expanded`, `flowgraph[,unlabelled]=<nodeid>`, `typed`, \
+                     `typed,annotated`, `identified`, `expanded,identified`, \
+                     or `everybody_loops`; got {}", name));
             } else {
                 sess.fatal(&format!(
                     "argument to `pretty` must be one of `normal`, \
-                     `expanded`, `typed`, `identified`, \
+                     `expanded`, `typed`, `typed,annotated`, `identified`, \
                      or `expanded,identified`; got {}", name));
             }
         }
@@ -157,6 +165,18 @@ impl PpSourceMode {
                     f(&annotation, payload)
                 }).1
             }
+            PpmTypedAnnotated => {
+                let ast_map = ast_map.expect("--pretty=typed,annotated missing ast_map");
+                driver::phase_3_run_analysis_passes(sess,
+                                                    ast_map,
+                                                    arenas,
+                                                    id,
+                                                    resolve::MakeGlobMap::No,
+                                                    |tcx, _| {
+                    let annotation = TypedAnnotatedAnnotation::new(tcx);
+                    f(&annotation, payload)
+                }).1
+            }
         }
     }
 }
@@ -325,6 +345,161 @@ impl<'a, 'tcx> pprust::PpAnn for TypedAnnotation<'a, 'tcx> {
     }
 }
 
+/// Annotates bindings introduced by `let` and closure parameters with their
+/// inferred types, and method calls with the method they resolved to, but
+/// leaves every other expression alone. Bindings whose type was written out
+/// are not annotated.
+struct TypedAnnotatedAnnotation<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    inferred_bindings: NodeSet,
+}
+
+impl<'a, 'tcx> TypedAnnotatedAnnotation<'a, 'tcx> {
+    fn new(tcx: &'a ty::ctxt<'tcx>) -> TypedAnnotatedAnnotation<'a, 'tcx> {
+        let mut visitor = InferredBindingsVisitor {
+            tcx: tcx,
+            bindings: NodeSet(),
+        };
+        visit::walk_crate(&mut visitor, tcx.map.krate());
+        TypedAnnotatedAnnotation {
+            tcx: tcx,
+            inferred_bindings: visitor.bindings,
+        }
+    }
+
+    // `path (krate:node): type` for the method called by `expr`. Methods
+    // called through a trait are shown as `<Self as Trait>::method`, with the
+    // id of the impl's method when the impl is known.
+    fn method_string(&self, expr: &ast::Expr) -> Option<String> {
+        let method_call = ty::MethodCall::expr(expr.id);
+        // Selecting the impl may need the tables, so they aren't kept borrowed.
+        let callee = match self.tcx.tables.borrow().method_map.get(&method_call) {
+            Some(&callee) => callee,
+            None => return None,
+        };
+        let item = self.tcx.impl_or_trait_item(callee.def_id);
+        let (path, def_id) = match (item.container(), callee.substs.self_ty()) {
+            (ty::TraitContainer(trait_id), Some(self_ty)) => {
+                (format!("<{} as {}>::{}",
+                         self_ty,
+                         self.tcx.item_path_str(trait_id),
+                         item.name()),
+                 self.impl_method(expr.id, trait_id, &callee))
+            }
+            _ => (self.tcx.item_path_str(callee.def_id), callee.def_id),
+        };
+        Some(format!("{} ({}:{}): {}",
+                     path,
+                     def_id.krate,
+                     def_id.node,
+                     callee.ty))
+    }
+
+    // The method a call through `trait_id` ends up in: the impl's own method
+    // if the impl can be selected from the receiver's substs and defines it,
+    // and the trait's method otherwise (a provided method, or a call on a
+    // type parameter).
+    fn impl_method(&self,
+                   id: ast::NodeId,
+                   trait_id: ast::DefId,
+                   callee: &ty::MethodCallee<'tcx>)
+                   -> ast::DefId {
+        let trait_substs = self.tcx.mk_substs(callee.substs.clone().method_to_trait());
+        let trait_ref = ty::Binder(ty::TraitRef::new(trait_id, trait_substs));
+        let obligation =
+            traits::Obligation::new(traits::ObligationCause::misc(self.tcx.map.span(id), id),
+                                    trait_ref.to_poly_trait_predicate());
+
+        let param_env = ty::ParameterEnvironment::for_item(self.tcx, id);
+        let infcx = infer::new_infer_ctxt(self.tcx, &self.tcx.tables, Some(param_env), false);
+        let mut selcx = traits::SelectionContext::new(&infcx);
+        let impl_def_id = match selcx.select(&obligation) {
+            Ok(Some(traits::VtableImpl(vtable_impl))) => vtable_impl.impl_def_id,
+            _ => return callee.def_id,
+        };
+
+        let name = self.tcx.impl_or_trait_item(callee.def_id).name();
+        let impl_items = self.tcx.impl_items.borrow();
+        impl_items.get(&impl_def_id).and_then(|items| {
+            items.iter()
+                 .map(|item| item.def_id())
+                 .find(|&def_id| self.tcx.impl_or_trait_item(def_id).name() == name)
+        }).unwrap_or(callee.def_id)
+    }
+}
+
+struct InferredBindingsVisitor<'a, 'tcx: 'a> {
+    tcx: &'a ty::ctxt<'tcx>,
+    bindings: NodeSet,
+}
+
+impl<'a, 'tcx> InferredBindingsVisitor<'a, 'tcx> {
+    fn add_bindings(&mut self, pat: &ast::Pat) {
+        let bindings = &mut self.bindings;
+        pat_util::pat_bindings(&self.tcx.def_map, pat, |_, id, _, _| {
+            bindings.insert(id);
+        });
+    }
+}
+
+impl<'a, 'tcx, 'v> Visitor<'v> for InferredBindingsVisitor<'a, 'tcx> {
+    fn visit_local(&mut self, local: &'v ast::Local) {
+        if local.ty.is_none() {
+            self.add_bindings(&local.pat);
+        }
+        visit::walk_local(self, local)
+    }
+
+    fn visit_expr(&mut self, expr: &'v ast::Expr) {
+        if let ast::ExprClosure(_, ref decl, _) = expr.node {
+            for arg in &decl.inputs {
+                if let ast::TyInfer = arg.ty.node {
+                    self.add_bindings(&arg.pat);
+                }
+            }
+        }
+        visit::walk_expr(self, expr)
+    }
+}
+
+impl<'b, 'tcx> PrinterSupport<'tcx> for TypedAnnotatedAnnotation<'b, 'tcx> {
+    fn sess<'a>(&'a self) -> &'a Session { &self.tcx.sess }
+
+    fn ast_map<'a>(&'a self) -> Option<&'a ast_map::Map<'tcx>> {
+        Some(&self.tcx.map)
+    }
+
+    fn pp_ann<'a>(&'a self) -> &'a pprust::PpAnn { self }
+}
+
+impl<'a, 'tcx> pprust::PpAnn for TypedAnnotatedAnnotation<'a, 'tcx> {
+    fn post(&self,
+            s: &mut pprust::State,
+            node: pprust::AnnNode) -> io::Result<()> {
+        match node {
+            pprust::NodePat(pat) if self.inferred_bindings.contains(&pat.id) => {
+                match self.tcx.node_id_to_type_opt(pat.id) {
+                    Some(ty) => {
+                        try!(pp::space(&mut s.s));
+                        s.synth_comment(ty.to_string())
+                    }
+                    None => Ok(())
+                }
+            }
+            pprust::NodeExpr(expr) => {
+                match self.method_string(expr) {
+                    Some(method) => {
+                        try!(pp::space(&mut s.s));
+                        s.synth_comment(method)
+                    }
+                    None => Ok(())
+                }
+            }
+            _ => Ok(())
+        }
+    }
+}
+
 fn gather_flowgraph_variants(sess: &Session) -> Vec<borrowck_dot::Variant> {
     let print_loans = sess.opts.debugging_opts.flowgraph_print_loans;
     let print_moves = sess.opts.debugging_opts.flowgraph_print_moves;
@@ -431,6 +606,7 @@ fn needs_ast_map(ppm: &PpMode, opt_uii: &Option<UserIdentifiedItem>) -> bool {
         PpmSource(PpmExpandedIdentified) |
         PpmSource(PpmExpandedHygiene) |
         PpmSource(PpmTyped) |
+        PpmSource(PpmTypedAnnotated) |
         PpmFlowGraph(_) => true
     }
 }
@@ -445,6 +621,7 @@ fn needs_expansion(ppm: &PpMode) -> bool {
         PpmSource(PpmExpandedIdentified) |
         PpmSource(PpmExpandedHygiene) |
         PpmSource(PpmTyped) |
+        PpmSource(PpmTypedAnnotated) |
         PpmFlowGraph(_) => true
     }
 }
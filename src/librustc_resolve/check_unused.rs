@@ -26,7 +26,8 @@ use rustc::lint;
 use rustc::middle::privacy::{DependsOn, LastImport, Used, Unused};
 use syntax::ast;
 use syntax::ast::{ViewPathGlob, ViewPathList, ViewPathSimple};
-use syntax::codemap::{Span, DUMMY_SP};
+use syntax::codemap::{BytePos, Pos, Span, DUMMY_SP};
+use syntax::diagnostic::{Applicability, CodeSuggestion};
 use syntax::visit::{self, Visitor};
 
 struct UnusedImportCheckVisitor<'a, 'b:'a, 'tcx:'b> {
@@ -56,16 +57,22 @@ impl<'a, 'b, 'tcx> UnusedImportCheckVisitor<'a, 'b, 'tcx> {
     // which are used. In particular, this means that if an import could name either a
     // public or private item, we will check the correct thing, dependent on how the import
     // is used.
-    fn finalize_import(&mut self, id: ast::NodeId, span: Span) {
+    //
+    // `removal`, if given, is a suggestion that removes the unused import.
+    fn finalize_import(&mut self, id: ast::NodeId, span: Span,
+                       removal: Option<CodeSuggestion>) {
         debug!("finalizing import uses for {:?}",
                 self.session.codemap().span_to_snippet(span));
 
-        if !self.used_imports.contains(&(id, TypeNS)) &&
-           !self.used_imports.contains(&(id, ValueNS)) {
-            self.session.add_lint(lint::builtin::UNUSED_IMPORTS,
-                                  id,
-                                  span,
-                                  "unused import".to_string());
+        if !self.is_import_used(id) {
+            let msg = "unused import".to_string();
+            match removal {
+                Some(removal) => {
+                    self.session.add_lint_with_suggestion(lint::builtin::UNUSED_IMPORTS,
+                                                          id, span, msg, removal)
+                }
+                None => self.session.add_lint(lint::builtin::UNUSED_IMPORTS, id, span, msg),
+            }
         }
 
         let mut def_map = self.def_map.borrow_mut();
@@ -107,6 +114,42 @@ impl<'a, 'b, 'tcx> UnusedImportCheckVisitor<'a, 'b, 'tcx> {
             type_used: t_used
         };
     }
+
+    fn is_import_used(&self, id: ast::NodeId) -> bool {
+        self.used_imports.contains(&(id, TypeNS)) ||
+        self.used_imports.contains(&(id, ValueNS))
+    }
+
+    // A suggestion removing `item`. If nothing else is on its lines, the
+    // indentation before it and the line break after it go too, so that no
+    // blank line is left behind.
+    fn remove_item(&self, item: &ast::Item) -> CodeSuggestion {
+        let cm = self.session.codemap();
+        let mut span = item.span;
+        let lo = cm.lookup_byte_offset(span.lo);
+        if let Some(ref src) = lo.fm.src {
+            let (lo, hi) = (lo.pos.to_usize(), cm.lookup_byte_offset(span.hi).pos.to_usize());
+            let line_start = src[..lo].rfind('\n').map_or(0, |i| i + 1);
+            let indented = src[line_start..lo].chars().all(|c| c == ' ' || c == '\t');
+            let newline = if src[hi..].starts_with("\r\n") {
+                2
+            } else if src[hi..].starts_with("\n") {
+                1
+            } else {
+                0
+            };
+            if indented && newline > 0 {
+                span.lo = span.lo - BytePos((lo - line_start) as u32);
+                span.hi = span.hi + BytePos(newline);
+            }
+        }
+        CodeSuggestion {
+            msg: "remove the unused import".to_string(),
+            span: span,
+            replacement: String::new(),
+            applicability: Applicability::MachineApplicable,
+        }
+    }
 }
 
 impl<'a, 'b, 'v, 'tcx> Visitor<'v> for UnusedImportCheckVisitor<'a, 'b, 'tcx> {
@@ -134,22 +177,31 @@ impl<'a, 'b, 'v, 'tcx> Visitor<'v> for UnusedImportCheckVisitor<'a, 'b, 'tcx> {
             ast::ItemUse(ref p) => {
                 match p.node {
                     ViewPathSimple(_, _) => {
-                        self.finalize_import(item.id, p.span)
+                        self.finalize_import(item.id, p.span, Some(self.remove_item(item)))
                     }
 
                     ViewPathList(_, ref list) => {
+                        // Removing single names from the list is left to the
+                        // user; only suggest a fix when none of them is used,
+                        // and only once for the whole item.
+                        let all_unused = list.iter().all(|i| !self.is_import_used(i.node.id()));
+                        let mut removal = if all_unused {
+                            Some(self.remove_item(item))
+                        } else {
+                            None
+                        };
                         for i in list {
-                            self.finalize_import(i.node.id(), i.span);
+                            self.finalize_import(i.node.id(), i.span, removal.take());
                         }
                     }
                     ViewPathGlob(_) => {
-                        if !self.used_imports.contains(&(item.id, TypeNS)) &&
-                           !self.used_imports.contains(&(item.id, ValueNS)) {
+                        if !self.is_import_used(item.id) {
                             self.session
-                                .add_lint(lint::builtin::UNUSED_IMPORTS,
-                                          item.id,
-                                          p.span,
-                                          "unused import".to_string());
+                                .add_lint_with_suggestion(lint::builtin::UNUSED_IMPORTS,
+                                                          item.id,
+                                                          p.span,
+                                                          "unused import".to_string(),
+                                                          self.remove_item(item));
                         }
                     }
                 }
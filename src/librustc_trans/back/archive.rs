@@ -216,6 +216,11 @@ impl<'a> ArchiveBuilder<'a> {
         if let Err(e) = res {
             self.config.sess.fatal(&format!("failed to build archive: {}", e));
         }
+        if self.config.sess.opts.debugging_opts.deterministic {
+            if let Err(e) = clear_member_metadata(&self.config.dst) {
+                self.config.sess.fatal(&format!("failed to build archive: {}", e));
+            }
+        }
     }
 
     pub fn using_llvm(&self) -> bool {
@@ -502,3 +507,60 @@ impl<'a> ArchiveBuilder<'a> {
         }
     }
 }
+
+/// Rewrites the header of each member of the archive at `path` to have a
+/// modification time, owner and group of zero and a mode of 644, as `ar D`
+/// does. Both `ar` and LLVM otherwise copy these from the files added.
+///
+/// The BSD symbol table is left alone, as the linker on OSX warns that it is
+/// out of date if it is older than the archive's other members.
+fn clear_member_metadata(path: &Path) -> io::Result<()> {
+    const MAGIC: &'static [u8] = b"!<arch>\n";
+    const HEADER_LEN: usize = 60;
+
+    let mut data = Vec::new();
+    try!(try!(File::open(path)).read_to_end(&mut data));
+    if !data.starts_with(MAGIC) {
+        return Err(io::Error::new(io::ErrorKind::Other, "not an archive"));
+    }
+
+    let mut pos = MAGIC.len();
+    while pos + HEADER_LEN <= data.len() {
+        let size = str::from_utf8(&data[pos + 48..pos + 58]).ok()
+                       .and_then(|s| s.trim().parse::<usize>().ok());
+        let size = match size {
+            Some(size) => size,
+            None => return Err(io::Error::new(io::ErrorKind::Other,
+                                              "malformed archive member header")),
+        };
+
+        // Long BSD names are stored at the start of the member's data.
+        let symdef = {
+            let name = &data[pos..pos + 16];
+            name.starts_with(b"__.SYMDEF") ||
+                (name.starts_with(b"#1/") &&
+                 data[pos + HEADER_LEN..].starts_with(b"__.SYMDEF"))
+        };
+        if !symdef {
+            set_header_field(&mut data[pos + 16..pos + 28], "0");   // mtime
+            set_header_field(&mut data[pos + 28..pos + 34], "0");   // uid
+            set_header_field(&mut data[pos + 34..pos + 40], "0");   // gid
+            set_header_field(&mut data[pos + 40..pos + 48], "644"); // mode
+        }
+
+        // Members are aligned to two bytes.
+        pos += HEADER_LEN + size + size % 2;
+    }
+
+    try!(File::create(path)).write_all(&data)
+}
+
+// Fields left blank, such as in GNU string tables, stay blank.
+fn set_header_field(field: &mut [u8], value: &str) {
+    if field.iter().all(|&b| b == b' ') {
+        return
+    }
+    for (i, b) in field.iter_mut().enumerate() {
+        *b = value.as_bytes().get(i).cloned().unwrap_or(b' ');
+    }
+}
@@ -405,6 +405,10 @@ pub fn link_binary(sess: &Session,
                    trans: &CrateTranslation,
                    outputs: &OutputFilenames,
                    crate_name: &str) -> Vec<PathBuf> {
+    if sess.crate_types.borrow().iter().any(|&t| t != config::CrateTypeRlib) {
+        check_no_metadata_only_deps(sess);
+    }
+
     let mut out_filenames = Vec::new();
     for &crate_type in sess.crate_types.borrow().iter() {
         if invalid_output_for_target(sess, crate_type) {
@@ -427,6 +431,40 @@ pub fn link_binary(sess: &Session,
     out_filenames
 }
 
+// Crates built with `--emit=metadata` have no code, so anything that links
+// them would fail with undefined symbols.
+fn check_no_metadata_only_deps(sess: &Session) {
+    for (cnum, path) in sess.cstore.get_used_crates(cstore::RequireStatic) {
+        let metadata_only = path.as_ref().map_or(false, |p| {
+            p.extension().and_then(|ext| ext.to_str()) == Some("rmeta")
+        });
+        if metadata_only {
+            let name = sess.cstore.get_crate_data(cnum).name.clone();
+            sess.fatal(&format!("crate `{}` was only compiled with --emit=metadata, \
+                                 so it cannot be linked", name));
+        }
+    }
+}
+
+/// Writes the crate metadata on its own for `--emit=metadata`. The file holds
+/// the same compressed metadata as a dylib, after the encoding version, and
+/// is read by `metadata::loader` in place of an rlib.
+pub fn write_metadata_file(sess: &Session,
+                           metadata: &[u8],
+                           outputs: &OutputFilenames) {
+    // Metadata is only encoded for library crate types.
+    if metadata.is_empty() {
+        sess.warn("ignoring --emit=metadata because no library crate type was requested");
+        return;
+    }
+
+    let path = outputs.path(config::OutputTypeMetadata);
+    let mut data = encoder::metadata_encoding_version.to_vec();
+    data.push_all(&flate::deflate_bytes(metadata));
+    if let Err(e) = fs::File::create(&path).and_then(|mut f| f.write_all(&data)) {
+        sess.err(&format!("failed to write {}: {}", path.display(), e));
+    }
+}
 
 /// Returns default crate type for target
 ///
@@ -787,6 +825,11 @@ fn link_staticlib(sess: &Session, objects: &[PathBuf], out_filename: &Path,
     ab.update_symbols();
     ab.build();
 
+    if sess.opts.prints.contains(&config::PrintRequest::NativeStaticLibs) {
+        print_native_static_libs(sess, &all_native_libs);
+        return
+    }
+
     if !all_native_libs.is_empty() {
         sess.note("link against the following native artifacts when linking against \
                   this static library");
@@ -804,6 +847,27 @@ fn link_staticlib(sess: &Session, objects: &[PathBuf], out_filename: &Path,
     }
 }
 
+// Prints the native libraries as a single line of linker arguments, for
+// `--print native-static-libs`.
+fn print_native_static_libs(sess: &Session,
+                            all_native_libs: &[(cstore::NativeLibraryKind, String)]) {
+    let msvc = sess.target.target.options.is_like_msvc;
+    let args = all_native_libs.iter().map(|&(kind, ref lib)| {
+        match kind {
+            cstore::NativeFramework => format!("-framework {}", lib),
+            cstore::NativeStatic |
+            cstore::NativeUnknown if msvc => format!("{}.lib", lib),
+            cstore::NativeStatic |
+            cstore::NativeUnknown => format!("-l{}", lib),
+        }
+    }).collect::<Vec<_>>();
+    if args.is_empty() {
+        sess.note("native-static-libs: no native libraries are needed");
+    } else {
+        sess.note(&format!("native-static-libs: {}", args.join(" ")));
+    }
+}
+
 // Create a dynamic library or executable
 //
 // This will invoke the system linker/cc to create the resulting file. This
@@ -19,6 +19,7 @@ use llvm::SMDiagnosticRef;
 use trans::{CrateTranslation, ModuleTranslation};
 use util::common::time;
 use util::common::path2cstr;
+use util::profiling;
 use syntax::codemap;
 use syntax::diagnostic;
 use syntax::diagnostic::{Emitter, Handler, Level};
@@ -642,7 +643,8 @@ pub fn run_passes(sess: &Session,
                 modules_config.emit_obj = true;
                 metadata_config.emit_obj = true;
             },
-            config::OutputTypeDepInfo => {}
+            config::OutputTypeDepInfo |
+            config::OutputTypeMetadata => {}
         }
     }
 
@@ -742,7 +744,8 @@ pub fn run_passes(sess: &Session,
                 copy_if_one_unit("0.o", config::OutputTypeObject, true);
             }
             config::OutputTypeExe |
-            config::OutputTypeDepInfo => {}
+            config::OutputTypeDepInfo |
+            config::OutputTypeMetadata => {}
         }
     }
     let user_wants_bitcode = user_wants_bitcode;
@@ -833,10 +836,11 @@ fn build_work_item(sess: &Session,
 
 fn execute_work_item(cgcx: &CodegenContext,
                      work_item: WorkItem) {
-    unsafe {
+    let what = format!("codegen unit {}", work_item.name_extra);
+    time(work_item.config.time_passes, &what, work_item, |work_item| unsafe {
         optimize_and_codegen(cgcx, work_item.mtrans, work_item.config,
                              work_item.name_extra, work_item.output_names);
-    }
+    })
 }
 
 fn run_work_singlethreaded(sess: &Session,
@@ -864,6 +868,7 @@ fn run_work_multithreaded(sess: &Session,
         let diag_emitter = diag_emitter.clone();
         let plugin_passes = sess.plugin_llvm_passes.borrow().clone();
         let remark = sess.opts.cg.remark.clone();
+        let profile_start = if profiling::is_enabled() { Some(profiling::origin()) } else { None };
 
         let (tx, rx) = channel();
         let mut tx = Some(tx);
@@ -871,6 +876,9 @@ fn run_work_multithreaded(sess: &Session,
 
         thread::Builder::new().name(format!("codegen-{}", i)).spawn(move || {
             let diag_handler = Handler::with_emitter(true, box diag_emitter);
+            if let Some(start) = profile_start {
+                profiling::start_at(start);
+            }
 
             // Must construct cgcx inside the proc because it has non-Send
             // fields.
@@ -896,14 +904,14 @@ fn run_work_multithreaded(sess: &Session,
                 }
             }
 
-            tx.take().unwrap().send(()).unwrap();
+            tx.take().unwrap().send(profiling::finish()).unwrap();
         }).unwrap();
     }
 
     let mut panicked = false;
-    for rx in futures {
+    for (i, rx) in futures.into_iter().enumerate() {
         match rx.recv() {
-            Ok(()) => {},
+            Ok(events) => profiling::add_events(i + 1, events),
             Err(_) => {
                 panicked = true;
             },
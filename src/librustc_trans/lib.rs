@@ -55,6 +55,7 @@ extern crate rustc;
 extern crate rustc_back;
 extern crate rustc_llvm as llvm;
 extern crate serialize;
+extern crate serialize as rustc_serialize; // used by deriving
 
 #[macro_use] extern crate log;
 #[macro_use] extern crate syntax;
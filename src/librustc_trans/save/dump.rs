@@ -0,0 +1,235 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The interface between the AST walk in `dump_csv` and the output formats.
+//!
+//! `FmtStrs` implements it by writing CSV rows as it goes, `JsonDumper` by
+//! building the typed model in `json_dumper` and serialising it at the end.
+
+use super::recorder::Row;
+
+use middle::ty::Ty;
+
+use syntax::ast::{self, NodeId, DefId};
+use syntax::codemap::Span;
+
+pub trait Dump {
+    fn variable_str(&mut self,
+                    span: Span,
+                    sub_span: Option<Span>,
+                    id: NodeId,
+                    name: &str,
+                    value: &str,
+                    typ: &str);
+
+    fn formal_str(&mut self,
+                  span: Span,
+                  sub_span: Option<Span>,
+                  id: NodeId,
+                  fn_name: &str,
+                  name: &str,
+                  typ: &str);
+
+    fn static_str(&mut self,
+                  span: Span,
+                  sub_span: Option<Span>,
+                  id: NodeId,
+                  name: &str,
+                  qualname: &str,
+                  value: &str,
+                  typ: &str,
+                  scope_id: NodeId);
+
+    fn field_str(&mut self,
+                 span: Span,
+                 sub_span: Option<Span>,
+                 id: NodeId,
+                 name: &str,
+                 qualname: &str,
+                 typ: &str,
+                 scope_id: NodeId);
+
+    fn enum_str(&mut self,
+                span: Span,
+                sub_span: Option<Span>,
+                id: NodeId,
+                name: &str,
+                scope_id: NodeId,
+                value: &str);
+
+    fn tuple_variant_str(&mut self,
+                         span: Span,
+                         sub_span: Option<Span>,
+                         id: NodeId,
+                         name: &str,
+                         qualname: &str,
+                         typ: &str,
+                         val: &str,
+                         scope_id: NodeId);
+
+    fn struct_variant_str(&mut self,
+                          span: Span,
+                          sub_span: Option<Span>,
+                          id: NodeId,
+                          ctor_id: NodeId,
+                          name: &str,
+                          typ: &str,
+                          val: &str,
+                          scope_id: NodeId);
+
+    fn fn_str(&mut self,
+              span: Span,
+              sub_span: Option<Span>,
+              id: NodeId,
+              name: &str,
+              scope_id: NodeId);
+
+    fn method_str(&mut self,
+                  span: Span,
+                  sub_span: Option<Span>,
+                  id: NodeId,
+                  name: &str,
+                  decl_id: Option<DefId>,
+                  scope_id: NodeId);
+
+    fn method_decl_str(&mut self,
+                       span: Span,
+                       sub_span: Option<Span>,
+                       id: NodeId,
+                       name: &str,
+                       scope_id: NodeId);
+
+    fn struct_str(&mut self,
+                  span: Span,
+                  sub_span: Option<Span>,
+                  id: NodeId,
+                  ctor_id: NodeId,
+                  name: &str,
+                  scope_id: NodeId,
+                  value: &str);
+
+    fn trait_str(&mut self,
+                 span: Span,
+                 sub_span: Option<Span>,
+                 id: NodeId,
+                 name: &str,
+                 scope_id: NodeId,
+                 value: &str);
+
+    fn impl_str(&mut self,
+                span: Span,
+                sub_span: Option<Span>,
+                id: NodeId,
+                ref_id: Option<DefId>,
+                trait_id: Option<DefId>,
+                scope_id: NodeId);
+
+    fn mod_str(&mut self,
+               span: Span,
+               sub_span: Option<Span>,
+               id: NodeId,
+               name: &str,
+               parent: NodeId,
+               filename: &str);
+
+    fn use_alias_str(&mut self,
+                     span: Span,
+                     sub_span: Option<Span>,
+                     id: NodeId,
+                     mod_id: Option<DefId>,
+                     name: &str,
+                     parent: NodeId);
+
+    fn use_glob_str(&mut self,
+                    span: Span,
+                    sub_span: Option<Span>,
+                    id: NodeId,
+                    values: &str,
+                    parent: NodeId);
+
+    fn extern_crate_str(&mut self,
+                        span: Span,
+                        sub_span: Option<Span>,
+                        id: NodeId,
+                        cnum: ast::CrateNum,
+                        name: &str,
+                        loc: &str,
+                        parent: NodeId);
+
+    fn inherit_str(&mut self,
+                   span: Span,
+                   sub_span: Option<Span>,
+                   base_id: DefId,
+                   deriv_id: NodeId);
+
+    fn fn_call_str(&mut self,
+                   span: Span,
+                   sub_span: Option<Span>,
+                   id: DefId,
+                   scope_id:NodeId);
+
+    fn meth_call_str(&mut self,
+                     span: Span,
+                     sub_span: Option<Span>,
+                     defid: Option<DefId>,
+                     declid: Option<DefId>,
+                     scope_id: NodeId);
+
+    fn sub_mod_ref_str(&mut self,
+                       span: Span,
+                       sub_span: Span,
+                       qualname: &str,
+                       parent:NodeId);
+
+    fn typedef_str(&mut self,
+                   span: Span,
+                   sub_span: Option<Span>,
+                   id: NodeId,
+                   qualname: &str,
+                   value: &str);
+
+    fn crate_str(&mut self,
+                 span: Span,
+                 name: &str);
+
+    fn external_crate_str(&mut self,
+                          span: Span,
+                          name: &str,
+                          num: ast::CrateNum);
+
+    fn sub_type_ref_str(&mut self,
+                        span: Span,
+                        sub_span: Span,
+                        qualname: &str);
+
+    // A reference to an item of any kind.
+    fn ref_str(&mut self,
+               kind: Row,
+               span: Span,
+               sub_span: Option<Span>,
+               id: DefId,
+               scope_id: NodeId);
+
+    fn macro_use_str(&mut self,
+                     span: Span,
+                     sub_span: Span,
+                     name: &str,
+                     qualname: &str,
+                     scope_id: NodeId);
+
+    /// The type of an expression. Only in-memory analysis keeps these.
+    fn expr_type_str(&mut self, _span: Span, _typ: Ty) {}
+
+    /// Called once all external crates have been dumped.
+    fn end_external_crates(&mut self);
+
+    /// Called once the whole crate has been dumped.
+    fn finish(&mut self) {}
+}
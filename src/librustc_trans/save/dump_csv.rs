@@ -25,7 +25,8 @@
 //! from spans (e.g., the span for `bar` from the above example path).
 //! Recorder is used for recording the output in csv format. FmtStrs separates
 //! the format of the output away from extracting it from the compiler.
-//! DumpCsvVisitor walks the AST and processes it.
+//! JsonDumper is the alternative to both for JSON and in-memory output; either
+//! is used through the Dump trait. DumpCsvVisitor walks the AST and processes it.
 
 
 use super::{escape, generated_code, recorder, SaveContext, PathCollector, Data};
@@ -36,7 +37,7 @@ use middle::def;
 use middle::ty::{self, Ty};
 
 use std::cell::Cell;
-use std::fs::File;
+use std::collections::HashSet;
 use std::path::Path;
 
 use syntax::ast::{self, NodeId, DefId};
@@ -47,8 +48,8 @@ use syntax::visit::{self, Visitor};
 use syntax::print::pprust::{path_to_string, ty_to_string};
 use syntax::ptr::P;
 
+use super::dump::Dump;
 use super::span_utils::SpanUtils;
-use super::recorder::{Recorder, FmtStrs};
 
 macro_rules! down_cast_data {
     ($id:ident, $kind:ident, $this:ident, $sp:expr) => {
@@ -67,15 +68,18 @@ pub struct DumpCsvVisitor<'l, 'tcx: 'l> {
     analysis: &'l ty::CrateAnalysis,
 
     span: SpanUtils<'l>,
-    fmt: FmtStrs<'l>,
+    fmt: Box<Dump + 'l>,
 
-    cur_scope: NodeId
+    cur_scope: NodeId,
+
+    // The call sites of the macro invocations recorded so far.
+    macro_calls: HashSet<(BytePos, BytePos)>,
 }
 
 impl <'l, 'tcx> DumpCsvVisitor<'l, 'tcx> {
     pub fn new(tcx: &'l ty::ctxt<'tcx>,
                analysis: &'l ty::CrateAnalysis,
-               output_file: Box<File>) -> DumpCsvVisitor<'l, 'tcx> {
+               fmt: Box<Dump + 'l>) -> DumpCsvVisitor<'l, 'tcx> {
         let span_utils = SpanUtils {
             sess: &tcx.sess,
             err_count: Cell::new(0)
@@ -85,15 +89,17 @@ impl <'l, 'tcx> DumpCsvVisitor<'l, 'tcx> {
             tcx: tcx,
             save_ctxt: SaveContext::new(tcx, span_utils.clone()),
             analysis: analysis,
-            span: span_utils.clone(),
-            fmt: FmtStrs::new(box Recorder {
-                                out: output_file,
-                                dump_spans: false,
-                              }, span_utils),
-            cur_scope: 0
+            span: span_utils,
+            fmt: fmt,
+            cur_scope: 0,
+            macro_calls: HashSet::new(),
         }
     }
 
+    pub fn finish(&mut self) {
+        self.fmt.finish();
+    }
+
     fn nest<F>(&mut self, scope_id: NodeId, f: F) where
         F: FnOnce(&mut DumpCsvVisitor<'l, 'tcx>),
     {
@@ -111,7 +117,39 @@ impl <'l, 'tcx> DumpCsvVisitor<'l, 'tcx> {
         for c in &self.save_ctxt.get_external_crates() {
             self.fmt.external_crate_str(krate.span, &c.name, c.number);
         }
-        self.fmt.recorder.record("end_external_crates\n");
+        self.fmt.end_external_crates();
+    }
+
+    // Record the macro invocation that `span` was expanded from, once per
+    // invocation. Nested expansions are attributed to the outermost macro,
+    // since that is the only one written in the source.
+    fn process_macro_use(&mut self, span: Span) {
+        let mut outermost = None;
+        let mut expn_id = span.expn_id;
+        loop {
+            let info = self.sess.codemap().with_expn_info(expn_id, |info| {
+                info.map(|info| (info.call_site, info.callee.name.clone(), info.callee.format))
+            });
+            match info {
+                Some((call_site, name, format)) => {
+                    expn_id = call_site.expn_id;
+                    outermost = Some((call_site, name, format));
+                }
+                None => break,
+            }
+        }
+
+        let (call_site, name, format) = match outermost {
+            Some(outermost) => outermost,
+            None => return,
+        };
+        // Attributes such as `#[derive]` and desugarings are not macro calls.
+        if format != MacroBang || !self.macro_calls.insert((call_site.lo, call_site.hi)) {
+            return;
+        }
+        let sub_span = self.span.span_for_first_ident(call_site).unwrap_or(call_site);
+        let qualname = format!("{}!", name);
+        self.fmt.macro_use_str(call_site, sub_span, &name, &qualname, self.cur_scope);
     }
 
     // Return all non-empty prefixes of a path.
@@ -846,6 +884,7 @@ impl <'l, 'tcx> DumpCsvVisitor<'l, 'tcx> {
 impl<'l, 'tcx, 'v> Visitor<'v> for DumpCsvVisitor<'l, 'tcx> {
     fn visit_item(&mut self, item: &ast::Item) {
         if generated_code(item.span) {
+            self.process_macro_use(item.span);
             return
         }
 
@@ -1075,9 +1114,14 @@ impl<'l, 'tcx, 'v> Visitor<'v> for DumpCsvVisitor<'l, 'tcx> {
 
     fn visit_expr(&mut self, ex: &ast::Expr) {
         if generated_code(ex.span) {
+            self.process_macro_use(ex.span);
             return
         }
 
+        if let Some(ty) = self.tcx.expr_ty_opt(ex) {
+            self.fmt.expr_type_str(ex.span, ty);
+        }
+
         match ex.node {
             ast::ExprCall(ref _f, ref _args) => {
                 // Don't need to do anything for function calls,
@@ -1228,6 +1272,7 @@ impl<'l, 'tcx, 'v> Visitor<'v> for DumpCsvVisitor<'l, 'tcx> {
 
     fn visit_stmt(&mut self, s: &ast::Stmt) {
         if generated_code(s.span) {
+            self.process_macro_use(s.span);
             return
         }
 
@@ -0,0 +1,119 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Queries over save-analysis data held in memory, as returned by
+//! `save::analyze_crate`.
+//!
+//! Positions are given as a file name (as it appears in the codemap), a
+//! line and a column, both starting at 1 with columns counting characters,
+//! the same as the spans in `json_dumper`.
+
+use super::json_dumper::{Analysis, Def, Ref, SpanData, Id};
+
+use std::collections::HashMap;
+
+pub struct AnalysisIndex {
+    analysis: Analysis,
+    // Indices into `analysis.defs` and `analysis.refs`.
+    defs_by_id: HashMap<Id, usize>,
+    refs_by_id: HashMap<Id, Vec<usize>>,
+}
+
+impl AnalysisIndex {
+    pub fn new(analysis: Analysis) -> AnalysisIndex {
+        let mut defs_by_id = HashMap::new();
+        for (i, def) in analysis.defs.iter().enumerate() {
+            defs_by_id.insert(def.id, i);
+        }
+        let mut refs_by_id = HashMap::new();
+        for (i, r) in analysis.refs.iter().enumerate() {
+            if let Some(id) = r.ref_id {
+                refs_by_id.entry(id).or_insert(vec![]).push(i);
+            }
+        }
+
+        AnalysisIndex {
+            analysis: analysis,
+            defs_by_id: defs_by_id,
+            refs_by_id: refs_by_id,
+        }
+    }
+
+    /// All of the data, as it would have been written out.
+    pub fn analysis(&self) -> &Analysis {
+        &self.analysis
+    }
+
+    /// The definition with the given id, if it is in the analysed crate.
+    pub fn def(&self, id: Id) -> Option<&Def> {
+        self.defs_by_id.get(&id).map(|&i| &self.analysis.defs[i])
+    }
+
+    /// The item named at the given position, whether that is its
+    /// definition or a reference to it. Items from other crates have an
+    /// id but no `def`.
+    pub fn id_at(&self, file_name: &str, line: usize, column: usize) -> Option<Id> {
+        let def = innermost(self.analysis.defs.iter(),
+                            |d| &d.span,
+                            file_name, line, column);
+        if let Some(def) = def {
+            return Some(def.id);
+        }
+        innermost(self.analysis.refs.iter().filter(|r| r.ref_id.is_some()),
+                  |r| &r.span,
+                  file_name, line, column).and_then(|r| r.ref_id)
+    }
+
+    /// The definition of the item named at the given position.
+    pub fn def_at(&self, file_name: &str, line: usize, column: usize) -> Option<&Def> {
+        self.id_at(file_name, line, column).and_then(|id| self.def(id))
+    }
+
+    /// All references to the given item, in the order they occur in the
+    /// crate.
+    pub fn refs_to(&self, id: Id) -> Vec<&Ref> {
+        match self.refs_by_id.get(&id) {
+            Some(refs) => refs.iter().map(|&i| &self.analysis.refs[i]).collect(),
+            None => vec![],
+        }
+    }
+
+    /// The type of the innermost expression covering the given position.
+    pub fn type_at(&self, file_name: &str, line: usize, column: usize) -> Option<&str> {
+        innermost(self.analysis.expr_types.iter(),
+                  |e| &e.span,
+                  file_name, line, column).map(|e| &e.type_value[..])
+    }
+}
+
+// The element with the smallest span covering the position.
+fn innermost<'a, T, I, F>(items: I,
+                          span_of: F,
+                          file_name: &str,
+                          line: usize,
+                          column: usize)
+                          -> Option<&'a T> where
+    I: Iterator<Item=&'a T>,
+    F: Fn(&T) -> &SpanData,
+{
+    let mut best: Option<(&'a T, u32)> = None;
+    for item in items {
+        let span = span_of(item);
+        if !span.contains(file_name, line, column) {
+            continue;
+        }
+        let len = span.byte_end - span.byte_start;
+        match best {
+            Some((_, best_len)) if best_len <= len => {}
+            _ => best = Some((item, len)),
+        }
+    }
+    best.map(|(item, _)| item)
+}
@@ -0,0 +1,696 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Save-analysis output as JSON, selected by `-Z save-analysis-json`.
+//!
+//! The whole crate is written as a single `Analysis` object, whose layout
+//! is given by the types in this module: field names are the JSON keys,
+//! `Option`s are `null` when absent, and the `*Kind` enums are written as
+//! the name of the variant. In addition:
+//!
+//! * `version` is `FORMAT_VERSION`, which is bumped whenever a field is
+//!   removed or changes meaning, so that indexers can reject data they do
+//!   not understand. Adding fields does not change the version.
+//! * An `Id` names an item by crate number and node id. Crate 0 is the
+//!   crate being analysed, other numbers are listed in `external_crates`.
+//! * Spans cover the name of the thing they describe, not the whole item.
+//!   Byte offsets are from the start of the file, lines and columns
+//!   start at 1, and columns count characters.
+//!
+//! The same model is what `save::analyze_crate` hands to in-process
+//! consumers, with `expr_types` filled in as well.
+
+use super::dump::Dump;
+use super::recorder::{self, Row};
+use super::span_utils::SpanUtils;
+
+use middle::ty::Ty;
+
+use std::io::Write;
+use std::mem;
+
+use serialize::json::as_json;
+use syntax::ast::{self, NodeId, DefId};
+use syntax::codemap::*;
+
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(RustcEncodable)]
+pub struct Analysis {
+    pub version: u32,
+    pub crate_name: String,
+    pub external_crates: Vec<ExternalCrate>,
+    pub defs: Vec<Def>,
+    pub refs: Vec<Ref>,
+    pub impls: Vec<Impl>,
+    /// Supertraits of traits.
+    pub inheritance: Vec<Inheritance>,
+    pub imports: Vec<Import>,
+    pub macro_refs: Vec<MacroRef>,
+    /// The types of expressions. Only recorded for in-memory analysis, it
+    /// is always empty in the JSON output.
+    pub expr_types: Vec<ExprType>,
+}
+
+impl Analysis {
+    /// The analysis of a crate with nothing in it.
+    pub fn empty(crate_name: String) -> Analysis {
+        Analysis { crate_name: crate_name, ..Analysis::new() }
+    }
+
+    fn new() -> Analysis {
+        Analysis {
+            version: FORMAT_VERSION,
+            crate_name: String::new(),
+            external_crates: vec![],
+            defs: vec![],
+            refs: vec![],
+            impls: vec![],
+            inheritance: vec![],
+            imports: vec![],
+            macro_refs: vec![],
+            expr_types: vec![],
+        }
+    }
+}
+
+#[derive(RustcEncodable)]
+pub struct ExternalCrate {
+    pub name: String,
+    pub num: u32,
+    pub file_name: String,
+}
+
+#[derive(RustcEncodable, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Id {
+    pub krate: u32,
+    pub index: u32,
+}
+
+#[derive(RustcEncodable)]
+pub struct SpanData {
+    pub file_name: String,
+    pub byte_start: u32,
+    pub byte_end: u32,
+    pub line_start: usize,
+    pub line_end: usize,
+    pub column_start: usize,
+    pub column_end: usize,
+}
+
+impl SpanData {
+    /// Whether the span covers the given position, with the line and
+    /// column counted the same way as in the span.
+    pub fn contains(&self, file_name: &str, line: usize, column: usize) -> bool {
+        self.file_name == file_name &&
+            (line, column) >= (self.line_start, self.column_start) &&
+            (line, column) < (self.line_end, self.column_end)
+    }
+}
+
+#[derive(RustcEncodable)]
+pub enum DefKind {
+    Function,
+    Method,
+    /// A required or provided method in a trait.
+    TraitMethod,
+    /// A local variable or function argument.
+    Local,
+    /// A static or a const.
+    Static,
+    Field,
+    Enum,
+    TupleVariant,
+    StructVariant,
+    Struct,
+    Trait,
+    Mod,
+    Type,
+}
+
+/// The definition of an item or variable.
+#[derive(RustcEncodable)]
+pub struct Def {
+    pub kind: DefKind,
+    pub id: Id,
+    pub span: SpanData,
+    pub name: String,
+    pub qualname: String,
+    /// A textual summary of the definition, e.g. the initialiser of a
+    /// constant, or the source of an enum.
+    pub value: String,
+    /// The type of variables and fields, or the empty string.
+    pub type_value: String,
+    /// The enclosing item, if any.
+    pub parent: Option<Id>,
+    /// For methods, the trait method they implement.
+    pub decl_id: Option<Id>,
+}
+
+#[derive(RustcEncodable)]
+pub enum RefKind {
+    Function,
+    Method,
+    Mod,
+    Type,
+    Variable,
+}
+
+/// A use of an item or variable.
+#[derive(RustcEncodable)]
+pub struct Ref {
+    pub kind: RefKind,
+    pub span: SpanData,
+    /// The item referred to. Only the qualified name is known for some
+    /// path prefixes.
+    pub ref_id: Option<Id>,
+    pub qualname: String,
+}
+
+#[derive(RustcEncodable)]
+pub struct Impl {
+    pub id: Id,
+    pub span: SpanData,
+    /// The type and trait being implemented, when they are nominal.
+    pub self_id: Option<Id>,
+    pub trait_id: Option<Id>,
+    pub parent: Option<Id>,
+}
+
+#[derive(RustcEncodable)]
+pub struct Inheritance {
+    pub span: SpanData,
+    pub base_id: Id,
+    pub derived_id: Id,
+}
+
+#[derive(RustcEncodable)]
+pub enum ImportKind {
+    ExternCrate,
+    Use,
+    GlobUse,
+}
+
+#[derive(RustcEncodable)]
+pub struct Import {
+    pub kind: ImportKind,
+    pub id: Id,
+    pub span: SpanData,
+    pub name: String,
+    /// The names imported by a glob, or the path of an external crate.
+    pub value: String,
+    pub ref_id: Option<Id>,
+    pub parent: Option<Id>,
+}
+
+/// An invocation of a macro.
+#[derive(RustcEncodable)]
+pub struct MacroRef {
+    pub span: SpanData,
+    pub name: String,
+    pub qualname: String,
+    pub parent: Option<Id>,
+}
+
+/// The type of an expression.
+#[derive(RustcEncodable)]
+pub struct ExprType {
+    pub span: SpanData,
+    pub type_value: String,
+}
+
+/// Where a `JsonDumper` puts the analysis once the crate has been walked.
+enum Output<'a> {
+    Writer(Box<Write+'static>),
+    Memory(&'a mut Option<Analysis>),
+}
+
+pub struct JsonDumper<'a> {
+    out: Output<'a>,
+    span: SpanUtils<'a>,
+    result: Analysis,
+}
+
+impl<'a> JsonDumper<'a> {
+    pub fn new(out: Box<Write+'static>, span: SpanUtils<'a>) -> JsonDumper<'a> {
+        JsonDumper::with_output(Output::Writer(out), span)
+    }
+
+    /// A dumper which stores the analysis in `sink` when it is finished
+    /// instead of writing it out. It also records the types of expressions.
+    pub fn in_memory(sink: &'a mut Option<Analysis>, span: SpanUtils<'a>) -> JsonDumper<'a> {
+        JsonDumper::with_output(Output::Memory(sink), span)
+    }
+
+    fn with_output(out: Output<'a>, span: SpanUtils<'a>) -> JsonDumper<'a> {
+        JsonDumper {
+            out: out,
+            span: span,
+            result: Analysis::new(),
+        }
+    }
+
+    fn span_data(&self, span: Span) -> SpanData {
+        let cm = self.span.sess.codemap();
+        let start = cm.lookup_char_pos(span.lo);
+        let end = cm.lookup_char_pos(span.hi);
+        SpanData {
+            file_name: start.file.name.clone(),
+            byte_start: cm.lookup_byte_offset(span.lo).pos.0,
+            byte_end: cm.lookup_byte_offset(span.hi).pos.0,
+            line_start: start.line,
+            line_end: end.line,
+            column_start: start.col.0 + 1,
+            column_end: end.col.0 + 1,
+        }
+    }
+
+    /// Like `FmtStrs::check_and_record`, skips (and reports) anything for
+    /// which no sub-span could be found.
+    fn sub_span(&self, kind: &str, span: Span, sub_span: Option<Span>) -> Option<SpanData> {
+        match sub_span {
+            Some(sub_span) => Some(self.span_data(sub_span)),
+            None => {
+                self.span.report_span_err(kind, span);
+                None
+            }
+        }
+    }
+
+    fn def(&mut self,
+           kind: DefKind,
+           span: Span,
+           sub_span: Option<Span>,
+           id: NodeId,
+           name: &str,
+           qualname: &str,
+           value: &str,
+           type_value: &str,
+           scope_id: NodeId,
+           decl_id: Option<DefId>) {
+        if let Some(span) = self.sub_span("def", span, sub_span) {
+            self.result.defs.push(Def {
+                kind: kind,
+                id: local_id(id),
+                span: span,
+                name: name.to_string(),
+                qualname: qualname.to_string(),
+                value: value.to_string(),
+                type_value: type_value.to_string(),
+                parent: scope(scope_id),
+                decl_id: decl_id.map(def_id),
+            });
+        }
+    }
+
+    fn reference(&mut self,
+                 kind: RefKind,
+                 span: Span,
+                 sub_span: Option<Span>,
+                 ref_id: Option<DefId>,
+                 qualname: &str) {
+        if let Some(span) = self.sub_span("ref", span, sub_span) {
+            self.result.refs.push(Ref {
+                kind: kind,
+                span: span,
+                ref_id: ref_id.map(def_id),
+                qualname: qualname.to_string(),
+            });
+        }
+    }
+
+    fn import(&mut self,
+              kind: ImportKind,
+              span: Span,
+              sub_span: Option<Span>,
+              id: NodeId,
+              name: &str,
+              value: &str,
+              ref_id: Option<DefId>,
+              parent: NodeId) {
+        if let Some(span) = self.sub_span("import", span, sub_span) {
+            self.result.imports.push(Import {
+                kind: kind,
+                id: local_id(id),
+                span: span,
+                name: name.to_string(),
+                value: value.to_string(),
+                ref_id: ref_id.map(def_id),
+                parent: scope(parent),
+            });
+        }
+    }
+}
+
+fn local_id(id: NodeId) -> Id {
+    Id { krate: ast::LOCAL_CRATE, index: id }
+}
+
+fn def_id(id: DefId) -> Id {
+    Id { krate: id.krate, index: id.node }
+}
+
+// Node 0 is the crate root, which is how items at the top level are scoped.
+fn scope(id: NodeId) -> Option<Id> {
+    if id == 0 { None } else { Some(local_id(id)) }
+}
+
+// The last segment of a qualified name.
+fn last_segment(qualname: &str) -> &str {
+    qualname.rsplit("::").next().unwrap_or(qualname)
+}
+
+impl<'a> Dump for JsonDumper<'a> {
+    fn variable_str(&mut self,
+                    span: Span,
+                    sub_span: Option<Span>,
+                    id: NodeId,
+                    name: &str,
+                    value: &str,
+                    typ: &str) {
+        let qualname = format!("{}${}", name, id);
+        self.def(DefKind::Local, span, sub_span, id, name, &qualname, value, typ, 0, None);
+    }
+
+    fn formal_str(&mut self,
+                  span: Span,
+                  sub_span: Option<Span>,
+                  id: NodeId,
+                  fn_name: &str,
+                  name: &str,
+                  typ: &str) {
+        let qualname = format!("{}::{}", fn_name, name);
+        self.def(DefKind::Local, span, sub_span, id, name, &qualname, "", typ, 0, None);
+    }
+
+    fn static_str(&mut self,
+                  span: Span,
+                  sub_span: Option<Span>,
+                  id: NodeId,
+                  name: &str,
+                  qualname: &str,
+                  value: &str,
+                  typ: &str,
+                  scope_id: NodeId) {
+        self.def(DefKind::Static, span, sub_span, id, name, qualname, value, typ, scope_id, None);
+    }
+
+    fn field_str(&mut self,
+                 span: Span,
+                 sub_span: Option<Span>,
+                 id: NodeId,
+                 name: &str,
+                 qualname: &str,
+                 typ: &str,
+                 scope_id: NodeId) {
+        self.def(DefKind::Field, span, sub_span, id, name, qualname, "", typ, scope_id, None);
+    }
+
+    fn enum_str(&mut self,
+                span: Span,
+                sub_span: Option<Span>,
+                id: NodeId,
+                name: &str,
+                scope_id: NodeId,
+                value: &str) {
+        self.def(DefKind::Enum, span, sub_span, id, last_segment(name), name, value, "",
+                 scope_id, None);
+    }
+
+    fn tuple_variant_str(&mut self,
+                         span: Span,
+                         sub_span: Option<Span>,
+                         id: NodeId,
+                         name: &str,
+                         qualname: &str,
+                         typ: &str,
+                         val: &str,
+                         scope_id: NodeId) {
+        self.def(DefKind::TupleVariant, span, sub_span, id, name, qualname, val, typ,
+                 scope_id, None);
+    }
+
+    fn struct_variant_str(&mut self,
+                          span: Span,
+                          sub_span: Option<Span>,
+                          id: NodeId,
+                          _ctor_id: NodeId,
+                          name: &str,
+                          typ: &str,
+                          val: &str,
+                          scope_id: NodeId) {
+        self.def(DefKind::StructVariant, span, sub_span, id, last_segment(name), name, val, typ,
+                 scope_id, None);
+    }
+
+    fn fn_str(&mut self,
+              span: Span,
+              sub_span: Option<Span>,
+              id: NodeId,
+              name: &str,
+              scope_id: NodeId) {
+        self.def(DefKind::Function, span, sub_span, id, last_segment(name), name, "", "",
+                 scope_id, None);
+    }
+
+    fn method_str(&mut self,
+                  span: Span,
+                  sub_span: Option<Span>,
+                  id: NodeId,
+                  name: &str,
+                  decl_id: Option<DefId>,
+                  scope_id: NodeId) {
+        self.def(DefKind::Method, span, sub_span, id, last_segment(name), name, "", "",
+                 scope_id, decl_id);
+    }
+
+    fn method_decl_str(&mut self,
+                       span: Span,
+                       sub_span: Option<Span>,
+                       id: NodeId,
+                       name: &str,
+                       scope_id: NodeId) {
+        self.def(DefKind::TraitMethod, span, sub_span, id, last_segment(name), name, "", "",
+                 scope_id, None);
+    }
+
+    fn struct_str(&mut self,
+                  span: Span,
+                  sub_span: Option<Span>,
+                  id: NodeId,
+                  _ctor_id: NodeId,
+                  name: &str,
+                  scope_id: NodeId,
+                  value: &str) {
+        self.def(DefKind::Struct, span, sub_span, id, last_segment(name), name, value, "",
+                 scope_id, None);
+    }
+
+    fn trait_str(&mut self,
+                 span: Span,
+                 sub_span: Option<Span>,
+                 id: NodeId,
+                 name: &str,
+                 scope_id: NodeId,
+                 value: &str) {
+        self.def(DefKind::Trait, span, sub_span, id, last_segment(name), name, value, "",
+                 scope_id, None);
+    }
+
+    fn impl_str(&mut self,
+                span: Span,
+                sub_span: Option<Span>,
+                id: NodeId,
+                ref_id: Option<DefId>,
+                trait_id: Option<DefId>,
+                scope_id: NodeId) {
+        if let Some(span) = self.sub_span("impl", span, sub_span) {
+            self.result.impls.push(Impl {
+                id: local_id(id),
+                span: span,
+                self_id: ref_id.map(def_id),
+                trait_id: trait_id.map(def_id),
+                parent: scope(scope_id),
+            });
+        }
+    }
+
+    fn mod_str(&mut self,
+               span: Span,
+               sub_span: Option<Span>,
+               id: NodeId,
+               name: &str,
+               parent: NodeId,
+               filename: &str) {
+        self.def(DefKind::Mod, span, sub_span, id, last_segment(name), name, filename, "",
+                 parent, None);
+    }
+
+    fn use_alias_str(&mut self,
+                     span: Span,
+                     sub_span: Option<Span>,
+                     id: NodeId,
+                     mod_id: Option<DefId>,
+                     name: &str,
+                     parent: NodeId) {
+        self.import(ImportKind::Use, span, sub_span, id, name, "", mod_id, parent);
+    }
+
+    fn use_glob_str(&mut self,
+                    span: Span,
+                    sub_span: Option<Span>,
+                    id: NodeId,
+                    values: &str,
+                    parent: NodeId) {
+        self.import(ImportKind::GlobUse, span, sub_span, id, "*", values, None, parent);
+    }
+
+    fn extern_crate_str(&mut self,
+                        span: Span,
+                        sub_span: Option<Span>,
+                        id: NodeId,
+                        cnum: ast::CrateNum,
+                        name: &str,
+                        loc: &str,
+                        parent: NodeId) {
+        let krate = DefId { krate: cnum, node: ast::CRATE_NODE_ID };
+        self.import(ImportKind::ExternCrate, span, sub_span, id, name, loc, Some(krate), parent);
+    }
+
+    fn inherit_str(&mut self,
+                   span: Span,
+                   sub_span: Option<Span>,
+                   base_id: DefId,
+                   deriv_id: NodeId) {
+        if let Some(span) = self.sub_span("inheritance", span, sub_span) {
+            self.result.inheritance.push(Inheritance {
+                span: span,
+                base_id: def_id(base_id),
+                derived_id: local_id(deriv_id),
+            });
+        }
+    }
+
+    fn fn_call_str(&mut self,
+                   span: Span,
+                   sub_span: Option<Span>,
+                   id: DefId,
+                   _scope_id: NodeId) {
+        self.reference(RefKind::Function, span, sub_span, Some(id), "");
+    }
+
+    fn meth_call_str(&mut self,
+                     span: Span,
+                     sub_span: Option<Span>,
+                     defid: Option<DefId>,
+                     declid: Option<DefId>,
+                     _scope_id: NodeId) {
+        self.reference(RefKind::Method, span, sub_span, defid.or(declid), "");
+    }
+
+    fn sub_mod_ref_str(&mut self,
+                       span: Span,
+                       sub_span: Span,
+                       qualname: &str,
+                       _parent: NodeId) {
+        self.reference(RefKind::Mod, span, Some(sub_span), None, qualname);
+    }
+
+    fn typedef_str(&mut self,
+                   span: Span,
+                   sub_span: Option<Span>,
+                   id: NodeId,
+                   qualname: &str,
+                   value: &str) {
+        self.def(DefKind::Type, span, sub_span, id, last_segment(qualname), qualname, value, "",
+                 0, None);
+    }
+
+    fn crate_str(&mut self,
+                 _span: Span,
+                 name: &str) {
+        self.result.crate_name = name.to_string();
+    }
+
+    fn external_crate_str(&mut self,
+                          span: Span,
+                          name: &str,
+                          num: ast::CrateNum) {
+        let lo_loc = self.span.sess.codemap().lookup_char_pos(span.lo);
+        self.result.external_crates.push(ExternalCrate {
+            name: name.to_string(),
+            num: num,
+            file_name: lo_loc.file.name.clone(),
+        });
+    }
+
+    fn sub_type_ref_str(&mut self,
+                        span: Span,
+                        sub_span: Span,
+                        qualname: &str) {
+        self.reference(RefKind::Type, span, Some(sub_span), None, qualname);
+    }
+
+    fn ref_str(&mut self,
+               kind: Row,
+               span: Span,
+               sub_span: Option<Span>,
+               id: DefId,
+               _scope_id: NodeId) {
+        let kind = match kind {
+            recorder::FnRef | recorder::FnCall => RefKind::Function,
+            recorder::MethodCall => RefKind::Method,
+            recorder::ModRef => RefKind::Mod,
+            recorder::TypeRef => RefKind::Type,
+            _ => RefKind::Variable,
+        };
+        self.reference(kind, span, sub_span, Some(id), "");
+    }
+
+    fn macro_use_str(&mut self,
+                     span: Span,
+                     sub_span: Span,
+                     name: &str,
+                     qualname: &str,
+                     scope_id: NodeId) {
+        let span = self.sub_span("macro_use", span, Some(sub_span)).unwrap();
+        self.result.macro_refs.push(MacroRef {
+            span: span,
+            name: name.to_string(),
+            qualname: qualname.to_string(),
+            parent: scope(scope_id),
+        });
+    }
+
+    fn expr_type_str(&mut self, span: Span, typ: Ty) {
+        if let Output::Memory(_) = self.out {
+            let span = self.span_data(span);
+            self.result.expr_types.push(ExprType {
+                span: span,
+                type_value: typ.to_string(),
+            });
+        }
+    }
+
+    fn end_external_crates(&mut self) {}
+
+    fn finish(&mut self) {
+        match self.out {
+            Output::Writer(ref mut out) => {
+                if let Err(e) = write!(out, "{}", as_json(&self.result)) {
+                    error!("Error writing save-analysis output: {}", e);
+                }
+            }
+            Output::Memory(ref mut sink) => {
+                **sink = Some(mem::replace(&mut self.result, Analysis::new()));
+            }
+        }
+    }
+}
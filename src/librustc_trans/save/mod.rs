@@ -11,6 +11,7 @@
 use middle::ty;
 use middle::def;
 
+use std::cell::Cell;
 use std::env;
 use std::fs::{self, File};
 use std::path::{Path, PathBuf};
@@ -25,14 +26,31 @@ use syntax::parse::token::{self, get_ident, keywords};
 use syntax::visit::{self, Visitor};
 use syntax::print::pprust::ty_to_string;
 
+use self::dump::Dump;
+use self::json_dumper::JsonDumper;
+use self::recorder::{Recorder, FmtStrs};
 use self::span_utils::SpanUtils;
 
+pub use self::index::AnalysisIndex;
+pub use self::json_dumper::Analysis;
 
 mod span_utils;
 mod recorder;
+mod dump;
+mod index;
+pub mod json_dumper;
 
 mod dump_csv;
 
+/// The formats save-analysis data can be written in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// CSV rows, as described in `recorder::FmtStrs::lookup_row`.
+    Csv,
+    /// A versioned JSON document, as described in `json_dumper`.
+    Json,
+}
+
 pub struct SaveContext<'l, 'tcx: 'l> {
     tcx: &'l ty::ctxt<'tcx>,
     span_utils: SpanUtils<'l>,
@@ -689,20 +707,15 @@ impl<'v> Visitor<'v> for PathCollector {
 #[allow(deprecated)]
 pub fn process_crate(tcx: &ty::ctxt,
                      analysis: &ty::CrateAnalysis,
-                     odir: Option<&Path>) {
+                     odir: Option<&Path>,
+                     format: Format) {
     let krate = tcx.map.krate();
     if generated_code(krate.span) {
         return;
     }
 
     assert!(analysis.glob_map.is_some());
-    let cratename = match attr::find_crate_name(&krate.attrs) {
-        Some(name) => name.to_string(),
-        None => {
-            info!("Could not find crate name, using 'unknown_crate'");
-            String::from("unknown_crate")
-        },
-    };
+    let cratename = crate_name(krate);
 
     info!("Dumping crate {}", cratename);
 
@@ -727,7 +740,10 @@ pub fn process_crate(tcx: &ty::ctxt,
 
     // Create output file.
     let mut out_name = cratename.clone();
-    out_name.push_str(".csv");
+    out_name.push_str(match format {
+        Format::Csv => ".csv",
+        Format::Json => ".json",
+    });
     root_path.push(&out_name);
     let output_file = match File::create(&root_path) {
         Ok(f) => box f,
@@ -738,10 +754,67 @@ pub fn process_crate(tcx: &ty::ctxt,
     };
     root_path.pop();
 
-    let mut visitor = dump_csv::DumpCsvVisitor::new(tcx, analysis, output_file);
+    let span_utils = SpanUtils {
+        sess: &tcx.sess,
+        err_count: Cell::new(0)
+    };
+    match format {
+        Format::Csv => {
+            let fmt = box FmtStrs::new(box Recorder {
+                                           out: output_file,
+                                           dump_spans: false,
+                                       }, span_utils);
+            dump_crate(tcx, analysis, &cratename, fmt);
+        }
+        Format::Json => {
+            let fmt = box JsonDumper::new(output_file, span_utils);
+            dump_crate(tcx, analysis, &cratename, fmt);
+        }
+    }
+}
+
+/// Runs save-analysis over the crate and returns the data, rather than
+/// writing it to disk. This is meant for tools which drive the compiler
+/// themselves, see `CompileController::save_analysis` in the driver. As
+/// for `process_crate`, resolve must have been asked for a glob map.
+pub fn analyze_crate(tcx: &ty::ctxt, analysis: &ty::CrateAnalysis) -> AnalysisIndex {
+    let krate = tcx.map.krate();
+    assert!(analysis.glob_map.is_some());
+    let cratename = crate_name(krate);
 
-    visitor.dump_crate_info(&cratename, krate);
+    let mut result = None;
+    if !generated_code(krate.span) {
+        let span_utils = SpanUtils {
+            sess: &tcx.sess,
+            err_count: Cell::new(0)
+        };
+        let fmt = box JsonDumper::in_memory(&mut result, span_utils);
+        dump_crate(tcx, analysis, &cratename, fmt);
+    }
+
+    AnalysisIndex::new(result.unwrap_or_else(|| Analysis::empty(cratename)))
+}
+
+fn dump_crate<'l, 'tcx>(tcx: &'l ty::ctxt<'tcx>,
+                        analysis: &'l ty::CrateAnalysis,
+                        cratename: &str,
+                        fmt: Box<Dump + 'l>) {
+    let krate = tcx.map.krate();
+    let mut visitor = dump_csv::DumpCsvVisitor::new(tcx, analysis, fmt);
+
+    visitor.dump_crate_info(cratename, krate);
     visit::walk_crate(&mut visitor, krate);
+    visitor.finish();
+}
+
+fn crate_name(krate: &ast::Crate) -> String {
+    match attr::find_crate_name(&krate.attrs) {
+        Some(name) => name.to_string(),
+        None => {
+            info!("Could not find crate name, using 'unknown_crate'");
+            String::from("unknown_crate")
+        },
+    }
 }
 
 // Utility functions for the module.
@@ -11,6 +11,7 @@
 pub use self::Row::*;
 
 use super::escape;
+use super::dump::Dump;
 use super::span_utils::SpanUtils;
 
 use std::io::Write;
@@ -90,6 +91,7 @@ pub enum Row {
     VarRef,
     TypeRef,
     FnRef,
+    MacroUse,
 }
 
 impl<'a> FmtStrs<'a> {
@@ -149,7 +151,8 @@ impl<'a> FmtStrs<'a> {
             TypeRef => ("type_ref",
                         vec!("refid","refidcrate","qualname","scopeid"),
                         true, true),
-            FnRef => ("fn_ref", vec!("refid","refidcrate","qualname","scopeid"), true, true)
+            FnRef => ("fn_ref", vec!("refid","refidcrate","qualname","scopeid"), true, true),
+            MacroUse => ("macro_use", vec!("name","qualname","scopeid"), true, true)
         }
     }
 
@@ -253,14 +256,16 @@ impl<'a> FmtStrs<'a> {
             }
         }
     }
+}
 
-    pub fn variable_str(&mut self,
-                        span: Span,
-                        sub_span: Option<Span>,
-                        id: NodeId,
-                        name: &str,
-                        value: &str,
-                        typ: &str) {
+impl<'a> Dump for FmtStrs<'a> {
+    fn variable_str(&mut self,
+                    span: Span,
+                    sub_span: Option<Span>,
+                    id: NodeId,
+                    name: &str,
+                    value: &str,
+                    typ: &str) {
         // Getting a fully qualified name for a variable is hard because in
         // the local case they can be overridden in one block and there is no nice way
         // to refer to such a scope in english, so we just hack it by appending the
@@ -275,13 +280,13 @@ impl<'a> FmtStrs<'a> {
     }
 
     // formal parameters
-    pub fn formal_str(&mut self,
-                      span: Span,
-                      sub_span: Option<Span>,
-                      id: NodeId,
-                      fn_name: &str,
-                      name: &str,
-                      typ: &str) {
+    fn formal_str(&mut self,
+                  span: Span,
+                  sub_span: Option<Span>,
+                  id: NodeId,
+                  fn_name: &str,
+                  name: &str,
+                  typ: &str) {
         let mut qualname = String::from(fn_name);
         qualname.push_str("::");
         qualname.push_str(name);
@@ -292,97 +297,97 @@ impl<'a> FmtStrs<'a> {
     }
 
     // value is the initialising expression of the static if it is not mut, otherwise "".
-    pub fn static_str(&mut self,
-                      span: Span,
-                      sub_span: Option<Span>,
-                      id: NodeId,
-                      name: &str,
-                      qualname: &str,
-                      value: &str,
-                      typ: &str,
-                      scope_id: NodeId) {
+    fn static_str(&mut self,
+                  span: Span,
+                  sub_span: Option<Span>,
+                  id: NodeId,
+                  name: &str,
+                  qualname: &str,
+                  value: &str,
+                  typ: &str,
+                  scope_id: NodeId) {
         self.check_and_record(Variable,
                               span,
                               sub_span,
                               svec!(id, name, qualname, value, typ, scope_id));
     }
 
-    pub fn field_str(&mut self,
-                     span: Span,
-                     sub_span: Option<Span>,
-                     id: NodeId,
-                     name: &str,
-                     qualname: &str,
-                     typ: &str,
-                     scope_id: NodeId) {
+    fn field_str(&mut self,
+                 span: Span,
+                 sub_span: Option<Span>,
+                 id: NodeId,
+                 name: &str,
+                 qualname: &str,
+                 typ: &str,
+                 scope_id: NodeId) {
         self.check_and_record(Variable,
                               span,
                               sub_span,
                               svec!(id, name, qualname, "", typ, scope_id));
     }
 
-    pub fn enum_str(&mut self,
-                    span: Span,
-                    sub_span: Option<Span>,
-                    id: NodeId,
-                    name: &str,
-                    scope_id: NodeId,
-                    value: &str) {
+    fn enum_str(&mut self,
+                span: Span,
+                sub_span: Option<Span>,
+                id: NodeId,
+                name: &str,
+                scope_id: NodeId,
+                value: &str) {
         self.check_and_record(Enum,
                               span,
                               sub_span,
                               svec!(id, name, scope_id, value));
     }
 
-    pub fn tuple_variant_str(&mut self,
-                             span: Span,
-                             sub_span: Option<Span>,
-                             id: NodeId,
-                             name: &str,
-                             qualname: &str,
-                             typ: &str,
-                             val: &str,
-                             scope_id: NodeId) {
+    fn tuple_variant_str(&mut self,
+                         span: Span,
+                         sub_span: Option<Span>,
+                         id: NodeId,
+                         name: &str,
+                         qualname: &str,
+                         typ: &str,
+                         val: &str,
+                         scope_id: NodeId) {
         self.check_and_record(Variant,
                               span,
                               sub_span,
                               svec!(id, name, qualname, typ, val, scope_id));
     }
 
-    pub fn struct_variant_str(&mut self,
-                              span: Span,
-                              sub_span: Option<Span>,
-                              id: NodeId,
-                              ctor_id: NodeId,
-                              name: &str,
-                              typ: &str,
-                              val: &str,
-                              scope_id: NodeId) {
+    fn struct_variant_str(&mut self,
+                          span: Span,
+                          sub_span: Option<Span>,
+                          id: NodeId,
+                          ctor_id: NodeId,
+                          name: &str,
+                          typ: &str,
+                          val: &str,
+                          scope_id: NodeId) {
         self.check_and_record(VariantStruct,
                               span,
                               sub_span,
                               svec!(id, ctor_id, name, typ, val, scope_id));
     }
 
-    pub fn fn_str(&mut self,
-                  span: Span,
-                  sub_span: Option<Span>,
-                  id: NodeId,
-                  name: &str,
-                  scope_id: NodeId) {
+    fn fn_str(&mut self,
+              span: Span,
+              sub_span: Option<Span>,
+              id: NodeId,
+              name: &str,
+              scope_id: NodeId) {
         self.check_and_record(Function,
                               span,
                               sub_span,
                               svec!(id, name, "", "", scope_id));
     }
 
-    pub fn method_str(&mut self,
-                      span: Span,
-                      sub_span: Option<Span>,
-                      id: NodeId,
-                      name: &str,
-                      decl_id: Option<DefId>,
-                      scope_id: NodeId) {
+    fn method_str(&mut self,
+                  span: Span,
+                  sub_span: Option<Span>,
+                  id: NodeId,
+                  name: &str,
+                  decl_id: Option<DefId>,
+                  scope_id: NodeId) {
         let values = match decl_id {
             Some(decl_id) => svec!(id, name, decl_id.node, decl_id.krate, scope_id),
             None => svec!(id, name, "", "", scope_id)
@@ -393,52 +398,52 @@ impl<'a> FmtStrs<'a> {
                               values);
     }
 
-    pub fn method_decl_str(&mut self,
-                           span: Span,
-                           sub_span: Option<Span>,
-                           id: NodeId,
-                           name: &str,
-                           scope_id: NodeId) {
+    fn method_decl_str(&mut self,
+                       span: Span,
+                       sub_span: Option<Span>,
+                       id: NodeId,
+                       name: &str,
+                       scope_id: NodeId) {
         self.check_and_record(MethodDecl,
                               span,
                               sub_span,
                               svec!(id, name, scope_id));
     }
 
-    pub fn struct_str(&mut self,
-                      span: Span,
-                      sub_span: Option<Span>,
-                      id: NodeId,
-                      ctor_id: NodeId,
-                      name: &str,
-                      scope_id: NodeId,
-                      value: &str) {
+    fn struct_str(&mut self,
+                  span: Span,
+                  sub_span: Option<Span>,
+                  id: NodeId,
+                  ctor_id: NodeId,
+                  name: &str,
+                  scope_id: NodeId,
+                  value: &str) {
         self.check_and_record(Struct,
                               span,
                               sub_span,
                               svec!(id, ctor_id, name, scope_id, value));
     }
 
-    pub fn trait_str(&mut self,
-                     span: Span,
-                     sub_span: Option<Span>,
-                     id: NodeId,
-                     name: &str,
-                     scope_id: NodeId,
-                     value: &str) {
+    fn trait_str(&mut self,
+                 span: Span,
+                 sub_span: Option<Span>,
+                 id: NodeId,
+                 name: &str,
+                 scope_id: NodeId,
+                 value: &str) {
         self.check_and_record(Trait,
                               span,
                               sub_span,
                               svec!(id, name, scope_id, value));
     }
 
-    pub fn impl_str(&mut self,
-                    span: Span,
-                    sub_span: Option<Span>,
-                    id: NodeId,
-                    ref_id: Option<DefId>,
-                    trait_id: Option<DefId>,
-                    scope_id: NodeId) {
+    fn impl_str(&mut self,
+                span: Span,
+                sub_span: Option<Span>,
+                id: NodeId,
+                ref_id: Option<DefId>,
+                trait_id: Option<DefId>,
+                scope_id: NodeId) {
         let ref_id = ref_id.unwrap_or(ZERO_DEF_ID);
         let trait_id = trait_id.unwrap_or(ZERO_DEF_ID);
         self.check_and_record(Impl,
@@ -452,26 +457,26 @@ impl<'a> FmtStrs<'a> {
                                     scope_id));
     }
 
-    pub fn mod_str(&mut self,
-                   span: Span,
-                   sub_span: Option<Span>,
-                   id: NodeId,
-                   name: &str,
-                   parent: NodeId,
-                   filename: &str) {
+    fn mod_str(&mut self,
+               span: Span,
+               sub_span: Option<Span>,
+               id: NodeId,
+               name: &str,
+               parent: NodeId,
+               filename: &str) {
         self.check_and_record(Module,
                               span,
                               sub_span,
                               svec!(id, name, parent, filename));
     }
 
-    pub fn use_alias_str(&mut self,
-                         span: Span,
-                         sub_span: Option<Span>,
-                         id: NodeId,
-                         mod_id: Option<DefId>,
-                         name: &str,
-                         parent: NodeId) {
+    fn use_alias_str(&mut self,
+                     span: Span,
+                     sub_span: Option<Span>,
+                     id: NodeId,
+                     mod_id: Option<DefId>,
+                     name: &str,
+                     parent: NodeId) {
         let (mod_node, mod_crate) = match mod_id {
             Some(mod_id) => (mod_id.node, mod_id.krate),
             None => (0, 0)
@@ -482,37 +487,37 @@ impl<'a> FmtStrs<'a> {
                               svec!(id, mod_node, mod_crate, name, parent));
     }
 
-    pub fn use_glob_str(&mut self,
-                        span: Span,
-                        sub_span: Option<Span>,
-                        id: NodeId,
-                        values: &str,
-                        parent: NodeId) {
+    fn use_glob_str(&mut self,
+                    span: Span,
+                    sub_span: Option<Span>,
+                    id: NodeId,
+                    values: &str,
+                    parent: NodeId) {
         self.check_and_record(UseGlob,
                               span,
                               sub_span,
                               svec!(id, values, parent));
     }
 
-    pub fn extern_crate_str(&mut self,
-                            span: Span,
-                            sub_span: Option<Span>,
-                            id: NodeId,
-                            cnum: ast::CrateNum,
-                            name: &str,
-                            loc: &str,
-                            parent: NodeId) {
+    fn extern_crate_str(&mut self,
+                        span: Span,
+                        sub_span: Option<Span>,
+                        id: NodeId,
+                        cnum: ast::CrateNum,
+                        name: &str,
+                        loc: &str,
+                        parent: NodeId) {
         self.check_and_record(ExternCrate,
                               span,
                               sub_span,
                               svec!(id, name, loc, cnum, parent));
     }
 
-    pub fn inherit_str(&mut self,
-                       span: Span,
-                       sub_span: Option<Span>,
-                       base_id: DefId,
-                       deriv_id: NodeId) {
+    fn inherit_str(&mut self,
+                   span: Span,
+                   sub_span: Option<Span>,
+                   base_id: DefId,
+                   deriv_id: NodeId) {
         self.check_and_record(Inheritance,
                               span,
                               sub_span,
@@ -522,23 +527,23 @@ impl<'a> FmtStrs<'a> {
                                     0));
     }
 
-    pub fn fn_call_str(&mut self,
-                       span: Span,
-                       sub_span: Option<Span>,
-                       id: DefId,
-                       scope_id:NodeId) {
+    fn fn_call_str(&mut self,
+                   span: Span,
+                   sub_span: Option<Span>,
+                   id: DefId,
+                   scope_id:NodeId) {
         self.check_and_record(FnCall,
                               span,
                               sub_span,
                               svec!(id.node, id.krate, "", scope_id));
     }
 
-    pub fn meth_call_str(&mut self,
-                         span: Span,
-                         sub_span: Option<Span>,
-                         defid: Option<DefId>,
-                         declid: Option<DefId>,
-                         scope_id: NodeId) {
+    fn meth_call_str(&mut self,
+                     span: Span,
+                     sub_span: Option<Span>,
+                     defid: Option<DefId>,
+                     declid: Option<DefId>,
+                     scope_id: NodeId) {
         let (dfn, dfk) = match defid {
             Some(defid) => (defid.node, defid.krate),
             None => (0, 0)
@@ -553,52 +558,52 @@ impl<'a> FmtStrs<'a> {
                               svec!(dfn, dfk, dcn, dck, scope_id));
     }
 
-    pub fn sub_mod_ref_str(&mut self,
-                           span: Span,
-                           sub_span: Span,
-                           qualname: &str,
-                           parent:NodeId) {
+    fn sub_mod_ref_str(&mut self,
+                       span: Span,
+                       sub_span: Span,
+                       qualname: &str,
+                       parent:NodeId) {
         self.record_with_span(ModRef,
                               span,
                               sub_span,
                               svec!(0, 0, qualname, parent));
     }
 
-    pub fn typedef_str(&mut self,
-                       span: Span,
-                       sub_span: Option<Span>,
-                       id: NodeId,
-                       qualname: &str,
-                       value: &str) {
+    fn typedef_str(&mut self,
+                   span: Span,
+                   sub_span: Option<Span>,
+                   id: NodeId,
+                   qualname: &str,
+                   value: &str) {
         self.check_and_record(Typedef,
                               span,
                               sub_span,
                               svec!(id, qualname, value));
     }
 
-    pub fn crate_str(&mut self,
-                     span: Span,
-                     name: &str) {
+    fn crate_str(&mut self,
+                 span: Span,
+                 name: &str) {
         self.record_with_span(Crate,
                               span,
                               span,
                               svec!(name));
     }
 
-    pub fn external_crate_str(&mut self,
-                              span: Span,
-                              name: &str,
-                              num: ast::CrateNum) {
+    fn external_crate_str(&mut self,
+                          span: Span,
+                          name: &str,
+                          num: ast::CrateNum) {
         let lo_loc = self.span.sess.codemap().lookup_char_pos(span.lo);
         self.record_without_span(ExternalCrate,
                                  svec!(name, num, lo_loc.file.name),
                                  span);
     }
 
-    pub fn sub_type_ref_str(&mut self,
-                            span: Span,
-                            sub_span: Span,
-                            qualname: &str) {
+    fn sub_type_ref_str(&mut self,
+                        span: Span,
+                        sub_span: Span,
+                        qualname: &str) {
         self.record_with_span(TypeRef,
                               span,
                               sub_span,
@@ -606,15 +611,31 @@ impl<'a> FmtStrs<'a> {
     }
 
     // A slightly generic function for a reference to an item of any kind.
-    pub fn ref_str(&mut self,
-                   kind: Row,
-                   span: Span,
-                   sub_span: Option<Span>,
-                   id: DefId,
-                   scope_id: NodeId) {
+    fn ref_str(&mut self,
+               kind: Row,
+               span: Span,
+               sub_span: Option<Span>,
+               id: DefId,
+               scope_id: NodeId) {
         self.check_and_record(kind,
                               span,
                               sub_span,
                               svec!(id.node, id.krate, "", scope_id));
     }
+
+    fn macro_use_str(&mut self,
+                     span: Span,
+                     sub_span: Span,
+                     name: &str,
+                     qualname: &str,
+                     scope_id: NodeId) {
+        self.record_with_span(MacroUse,
+                              span,
+                              sub_span,
+                              svec!(name, qualname, scope_id));
+    }
+
+    fn end_external_crates(&mut self) {
+        self.recorder.record("end_external_crates\n");
+    }
 }
@@ -82,6 +82,7 @@ use trans::type_of;
 use trans::type_of::*;
 use trans::value::Value;
 use util::common::indenter;
+use util::profiling;
 use util::sha2::Sha256;
 use util::nodemap::NodeMap;
 
@@ -2554,6 +2555,98 @@ pub fn write_metadata(cx: &SharedCrateContext, krate: &ast::Crate) -> Vec<u8> {
     return metadata;
 }
 
+/// Records the symbol of every item whose symbol is written into the crate
+/// metadata, as `get_item_val` would, but without declaring anything in LLVM.
+struct MetadataSymbolVisitor<'a, 'tcx: 'a> {
+    ccx: &'a CrateContext<'a, 'tcx>,
+}
+
+impl<'a, 'tcx> MetadataSymbolVisitor<'a, 'tcx> {
+    fn record(&self, id: ast::NodeId, attrs: &[ast::Attribute]) {
+        let ty = self.ccx.tcx().node_id_to_type(id);
+        let sym = exported_name(self.ccx, id, ty, attrs);
+        self.ccx.item_symbols().borrow_mut().insert(id, sym);
+    }
+}
+
+impl<'a, 'tcx, 'v> Visitor<'v> for MetadataSymbolVisitor<'a, 'tcx> {
+    fn visit_item(&mut self, i: &ast::Item) {
+        match i.node {
+            ast::ItemStatic(..) => self.record(i.id, &i.attrs),
+            ast::ItemFn(_, _, _, _, ref generics, _) if !generics.is_type_parameterized() => {
+                self.record(i.id, &i.attrs)
+            }
+            ast::ItemStruct(ref struct_def, ref generics)
+                    if !generics.is_type_parameterized() => {
+                if let Some(ctor_id) = struct_def.ctor_id {
+                    self.record(ctor_id, &i.attrs);
+                }
+            }
+            _ => {}
+        }
+        visit::walk_item(self, i);
+    }
+
+    fn visit_impl_item(&mut self, ii: &ast::ImplItem) {
+        if let ast::MethodImplItem(..) = ii.node {
+            // The generics include those of the impl.
+            let scheme = self.ccx.tcx().lookup_item_type(local_def(ii.id));
+            if scheme.generics.types.is_empty() {
+                self.record(ii.id, &ii.attrs);
+            }
+        }
+        visit::walk_impl_item(self, ii);
+    }
+
+    fn visit_foreign_item(&mut self, ni: &ast::ForeignItem) {
+        let name = foreign::link_name(ni);
+        self.ccx.item_symbols().borrow_mut().insert(ni.id, name.to_string());
+        visit::walk_foreign_item(self, ni);
+    }
+}
+
+/// Encodes the crate metadata straight after analysis, for `--emit=metadata`
+/// on its own. Nothing is translated: the symbols recorded in the metadata
+/// are computed the same way translation would compute them. Returns an empty
+/// vector if no library crate type was requested.
+pub fn encode_metadata_only(tcx: &ty::ctxt, analysis: ty::CrateAnalysis) -> Vec<u8> {
+    let ty::CrateAnalysis { export_map, reachable, name, .. } = analysis;
+    let krate = tcx.map.krate();
+
+    let any_library = tcx.sess.crate_types.borrow().iter().any(|ty| {
+        *ty != config::CrateTypeExecutable
+    });
+    if !any_library {
+        return Vec::new()
+    }
+
+    let link_meta = link::build_link_meta(&tcx.sess, krate, name);
+
+    // The LLVM contexts are left empty; the crate context is only needed for
+    // symbol mangling.
+    let shared_ccx = SharedCrateContext::new(&link_meta.crate_name,
+                                             1,
+                                             tcx,
+                                             export_map,
+                                             Sha256::new(),
+                                             link_meta.clone(),
+                                             reachable,
+                                             false,
+                                             false);
+
+    {
+        let ccx = shared_ccx.get_ccx(0);
+        let _icx = push_ctxt("metadata symbols");
+        visit::walk_crate(&mut MetadataSymbolVisitor { ccx: &ccx }, krate);
+    }
+
+    let encode_inlined_item: encoder::EncodeInlinedItem =
+        Box::new(|ecx, rbml_w, ii| astencode::encode_inlined_item(ecx, rbml_w, ii));
+
+    let encode_parms = crate_ctxt_to_encode_parms(&shared_ccx, encode_inlined_item);
+    encoder::encode_metadata(encode_parms, krate)
+}
+
 /// Find any symbols that are defined in one compilation unit, but not declared
 /// in any other compilation unit.  Give these symbols internal linkage.
 fn internalize_symbols(cx: &SharedCrateContext, reachable: &HashSet<String>) {
@@ -2708,6 +2801,15 @@ pub fn trans_crate(tcx: &ty::ctxt, analysis: ty::CrateAnalysis) -> CrateTranslat
     // Translate the metadata.
     let metadata = write_metadata(&shared_ccx, krate);
 
+    {
+        let stats = shared_ccx.stats();
+        profiling::count("codegen units", shared_ccx.iter().count());
+        profiling::count("glues created", stats.n_glues_created.get());
+        profiling::count("monomorphizations", stats.n_monos.get());
+        profiling::count("inlined items", stats.n_inlines.get());
+        profiling::count("closures", stats.n_closures.get());
+    }
+
     if shared_ccx.sess().trans_stats() {
         let stats = shared_ccx.stats();
         println!("--- trans stats ---");
@@ -2769,6 +2871,8 @@ pub fn trans_crate(tcx: &ty::ctxt, analysis: ty::CrateAnalysis) -> CrateTranslat
     // referenced from rt/rust_try.ll
     reachable.push("rust_eh_personality_catch".to_string());
 
+    reachable.sort();
+
     if codegen_units > 1 {
         internalize_symbols(&shared_ccx, &reachable.iter().cloned().collect());
     }
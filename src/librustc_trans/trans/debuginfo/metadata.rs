@@ -39,7 +39,7 @@ use util::common::path2cstr;
 
 use libc::{c_uint, c_longlong};
 use std::ffi::CString;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::ptr;
 use std::rc::Rc;
 use syntax::util::interner::Interner;
@@ -880,7 +880,10 @@ pub fn file_metadata(cx: &CrateContext, full_path: &str) -> DIFile {
     debug!("file_metadata: {}", full_path);
 
     // FIXME (#9639): This needs to handle non-utf8 paths
-    let work_dir = cx.sess().working_dir.to_str().unwrap();
+    // `full_path` has already had --remap-path-prefix applied by the codemap.
+    let (work_dir, _) = cx.sess().codemap().path_mapping()
+                          .map_prefix(cx.sess().working_dir.to_str().unwrap());
+    let work_dir = &work_dir[..];
     let file_name =
         if full_path.starts_with(work_dir) {
             &full_path[work_dir.len() + 1..full_path.len()]
@@ -997,11 +1000,18 @@ fn pointer_type_metadata<'a, 'tcx>(cx: &CrateContext<'a, 'tcx>,
 }
 
 pub fn compile_unit_metadata(cx: &CrateContext) -> DIDescriptor {
-    let work_dir = &cx.sess().working_dir;
+    let path_mapping = cx.sess().codemap().path_mapping();
+    let (work_dir, _) = path_mapping.map_prefix(&cx.sess().working_dir.to_string_lossy());
+    let work_dir = &PathBuf::from(work_dir);
     let compile_unit_name = match cx.sess().local_crate_source_file {
         None => fallback_path(cx),
         Some(ref abs_path) => {
-            if abs_path.is_relative() {
+            let (abs_path, remapped) = path_mapping.map_prefix(&abs_path.to_string_lossy());
+            let abs_path = Path::new(&abs_path);
+            if remapped && abs_path.is_relative() {
+                // A prefix was deliberately remapped to a relative path.
+                path2cstr(abs_path)
+            } else if abs_path.is_relative() {
                 cx.sess().warn("debuginfo: Invalid path to crate's local root source file!");
                 fallback_path(cx)
             } else {
@@ -0,0 +1,669 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! The `api-json` output format: a documented description of a crate's
+//! API for tools other than rustdoc.
+//!
+//! Unlike `-w json`, whose shape is whatever `clean::Crate` encodes to, this
+//! format only changes together with `FORMAT_VERSION`. Fields may be added
+//! without changing the version, but are never removed or given a different
+//! meaning. The output is a single object:
+//!
+//! ```text
+//! {
+//!   "format_version": 1,
+//!   "crate_name": "foo",
+//!   "root": "foo:0",
+//!   "index": { id: item, ... },
+//!   "paths": { id: { "crate": name, "path": [name, ...], "kind": kind }, ... },
+//!   "external_crates": [name, ...],
+//!   "implementors": { trait id: [impl id, ...], ... }
+//! }
+//! ```
+//!
+//! An id is a string `"<crate name>:<node id>"`, where the node id is the
+//! one the item has in the crate defining it. The same item thus has the
+//! same id in the output of every crate referring to it, so ids in `paths`
+//! and `implementors` can be looked up in the `index` of other crates.
+//!
+//! `index` holds every item documented in this crate, including fields,
+//! variants, impls and associated items. Every item is an object with
+//! `id`, `crate`, `name` (`null` for impls), `kind`, `visibility`
+//! (`"public"` or `"default"`, `null` when not applicable), `docs` (the
+//! Markdown source, or `null`), `span` (`filename`, and `begin` and `end`
+//! as `[line, column]`), `stability` (`null` or an object with `level`,
+//! `feature`, `since`, `deprecated_since` and `reason`) and `inner`. The
+//! fields of `inner` depend on `kind`; items contained in another refer to
+//! them by id, as in `"items"`, `"fields"` and `"variants"`. The kinds are
+//! the names used in rustdoc's HTML file names, such as `mod`, `struct`,
+//! `fn`, `trait`, `impl`, `method` and `tymethod`.
+//!
+//! `paths` gives the path of every item in `index` which has one, and of
+//! every item of another crate which this crate refers to and rustdoc knows
+//! the path of. The first element of a path is the name of the crate.
+//! `implementors` maps each trait to the impls of it in this crate.
+//!
+//! Types are objects with a `kind` of `resolved_path` (with `path` and
+//! `id`), `generic`, `primitive`, `function_pointer`, `tuple`, `slice`,
+//! `array`, `never`, `box`, `raw_pointer`, `borrowed_ref`,
+//! `qualified_path`, `infer` or `trait_object`. Generics are objects with
+//! `lifetimes`, `params` and `where_predicates`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+
+use serialize::json::Json;
+use syntax::abi;
+use syntax::ast;
+use syntax::attr;
+
+use clean;
+use doctree;
+use html::item_type::ItemType;
+
+/// The version of the `api-json` format. See the module documentation.
+pub const FORMAT_VERSION: u64 = 1;
+
+/// Writes the `api-json` description of `krate` to `dst`.
+pub fn run(krate: &clean::Crate, dst: &Path) -> io::Result<()> {
+    let mut file = try!(File::create(dst));
+    write!(&mut file, "{}", to_json(krate))
+}
+
+pub fn to_json(krate: &clean::Crate) -> Json {
+    let mut crate_names = HashMap::new();
+    crate_names.insert(ast::LOCAL_CRATE, krate.name.clone());
+    for &(n, ref e) in &krate.externs {
+        crate_names.insert(n, e.name.clone());
+    }
+    let mut cx = Context {
+        crate_names: crate_names,
+        index: BTreeMap::new(),
+        paths: BTreeMap::new(),
+        implementors: BTreeMap::new(),
+        stack: vec![krate.name.clone()],
+    };
+
+    // Paths of items in other crates, as found when cleaning this one.
+    let analysis = ::ANALYSISKEY.with(|a| a.clone());
+    if let Some(ref analysis) = *analysis.borrow() {
+        if let Some(ref paths) = *analysis.external_paths.borrow() {
+            for (&did, &(ref path, kind)) in paths {
+                cx.add_path(did, path.clone(), ItemType::from_type_kind(kind));
+            }
+        }
+    }
+
+    let root = match krate.module {
+        Some(ref m) => {
+            cx.add_path(m.def_id, vec![krate.name.clone()], ItemType::Module);
+            cx.item(m);
+            cx.id(m.def_id)
+        }
+        None => Json::Null,
+    };
+
+    let mut doc = BTreeMap::new();
+    doc.insert("format_version".to_string(), Json::U64(FORMAT_VERSION));
+    doc.insert("crate_name".to_string(), string(&krate.name));
+    doc.insert("root".to_string(), root);
+    doc.insert("external_crates".to_string(),
+               Json::Array(krate.externs.iter().map(|&(_, ref e)| string(&e.name)).collect()));
+    doc.insert("index".to_string(), Json::Object(cx.index));
+    doc.insert("paths".to_string(), Json::Object(cx.paths));
+    doc.insert("implementors".to_string(),
+               Json::Object(cx.implementors.into_iter()
+                                           .map(|(k, v)| (k, Json::Array(v)))
+                                           .collect()));
+    Json::Object(doc)
+}
+
+struct Context {
+    crate_names: HashMap<ast::CrateNum, String>,
+    index: BTreeMap<String, Json>,
+    paths: BTreeMap<String, Json>,
+    implementors: BTreeMap<String, Vec<Json>>,
+    // The path of the module or enum currently being visited.
+    stack: Vec<String>,
+}
+
+impl Context {
+    fn id_string(&self, did: ast::DefId) -> String {
+        match self.crate_names.get(&did.krate) {
+            Some(name) => format!("{}:{}", name, did.node),
+            // Every crate an item can come from is in `externs`, but fall
+            // back to something which cannot clash with a crate name.
+            None => format!("#{}:{}", did.krate, did.node),
+        }
+    }
+
+    fn id(&self, did: ast::DefId) -> Json {
+        Json::String(self.id_string(did))
+    }
+
+    fn add_path(&mut self, did: ast::DefId, path: Vec<String>, kind: ItemType) {
+        let crate_name = match self.crate_names.get(&did.krate) {
+            Some(name) => string(name),
+            None => Json::Null,
+        };
+        let entry = object(vec![
+            ("crate", crate_name),
+            ("path", Json::Array(path.iter().map(|s| string(s)).collect())),
+            ("kind", string(kind.to_static_str())),
+        ]);
+        let id = self.id_string(did);
+        self.paths.insert(id, entry);
+    }
+
+    /// Adds `item` and everything it contains to the index, returning its
+    /// id.
+    fn item(&mut self, item: &clean::Item) -> Json {
+        let kind = ItemType::from_item(item);
+        if let Some(ref name) = item.name {
+            match kind {
+                ItemType::Module | ItemType::Struct | ItemType::Enum |
+                ItemType::Function | ItemType::Typedef | ItemType::Static |
+                ItemType::Constant | ItemType::Trait | ItemType::Macro |
+                ItemType::Primitive | ItemType::Variant => {
+                    // The crate root is already in `stack`, and associated
+                    // types are only reachable through their impl.
+                    let has_path = match item.inner {
+                        clean::ModuleItem(ref m) => !m.is_crate,
+                        clean::TypedefItem(_, associated) => !associated,
+                        _ => true,
+                    };
+                    if has_path {
+                        let mut path = self.stack.clone();
+                        path.push(name.clone());
+                        self.add_path(item.def_id, path, kind);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let pushed = match (&item.inner, &item.name) {
+            (&clean::ModuleItem(ref m), &Some(ref name)) if !m.is_crate => {
+                self.stack.push(name.clone());
+                true
+            }
+            (&clean::EnumItem(..), &Some(ref name)) => {
+                self.stack.push(name.clone());
+                true
+            }
+            _ => false,
+        };
+        let inner = self.inner(item);
+        if pushed {
+            self.stack.pop();
+        }
+
+        if let clean::ImplItem(ref i) = item.inner {
+            if let Some(clean::ResolvedPath { did, .. }) = i.trait_ {
+                let trait_id = self.id_string(did);
+                let impl_id = self.id(item.def_id);
+                self.implementors.entry(trait_id).or_insert(vec![]).push(impl_id);
+            }
+        }
+
+        let visibility = match item.visibility {
+            Some(ast::Public) => string("public"),
+            Some(ast::Inherited) => string("default"),
+            None => Json::Null,
+        };
+        let stability = match item.stability {
+            Some(ref s) => object(vec![
+                ("level", string(match s.level {
+                    attr::Stable => "stable",
+                    attr::Unstable => "unstable",
+                })),
+                ("feature", string(&s.feature)),
+                ("since", string(&s.since)),
+                ("deprecated_since", string(&s.deprecated_since)),
+                ("reason", string(&s.reason)),
+            ]),
+            None => Json::Null,
+        };
+        let span = &item.source;
+        let json = object(vec![
+            ("id", self.id(item.def_id)),
+            ("crate", match self.crate_names.get(&item.def_id.krate) {
+                Some(name) => string(name),
+                None => Json::Null,
+            }),
+            ("name", opt_string(&item.name)),
+            ("kind", string(kind.to_static_str())),
+            ("visibility", visibility),
+            ("docs", match item.doc_value() {
+                Some(doc) => string(doc),
+                None => Json::Null,
+            }),
+            ("span", object(vec![
+                ("filename", string(&span.filename)),
+                ("begin", Json::Array(vec![Json::U64(span.loline as u64),
+                                           Json::U64(span.locol as u64)])),
+                ("end", Json::Array(vec![Json::U64(span.hiline as u64),
+                                         Json::U64(span.hicol as u64)])),
+            ])),
+            ("stability", stability),
+            ("inner", inner),
+        ]);
+        let id = self.id_string(item.def_id);
+        self.index.insert(id, json);
+        self.id(item.def_id)
+    }
+
+    fn items(&mut self, items: &[clean::Item]) -> Json {
+        Json::Array(items.iter().map(|i| self.item(i)).collect())
+    }
+
+    fn inner(&mut self, item: &clean::Item) -> Json {
+        match item.inner {
+            clean::ModuleItem(ref m) => object(vec![
+                ("is_crate", Json::Boolean(m.is_crate)),
+                ("items", self.items(&m.items)),
+            ]),
+            clean::ExternCrateItem(ref name, ref source) => object(vec![
+                ("name", string(name)),
+                ("source", opt_string(source)),
+            ]),
+            clean::ImportItem(ref import) => self.import(import),
+            clean::StructItem(ref s) => object(vec![
+                ("struct_type", struct_type(s.struct_type)),
+                ("generics", self.generics(&s.generics)),
+                ("fields", self.items(&s.fields)),
+                ("fields_stripped", Json::Boolean(s.fields_stripped)),
+            ]),
+            clean::EnumItem(ref e) => object(vec![
+                ("generics", self.generics(&e.generics)),
+                ("variants", self.items(&e.variants)),
+                ("variants_stripped", Json::Boolean(e.variants_stripped)),
+            ]),
+            clean::VariantItem(ref v) => match v.kind {
+                clean::CLikeVariant => object(vec![
+                    ("variant_kind", string("plain")),
+                ]),
+                clean::TupleVariant(ref tys) => object(vec![
+                    ("variant_kind", string("tuple")),
+                    ("types", self.types(tys)),
+                ]),
+                clean::StructVariant(ref s) => object(vec![
+                    ("variant_kind", string("struct")),
+                    ("struct_type", struct_type(s.struct_type)),
+                    ("fields", self.items(&s.fields)),
+                    ("fields_stripped", Json::Boolean(s.fields_stripped)),
+                ]),
+            },
+            clean::StructFieldItem(ref f) => object(vec![
+                ("type", match *f {
+                    clean::TypedStructField(ref ty) => self.type_(ty),
+                    clean::HiddenStructField => Json::Null,
+                }),
+            ]),
+            clean::FunctionItem(ref f) | clean::ForeignFunctionItem(ref f) => object(vec![
+                ("decl", self.decl(&f.decl)),
+                ("generics", self.generics(&f.generics)),
+                ("unsafe", Json::Boolean(f.unsafety == ast::Unsafety::Unsafe)),
+                ("const", Json::Boolean(f.constness == ast::Constness::Const)),
+                ("abi", abi_(f.abi)),
+                ("foreign", Json::Boolean(match item.inner {
+                    clean::ForeignFunctionItem(..) => true,
+                    _ => false,
+                })),
+            ]),
+            clean::MethodItem(ref m) => object(vec![
+                ("decl", self.decl(&m.decl)),
+                ("generics", self.generics(&m.generics)),
+                ("self", self.self_ty(&m.self_)),
+                ("unsafe", Json::Boolean(m.unsafety == ast::Unsafety::Unsafe)),
+                ("const", Json::Boolean(m.constness == ast::Constness::Const)),
+                ("abi", abi_(m.abi)),
+            ]),
+            clean::TyMethodItem(ref m) => object(vec![
+                ("decl", self.decl(&m.decl)),
+                ("generics", self.generics(&m.generics)),
+                ("self", self.self_ty(&m.self_)),
+                ("unsafe", Json::Boolean(m.unsafety == ast::Unsafety::Unsafe)),
+                ("abi", abi_(m.abi)),
+            ]),
+            clean::TypedefItem(ref t, associated) => object(vec![
+                ("type", self.type_(&t.type_)),
+                ("generics", self.generics(&t.generics)),
+                ("associated", Json::Boolean(associated)),
+            ]),
+            clean::StaticItem(ref s) | clean::ForeignStaticItem(ref s) => object(vec![
+                ("type", self.type_(&s.type_)),
+                ("mutable", mutable(s.mutability)),
+                ("expr", string(&s.expr)),
+                ("foreign", Json::Boolean(match item.inner {
+                    clean::ForeignStaticItem(..) => true,
+                    _ => false,
+                })),
+            ]),
+            clean::ConstantItem(ref c) => object(vec![
+                ("type", self.type_(&c.type_)),
+                ("expr", string(&c.expr)),
+            ]),
+            clean::TraitItem(ref t) => object(vec![
+                ("unsafe", Json::Boolean(t.unsafety == ast::Unsafety::Unsafe)),
+                ("generics", self.generics(&t.generics)),
+                ("bounds", self.bounds(&t.bounds)),
+                ("items", self.items(&t.items)),
+            ]),
+            clean::ImplItem(ref i) => object(vec![
+                ("unsafe", Json::Boolean(i.unsafety == ast::Unsafety::Unsafe)),
+                ("generics", self.generics(&i.generics)),
+                ("trait", match i.trait_ {
+                    Some(ref t) => self.type_(t),
+                    None => Json::Null,
+                }),
+                ("for", self.type_(&i.for_)),
+                ("items", self.items(&i.items)),
+                ("derived", Json::Boolean(i.derived)),
+                ("negative", Json::Boolean(i.polarity ==
+                                           Some(clean::ImplPolarity::Negative))),
+            ]),
+            clean::DefaultImplItem(ref i) => object(vec![
+                ("unsafe", Json::Boolean(i.unsafety == ast::Unsafety::Unsafe)),
+                ("trait", self.type_(&i.trait_)),
+            ]),
+            clean::MacroItem(ref m) => object(vec![
+                ("source", string(&m.source)),
+                ("imported_from", opt_string(&m.imported_from)),
+            ]),
+            clean::PrimitiveItem(p) => object(vec![
+                ("name", string(p.to_string())),
+            ]),
+            clean::AssociatedConstItem(ref ty, ref default) => object(vec![
+                ("type", self.type_(ty)),
+                ("default", opt_string(default)),
+            ]),
+            clean::AssociatedTypeItem(ref bounds, ref default) => object(vec![
+                ("bounds", self.bounds(bounds)),
+                ("default", match *default {
+                    Some(ref ty) => self.type_(ty),
+                    None => Json::Null,
+                }),
+            ]),
+        }
+    }
+
+    fn import(&self, import: &clean::Import) -> Json {
+        let source = |s: &clean::ImportSource| object(vec![
+            ("path", self.path(&s.path)),
+            ("id", self.opt_id(s.did)),
+        ]);
+        match *import {
+            clean::SimpleImport(ref name, ref s) => object(vec![
+                ("import_kind", string("simple")),
+                ("name", string(name)),
+                ("source", source(s)),
+            ]),
+            clean::GlobImport(ref s) => object(vec![
+                ("import_kind", string("glob")),
+                ("source", source(s)),
+            ]),
+            clean::ImportList(ref s, ref names) => object(vec![
+                ("import_kind", string("list")),
+                ("source", source(s)),
+                ("names", Json::Array(names.iter().map(|n| object(vec![
+                    ("name", string(&n.name)),
+                    ("id", self.opt_id(n.source)),
+                ])).collect())),
+            ]),
+        }
+    }
+
+    fn opt_id(&self, did: Option<ast::DefId>) -> Json {
+        match did {
+            Some(did) => self.id(did),
+            None => Json::Null,
+        }
+    }
+
+    fn types(&self, tys: &[clean::Type]) -> Json {
+        Json::Array(tys.iter().map(|t| self.type_(t)).collect())
+    }
+
+    fn type_(&self, ty: &clean::Type) -> Json {
+        match *ty {
+            clean::ResolvedPath { ref path, ref typarams, did, is_generic } => {
+                let mut fields = vec![
+                    ("kind", string("resolved_path")),
+                    ("path", self.path(path)),
+                    ("id", self.id(did)),
+                    ("is_generic", Json::Boolean(is_generic)),
+                ];
+                if let Some(ref bounds) = *typarams {
+                    fields.push(("bounds", self.bounds(bounds)));
+                }
+                object(fields)
+            }
+            clean::Generic(ref name) => object(vec![
+                ("kind", string("generic")),
+                ("name", string(name)),
+            ]),
+            clean::Primitive(p) => object(vec![
+                ("kind", string("primitive")),
+                ("name", string(p.to_string())),
+            ]),
+            clean::BareFunction(ref f) => object(vec![
+                ("kind", string("function_pointer")),
+                ("unsafe", Json::Boolean(f.unsafety == ast::Unsafety::Unsafe)),
+                ("abi", string(if f.abi.is_empty() { "Rust" } else { &f.abi })),
+                ("generics", self.generics(&f.generics)),
+                ("decl", self.decl(&f.decl)),
+            ]),
+            clean::Tuple(ref tys) => object(vec![
+                ("kind", string("tuple")),
+                ("types", self.types(tys)),
+            ]),
+            clean::Vector(ref ty) => object(vec![
+                ("kind", string("slice")),
+                ("type", self.type_(ty)),
+            ]),
+            clean::FixedVector(ref ty, ref len) => object(vec![
+                ("kind", string("array")),
+                ("type", self.type_(ty)),
+                ("len", string(len)),
+            ]),
+            clean::Bottom => object(vec![("kind", string("never"))]),
+            clean::Unique(ref ty) => object(vec![
+                ("kind", string("box")),
+                ("type", self.type_(ty)),
+            ]),
+            clean::RawPointer(m, ref ty) => object(vec![
+                ("kind", string("raw_pointer")),
+                ("mutable", mutable(m)),
+                ("type", self.type_(ty)),
+            ]),
+            clean::BorrowedRef { ref lifetime, mutability, ref type_ } => object(vec![
+                ("kind", string("borrowed_ref")),
+                ("lifetime", match *lifetime {
+                    Some(ref l) => string(l.get_ref()),
+                    None => Json::Null,
+                }),
+                ("mutable", mutable(mutability)),
+                ("type", self.type_(type_)),
+            ]),
+            clean::QPath { ref name, ref self_type, ref trait_ } => object(vec![
+                ("kind", string("qualified_path")),
+                ("name", string(name)),
+                ("self_type", self.type_(self_type)),
+                ("trait", self.type_(trait_)),
+            ]),
+            clean::Infer => object(vec![("kind", string("infer"))]),
+            clean::PolyTraitRef(ref bounds) => object(vec![
+                ("kind", string("trait_object")),
+                ("bounds", self.bounds(bounds)),
+            ]),
+        }
+    }
+
+    fn path(&self, path: &clean::Path) -> Json {
+        let segments = path.segments.iter().map(|s| {
+            let args = match s.params {
+                clean::PathParameters::AngleBracketed {
+                    ref lifetimes, ref types, ref bindings
+                } => {
+                    if lifetimes.is_empty() && types.is_empty() && bindings.is_empty() {
+                        Json::Null
+                    } else {
+                        object(vec![
+                            ("kind", string("angle_bracketed")),
+                            ("lifetimes", lifetimes_(lifetimes)),
+                            ("types", self.types(types)),
+                            ("bindings", Json::Array(bindings.iter().map(|b| object(vec![
+                                ("name", string(&b.name)),
+                                ("type", self.type_(&b.ty)),
+                            ])).collect())),
+                        ])
+                    }
+                }
+                clean::PathParameters::Parenthesized { ref inputs, ref output } => object(vec![
+                    ("kind", string("parenthesized")),
+                    ("inputs", self.types(inputs)),
+                    ("output", match *output {
+                        Some(ref ty) => self.type_(ty),
+                        None => Json::Null,
+                    }),
+                ]),
+            };
+            object(vec![
+                ("name", string(&s.name)),
+                ("args", args),
+            ])
+        }).collect();
+        object(vec![
+            ("global", Json::Boolean(path.global)),
+            ("segments", Json::Array(segments)),
+        ])
+    }
+
+    fn bounds(&self, bounds: &[clean::TyParamBound]) -> Json {
+        Json::Array(bounds.iter().map(|b| match *b {
+            clean::RegionBound(ref l) => object(vec![
+                ("kind", string("outlives")),
+                ("lifetime", string(l.get_ref())),
+            ]),
+            clean::TraitBound(ref poly, modifier) => object(vec![
+                ("kind", string("trait")),
+                ("trait", self.type_(&poly.trait_)),
+                ("lifetimes", lifetimes_(&poly.lifetimes)),
+                ("maybe", Json::Boolean(modifier == ast::TraitBoundModifier::Maybe)),
+            ]),
+        }).collect())
+    }
+
+    fn generics(&self, g: &clean::Generics) -> Json {
+        let params = g.type_params.iter().map(|p| object(vec![
+            ("name", string(&p.name)),
+            ("bounds", self.bounds(&p.bounds)),
+            ("default", match p.default {
+                Some(ref ty) => self.type_(ty),
+                None => Json::Null,
+            }),
+        ])).collect();
+        let predicates = g.where_predicates.iter().map(|p| match *p {
+            clean::WherePredicate::BoundPredicate { ref ty, ref bounds } => object(vec![
+                ("kind", string("bound")),
+                ("type", self.type_(ty)),
+                ("bounds", self.bounds(bounds)),
+            ]),
+            clean::WherePredicate::RegionPredicate { ref lifetime, ref bounds } => object(vec![
+                ("kind", string("region")),
+                ("lifetime", string(lifetime.get_ref())),
+                ("bounds", lifetimes_(bounds)),
+            ]),
+            clean::WherePredicate::EqPredicate { ref lhs, ref rhs } => object(vec![
+                ("kind", string("eq")),
+                ("lhs", self.type_(lhs)),
+                ("rhs", self.type_(rhs)),
+            ]),
+        }).collect();
+        object(vec![
+            ("lifetimes", lifetimes_(&g.lifetimes)),
+            ("params", Json::Array(params)),
+            ("where_predicates", Json::Array(predicates)),
+        ])
+    }
+
+    fn decl(&self, decl: &clean::FnDecl) -> Json {
+        let inputs = decl.inputs.values.iter().map(|a| object(vec![
+            ("name", string(&a.name)),
+            ("type", self.type_(&a.type_)),
+        ])).collect();
+        object(vec![
+            ("inputs", Json::Array(inputs)),
+            ("output", match decl.output {
+                clean::Return(ref ty) => self.type_(ty),
+                clean::DefaultReturn => Json::Null,
+                clean::NoReturn => object(vec![("kind", string("never"))]),
+            }),
+        ])
+    }
+
+    fn self_ty(&self, s: &clean::SelfTy) -> Json {
+        match *s {
+            clean::SelfStatic => Json::Null,
+            clean::SelfValue => object(vec![("kind", string("value"))]),
+            clean::SelfBorrowed(ref lifetime, m) => object(vec![
+                ("kind", string("borrowed")),
+                ("lifetime", match *lifetime {
+                    Some(ref l) => string(l.get_ref()),
+                    None => Json::Null,
+                }),
+                ("mutable", mutable(m)),
+            ]),
+            clean::SelfExplicit(ref ty) => object(vec![
+                ("kind", string("explicit")),
+                ("type", self.type_(ty)),
+            ]),
+        }
+    }
+}
+
+fn object(fields: Vec<(&str, Json)>) -> Json {
+    Json::Object(fields.into_iter().map(|(k, v)| (k.to_string(), v)).collect())
+}
+
+fn string(s: &str) -> Json {
+    Json::String(s.to_string())
+}
+
+fn opt_string(s: &Option<String>) -> Json {
+    match *s {
+        Some(ref s) => string(s),
+        None => Json::Null,
+    }
+}
+
+fn lifetimes_(lifetimes: &[clean::Lifetime]) -> Json {
+    Json::Array(lifetimes.iter().map(|l| string(l.get_ref())).collect())
+}
+
+fn mutable(m: clean::Mutability) -> Json {
+    Json::Boolean(m == clean::Mutable)
+}
+
+fn abi_(abi: abi::Abi) -> Json {
+    string(abi.name())
+}
+
+fn struct_type(t: doctree::StructType) -> Json {
+    string(match t {
+        doctree::Plain => "plain",
+        doctree::Tuple => "tuple",
+        doctree::Newtype => "newtype",
+        doctree::Unit => "unit",
+    })
+}
@@ -117,6 +117,7 @@ fn try_inline_def(cx: &DocContext, tcx: &ty::ctxt,
     cx.inlined.borrow_mut().as_mut().unwrap().insert(did);
     ret.push(clean::Item {
         source: clean::Span::empty(),
+        doc_source: clean::Span::empty(),
         name: Some(fqn.last().unwrap().to_string()),
         attrs: load_attrs(cx, tcx, did),
         inner: inner,
@@ -299,6 +300,7 @@ pub fn build_impl(cx: &DocContext,
                 },
             }),
             source: clean::Span::empty(),
+            doc_source: clean::Span::empty(),
             name: None,
             attrs: attrs,
             visibility: Some(ast::Inherited),
@@ -329,6 +331,7 @@ pub fn build_impl(cx: &DocContext,
                         default,
                     ),
                     source: clean::Span::empty(),
+                    doc_source: clean::Span::empty(),
                     attrs: vec![],
                     visibility: None,
                     stability: stability::lookup(tcx, did).clean(cx),
@@ -374,6 +377,7 @@ pub fn build_impl(cx: &DocContext,
                     name: Some(assoc_ty.name.clean(cx)),
                     inner: clean::TypedefItem(typedef, true),
                     source: clean::Span::empty(),
+                    doc_source: clean::Span::empty(),
                     attrs: vec![],
                     visibility: None,
                     stability: stability::lookup(tcx, did).clean(cx),
@@ -406,6 +410,7 @@ pub fn build_impl(cx: &DocContext,
             polarity: polarity.map(|p| { p.clean(cx) }),
         }),
         source: clean::Span::empty(),
+        doc_source: clean::Span::empty(),
         name: None,
         attrs: attrs,
         visibility: Some(ast::Inherited),
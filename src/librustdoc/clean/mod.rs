@@ -182,6 +182,7 @@ impl<'a, 'tcx> Clean<Crate> for visit_ast::RustdocVisitor<'a, 'tcx> {
                 primitives.push(prim);
                 tmp.push(Item {
                     source: Span::empty(),
+                    doc_source: Span::empty(),
                     name: Some(prim.to_url_str().to_string()),
                     attrs: child.attrs.clone(),
                     visibility: Some(ast::Public),
@@ -247,6 +248,8 @@ impl Clean<ExternalCrate> for cstore::crate_metadata {
 pub struct Item {
     /// Stringified span
     pub source: Span,
+    /// Stringified span of the doc comment, empty if there is none
+    pub doc_source: Span,
     /// Not everything has a name. E.g., impls
     pub name: Option<String>,
     pub attrs: Vec<Attribute> ,
@@ -415,6 +418,7 @@ impl Clean<Item> for doctree::Module {
             name: Some(name),
             attrs: self.attrs.clean(cx),
             source: whence.clean(cx),
+            doc_source: doc_source(cx, &self.attrs),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
             def_id: ast_util::local_def(self.id),
@@ -1078,6 +1082,7 @@ impl Clean<Item> for doctree::Function {
             name: Some(self.name.clean(cx)),
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
+            doc_source: doc_source(cx, &self.attrs),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
             def_id: ast_util::local_def(self.id),
@@ -1200,6 +1205,7 @@ impl Clean<Item> for doctree::Trait {
             name: Some(self.name.clean(cx)),
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
+            doc_source: doc_source(cx, &self.attrs),
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
@@ -1250,6 +1256,7 @@ impl Clean<Item> for ast::TraitItem {
             name: Some(self.ident.clean(cx)),
             attrs: self.attrs.clean(cx),
             source: self.span.clean(cx),
+            doc_source: doc_source(cx, &self.attrs),
             def_id: ast_util::local_def(self.id),
             visibility: None,
             stability: get_stability(cx, ast_util::local_def(self.id)),
@@ -1288,6 +1295,7 @@ impl Clean<Item> for ast::ImplItem {
         Item {
             name: Some(self.ident.clean(cx)),
             source: self.span.clean(cx),
+            doc_source: doc_source(cx, &self.attrs),
             attrs: self.attrs.clean(cx),
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
@@ -1365,6 +1373,7 @@ impl<'tcx> Clean<Item> for ty::Method<'tcx> {
             def_id: self.def_id,
             attrs: inline::load_attrs(cx, cx.tcx(), self.def_id),
             source: Span::empty(),
+            doc_source: Span::empty(),
             inner: inner,
         }
     }
@@ -1721,6 +1730,7 @@ impl Clean<Item> for ast::StructField {
             name: name.clean(cx),
             attrs: self.node.attrs.clean(cx),
             source: self.span.clean(cx),
+            doc_source: doc_source(cx, &self.node.attrs),
             visibility: Some(vis),
             stability: get_stability(cx, ast_util::local_def(self.node.id)),
             def_id: ast_util::local_def(self.node.id),
@@ -1748,6 +1758,7 @@ impl Clean<Item> for ty::FieldTy {
             name: name.clean(cx),
             attrs: attrs.unwrap_or(&Vec::new()).clean(cx),
             source: Span::empty(),
+            doc_source: Span::empty(),
             visibility: Some(self.vis),
             stability: get_stability(cx, self.id),
             def_id: self.id,
@@ -1778,6 +1789,7 @@ impl Clean<Item> for doctree::Struct {
             name: Some(self.name.clean(cx)),
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
+            doc_source: doc_source(cx, &self.attrs),
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
@@ -1824,6 +1836,7 @@ impl Clean<Item> for doctree::Enum {
             name: Some(self.name.clean(cx)),
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
+            doc_source: doc_source(cx, &self.attrs),
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
@@ -1847,6 +1860,7 @@ impl Clean<Item> for doctree::Variant {
             name: Some(self.name.clean(cx)),
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
+            doc_source: doc_source(cx, &self.attrs),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
             def_id: ast_util::local_def(self.id),
@@ -1872,6 +1886,7 @@ impl<'tcx> Clean<Item> for ty::VariantInfo<'tcx> {
                     fields: s.iter().zip(&self.args).map(|(name, ty)| {
                         Item {
                             source: Span::empty(),
+                            doc_source: Span::empty(),
                             name: Some(name.clean(cx)),
                             attrs: Vec::new(),
                             visibility: Some(ast::Public),
@@ -1896,6 +1911,7 @@ impl<'tcx> Clean<Item> for ty::VariantInfo<'tcx> {
             name: Some(self.name.clean(cx)),
             attrs: inline::load_attrs(cx, cx.tcx(), self.id),
             source: Span::empty(),
+            doc_source: Span::empty(),
             visibility: Some(ast::Public),
             def_id: self.id,
             inner: VariantItem(Variant { kind: kind }),
@@ -1945,6 +1961,19 @@ impl Span {
     }
 }
 
+/// The span of the documentation in `attrs`, from the first doc comment or
+/// `#[doc = "..."]` attribute to the last.
+fn doc_source(cx: &DocContext, attrs: &[ast::Attribute]) -> Span {
+    let mut docs = attrs.iter().filter(|a| a.check_name("doc") && a.value_str().is_some());
+    match docs.next() {
+        Some(first) => {
+            let hi = docs.last().unwrap_or(first).span.hi;
+            codemap::mk_sp(first.span.lo, hi).clean(cx)
+        }
+        None => Span::empty(),
+    }
+}
+
 impl Clean<Span> for syntax::codemap::Span {
     fn clean(&self, cx: &DocContext) -> Span {
         let cm = cx.sess().codemap();
@@ -2079,6 +2108,7 @@ impl Clean<Item> for doctree::Typedef {
             name: Some(self.name.clean(cx)),
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
+            doc_source: doc_source(cx, &self.attrs),
             def_id: ast_util::local_def(self.id.clone()),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
@@ -2130,6 +2160,7 @@ impl Clean<Item> for doctree::Static {
             name: Some(self.name.clean(cx)),
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
+            doc_source: doc_source(cx, &self.attrs),
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
@@ -2154,6 +2185,7 @@ impl Clean<Item> for doctree::Constant {
             name: Some(self.name.clean(cx)),
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
+            doc_source: doc_source(cx, &self.attrs),
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
@@ -2228,6 +2260,7 @@ impl Clean<Vec<Item>> for doctree::Impl {
             name: None,
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
+            doc_source: doc_source(cx, &self.attrs),
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: self.stab.clean(cx),
@@ -2310,6 +2343,7 @@ impl Clean<Item> for doctree::DefaultImpl {
             name: None,
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
+            doc_source: doc_source(cx, &self.attrs),
             def_id: ast_util::local_def(self.id),
             visibility: Some(ast::Public),
             stability: None,
@@ -2327,6 +2361,7 @@ impl Clean<Item> for doctree::ExternCrate {
             name: None,
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
+            doc_source: doc_source(cx, &self.attrs),
             def_id: ast_util::local_def(0),
             visibility: self.vis.clean(cx),
             stability: None,
@@ -2392,6 +2427,7 @@ impl Clean<Vec<Item>> for doctree::Import {
             name: None,
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
+            doc_source: doc_source(cx, &self.attrs),
             def_id: ast_util::local_def(0),
             visibility: self.vis.clean(cx),
             stability: None,
@@ -2475,6 +2511,7 @@ impl Clean<Item> for ast::ForeignItem {
             name: Some(self.ident.clean(cx)),
             attrs: self.attrs.clean(cx),
             source: self.span.clean(cx),
+            doc_source: doc_source(cx, &self.attrs),
             def_id: ast_util::local_def(self.id),
             visibility: self.vis.clean(cx),
             stability: get_stability(cx, ast_util::local_def(self.id)),
@@ -2646,6 +2683,98 @@ fn resolve_def(cx: &DocContext, id: ast::NodeId) -> Option<ast::DefId> {
     })
 }
 
+/// What `use` brings into scope in the modules of a crate, as found by the
+/// resolver. Rustdoc looks the paths written in links in documentation up in
+/// it.
+#[derive(Default)]
+pub struct Imports {
+    /// The imports of each module, by module. External modules imported with
+    /// `*` are listed too, with their public items as their names.
+    pub modules: HashMap<ast::DefId, ModuleImports>,
+    /// The modules imported with `*` into every module: the prelude.
+    pub prelude: Vec<ast::DefId>,
+}
+
+#[derive(Default)]
+pub struct ModuleImports {
+    /// The items imported by name, by the name they are imported as.
+    pub names: HashMap<String, ast::DefId>,
+    /// The modules imported with `*`.
+    pub globs: Vec<ast::DefId>,
+}
+
+/// Finds the imports of every module of the crate in the `def_map`, where the
+/// resolver records what each `use` refers to.
+pub fn collect_imports(cx: &DocContext) -> Imports {
+    let mut imports = Imports::default();
+    collect_module_imports(cx, ast::CRATE_NODE_ID, &cx.krate.module, &mut imports);
+
+    let tcx = match cx.tcx_opt() {
+        Some(tcx) => tcx,
+        None => return imports,
+    };
+    let mut globs: Vec<ast::DefId> = imports.modules.values()
+                                            .flat_map(|m| m.globs.iter().cloned())
+                                            .chain(imports.prelude.iter().cloned())
+                                            .filter(|did| !ast_util::is_local(*did))
+                                            .collect();
+    globs.sort();
+    globs.dedup();
+    for did in globs {
+        let mut names = HashMap::new();
+        csearch::each_child_of_item(&tcx.sess.cstore, did, |def, name, vis| {
+            if let (decoder::DlDef(def), ast::Public) = (def, vis) {
+                names.insert(name.clean(cx), register_def(cx, def));
+            }
+        });
+        imports.modules.insert(did, ModuleImports { names: names, globs: Vec::new() });
+    }
+    imports
+}
+
+fn collect_module_imports(cx: &DocContext, id: ast::NodeId, m: &ast::Mod,
+                          imports: &mut Imports) {
+    let imported = |id: ast::NodeId| resolve_def(cx, id);
+    let mut module = ModuleImports::default();
+    for item in &m.items {
+        match item.node {
+            ast::ItemMod(ref m) => collect_module_imports(cx, item.id, m, imports),
+            ast::ItemUse(ref vp) => match vp.node {
+                ast::ViewPathSimple(ident, _) => {
+                    if let Some(did) = imported(item.id) {
+                        module.names.insert(ident.clean(cx), did);
+                    }
+                }
+                ast::ViewPathList(ref path, ref list) => {
+                    for pli in list {
+                        let name = match pli.node {
+                            ast::PathListIdent { name, .. } => name,
+                            ast::PathListMod { .. } => match path.segments.last() {
+                                Some(segment) => segment.identifier,
+                                None => continue,
+                            },
+                        };
+                        if let Some(did) = imported(pli.node.id()) {
+                            module.names.insert(name.clean(cx), did);
+                        }
+                    }
+                }
+                ast::ViewPathGlob(_) => {
+                    if let Some(did) = imported(item.id) {
+                        if attr::contains_name(&item.attrs, "prelude_import") {
+                            imports.prelude.push(did);
+                        } else {
+                            module.globs.push(did);
+                        }
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+    imports.modules.insert(ast_util::local_def(id), module);
+}
+
 #[derive(Clone, RustcEncodable, RustcDecodable, Debug)]
 pub struct Macro {
     pub source: String,
@@ -2658,6 +2787,7 @@ impl Clean<Item> for doctree::Macro {
             name: Some(format!("{}!", self.name.clean(cx))),
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
+            doc_source: doc_source(cx, &self.attrs),
             visibility: ast::Public.clean(cx),
             stability: self.stab.clean(cx),
             def_id: ast_util::local_def(self.id),
@@ -2712,6 +2842,7 @@ impl<'tcx> Clean<Item> for ty::AssociatedConst<'tcx> {
     fn clean(&self, cx: &DocContext) -> Item {
         Item {
             source: DUMMY_SP.clean(cx),
+            doc_source: Span::empty(),
             name: Some(self.name.clean(cx)),
             attrs: Vec::new(),
             inner: AssociatedConstItem(self.ty.clean(cx), None),
@@ -2769,6 +2900,7 @@ impl<'tcx> Clean<Item> for ty::AssociatedType<'tcx> {
 
         Item {
             source: DUMMY_SP.clean(cx),
+            doc_source: Span::empty(),
             name: Some(self.name.clean(cx)),
             attrs: inline::load_attrs(cx, cx.tcx(), self.def_id),
             inner: AssociatedTypeItem(bounds, self.ty.clean(cx)),
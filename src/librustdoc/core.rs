@@ -80,6 +80,7 @@ pub struct CrateAnalysis {
     pub external_typarams: RefCell<Option<HashMap<ast::DefId, String>>>,
     pub inlined: RefCell<Option<HashSet<ast::DefId>>>,
     pub deref_trait_did: Option<ast::DefId>,
+    pub imports: RefCell<Option<clean::Imports>>,
 }
 
 pub type Externs = HashMap<String, Vec<String>>;
@@ -161,6 +162,7 @@ pub fn run_core(search_paths: SearchPaths, cfgs: Vec<String>, externs: Externs,
             external_typarams: RefCell::new(None),
             inlined: RefCell::new(None),
             deref_trait_did: None,
+            imports: RefCell::new(None),
         };
 
         let krate = {
@@ -169,6 +171,9 @@ pub fn run_core(search_paths: SearchPaths, cfgs: Vec<String>, externs: Externs,
             v.clean(&ctxt)
         };
 
+        // Imports of external items add to the external paths.
+        *analysis.imports.borrow_mut() = Some(clean::collect_imports(&ctxt));
+
         let external_paths = ctxt.external_paths.borrow_mut().take();
         *analysis.external_paths.borrow_mut() = external_paths;
         let map = ctxt.external_typarams.borrow_mut().take();
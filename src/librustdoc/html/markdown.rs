@@ -30,16 +30,18 @@
 use libc;
 use std::ascii::AsciiExt;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::default::Default;
 use std::ffi::CString;
 use std::fmt;
+use std::mem;
 use std::slice;
 use std::str;
 
 use html::toc::TocBuilder;
 use html::highlight;
 use html::escape::Escape;
+use html::render;
 use test;
 
 /// A unit struct which has the `fmt::String` trait implemented. When
@@ -134,7 +136,10 @@ struct html_toc_data {
 struct MyOpaque {
     dfltblk: extern "C" fn(*mut hoedown_buffer, *const hoedown_buffer,
                            *const hoedown_buffer, *mut libc::c_void),
+    dfltlink: linkfn,
     toc_builder: Option<TocBuilder>,
+    /// Item paths which `add_item_link_definitions` turned into link targets.
+    item_links: HashSet<String>,
 }
 
 #[repr(C)]
@@ -210,6 +215,81 @@ thread_local!(pub static PLAYGROUND_KRATE: RefCell<Option<Option<String>>> = {
     RefCell::new(None)
 });
 
+thread_local!(static UNRESOLVED_LINKS: RefCell<Vec<String>> = {
+    RefCell::new(Vec::new())
+});
+
+/// Returns the item paths used as link targets since the last call which
+/// could not be resolved to the documentation of an item.
+pub fn take_unresolved_links() -> Vec<String> {
+    UNRESOLVED_LINKS.with(|links| mem::replace(&mut *links.borrow_mut(), Vec::new()))
+}
+
+/// Returns whether `s` looks like the path of an item, such as `Vec`,
+/// `self::foo` or `::std::vec::Vec::new`.
+fn is_item_path(s: &str) -> bool {
+    let s = if s.starts_with("::") { &s[2..] } else { s };
+    s.split("::").all(|segment| {
+        let mut chars = segment.chars();
+        match chars.next() {
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                chars.all(|c| c.is_alphanumeric() || c == '_')
+            }
+            _ => false,
+        }
+    })
+}
+
+/// Adds a reference definition for each shortcut link in `md`, like `[Vec]`
+/// or [`Vec::new()`], whose text is an item path, so that the link goes to
+/// the path and is resolved when rendered. Plain text in brackets is only
+/// taken as a link if it resolves; code or text with a `::` always is. The
+/// paths are added to `targets`.
+fn add_item_link_definitions(md: &str, targets: &mut HashSet<String>) -> String {
+    let bytes = md.as_bytes();
+    let mut labels = HashSet::new();
+    let mut defs = String::new();
+    let mut start = 0;
+    while let Some(open) = md[start..].find('[') {
+        let open = start + open;
+        start = open + 1;
+        let close = match md[open + 1..].find(|c: char| c == ']' || c == '[' || c == '\n') {
+            Some(i) if bytes[open + 1 + i] == b']' => open + 1 + i,
+            _ => continue,
+        };
+        // Skip images, escaped brackets, inline and reference links and
+        // reference definitions.
+        if open > 0 && (bytes[open - 1] == b'!' || bytes[open - 1] == b'\\' ||
+                        bytes[open - 1] == b']') {
+            continue
+        }
+        match bytes.get(close + 1) {
+            Some(&b'(') | Some(&b'[') | Some(&b':') => continue,
+            _ => {}
+        }
+
+        let label = &md[open + 1..close];
+        let code = label.len() > 2 && label.starts_with('`') && label.ends_with('`');
+        let path = if code { &label[1..label.len() - 1] } else { label };
+        let path = if path.ends_with("()") { &path[..path.len() - 2] } else { path };
+        if path.is_empty() || !is_item_path(path) || labels.contains(label) ||
+           md.contains(&format!("[{}]:", label)) {
+            continue
+        }
+        if !code && !path.contains("::") && render::resolve_item_link(path).is_none() {
+            continue
+        }
+        labels.insert(label);
+        targets.insert(path.to_string());
+        defs.push_str(&format!("\n[{}]: {}", label, path));
+    }
+    if defs.is_empty() {
+        md.to_string()
+    } else {
+        format!("{}\n{}\n", md, defs)
+    }
+}
+
 pub fn render(w: &mut fmt::Formatter, s: &str, print_toc: bool) -> fmt::Result {
     extern fn block(ob: *mut hoedown_buffer, orig_text: *const hoedown_buffer,
                     lang: *const hoedown_buffer, opaque: *mut libc::c_void) {
@@ -331,18 +411,69 @@ pub fn render(w: &mut fmt::Formatter, s: &str, print_toc: bool) -> fmt::Result {
         unsafe { hoedown_buffer_puts(ob, element.as_ptr()); }
     }
 
+    // Links to item paths go to the documentation of the item.
+    extern fn link(ob: *mut hoedown_buffer, link: *const hoedown_buffer,
+                   title: *const hoedown_buffer, content: *const hoedown_buffer,
+                   opaque: *mut libc::c_void) -> libc::c_int {
+        unsafe {
+            let state = opaque as *mut hoedown_html_renderer_state;
+            let my_opaque: &MyOpaque = &*((*state).opaque as *const MyOpaque);
+            let target = if link.is_null() {
+                ""
+            } else {
+                str::from_utf8((*link).as_bytes()).unwrap_or("")
+            };
+            if !target.is_empty() && is_item_path(target) {
+                if let Some(url) = render::resolve_item_link(target) {
+                    let url = CString::new(url).unwrap();
+                    let buf = hoedown_buffer_new(DEF_OUNIT);
+                    hoedown_buffer_puts(buf, url.as_ptr());
+                    let ret = (my_opaque.dfltlink)(ob, buf, title, content, opaque);
+                    hoedown_buffer_free(buf);
+                    return ret
+                }
+                let generated = my_opaque.item_links.contains(target);
+                if generated || target.contains("::") {
+                    UNRESOLVED_LINKS.with(|links| links.borrow_mut().push(target.to_string()));
+                }
+                // A shortcut link which doesn't go anywhere is left as text.
+                if generated {
+                    if !content.is_null() {
+                        hoedown_buffer_put(ob, (*content).data as *const libc::c_char,
+                                           (*content).size);
+                    }
+                    return 1
+                }
+            }
+            (my_opaque.dfltlink)(ob, link, title, content, opaque)
+        }
+    }
+
     unsafe {
+        // Item paths only mean something in the documentation of a crate.
+        let mut item_links = HashSet::new();
+        let in_crate = render::CURRENT_LOCATION_KEY.with(|loc| !loc.borrow().is_empty());
+        let with_links;
+        let s = if in_crate {
+            with_links = add_item_link_definitions(s, &mut item_links);
+            &with_links[..]
+        } else {
+            s
+        };
         let ob = hoedown_buffer_new(DEF_OUNIT);
         let renderer = hoedown_html_renderer_new(0, 0);
         let mut opaque = MyOpaque {
             dfltblk: (*renderer).blockcode.unwrap(),
-            toc_builder: if print_toc {Some(TocBuilder::new())} else {None}
+            dfltlink: (*renderer).link.unwrap(),
+            toc_builder: if print_toc {Some(TocBuilder::new())} else {None},
+            item_links: item_links,
         };
         (*((*renderer).opaque as *mut hoedown_html_renderer_state)).opaque
                 = &mut opaque as *mut _ as *mut libc::c_void;
         (*renderer).blockcode = Some(block);
         (*renderer).header = Some(header);
         (*renderer).codespan = Some(codespan);
+        (*renderer).link = Some(link);
 
         let document = hoedown_document_new(renderer, HOEDOWN_EXTENSIONS, 16);
         hoedown_document_render(document, ob, s.as_ptr(),
@@ -365,6 +496,30 @@ pub fn render(w: &mut fmt::Formatter, s: &str, print_toc: bool) -> fmt::Result {
     }
 }
 
+/// What `find_testable_code` passes to hoedown's callbacks.
+struct TestableCode<'a> {
+    tests: &'a mut ::test::Collector,
+    doc: &'a str,
+    /// How far the code blocks have been found in `doc`.
+    searched: usize,
+}
+
+impl<'a> TestableCode<'a> {
+    /// Returns the line of `doc`, counting from 0, on which the code block
+    /// `code` found after the previous ones starts.
+    fn line_of(&mut self, code: &str) -> usize {
+        let first = code.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+        match self.doc[self.searched..].find(first) {
+            Some(i) => {
+                let start = self.searched + i;
+                self.searched = start + first.len();
+                self.doc[..start].matches('\n').count()
+            }
+            None => 0,
+        }
+    }
+}
+
 pub fn find_testable_code(doc: &str, tests: &mut ::test::Collector) {
     extern fn block(_ob: *mut hoedown_buffer,
                     text: *const hoedown_buffer,
@@ -382,15 +537,14 @@ pub fn find_testable_code(doc: &str, tests: &mut ::test::Collector) {
             if !block_info.rust { return }
             let text = (*text).as_bytes();
             let opaque = opaque as *mut hoedown_html_renderer_state;
-            let tests = &mut *((*opaque).opaque as *mut ::test::Collector);
+            let code = &mut *((*opaque).opaque as *mut TestableCode);
             let text = str::from_utf8(text).unwrap();
+            let line = code.line_of(text);
             let lines = text.lines().map(|l| {
                 stripped_filtered_line(l).unwrap_or(l)
             });
             let text = lines.collect::<Vec<&str>>().join("\n");
-            tests.add_test(text.to_string(),
-                           block_info.should_panic, block_info.no_run,
-                           block_info.ignore, block_info.test_harness);
+            code.tests.add_test(text.to_string(), line, block_info);
         }
     }
 
@@ -399,24 +553,62 @@ pub fn find_testable_code(doc: &str, tests: &mut ::test::Collector) {
                      level: libc::c_int, opaque: *mut libc::c_void) {
         unsafe {
             let opaque = opaque as *mut hoedown_html_renderer_state;
-            let tests = &mut *((*opaque).opaque as *mut ::test::Collector);
+            let code = &mut *((*opaque).opaque as *mut TestableCode);
             if text.is_null() {
-                tests.register_header("", level as u32);
+                code.tests.register_header("", level as u32);
             } else {
                 let text = (*text).as_bytes();
                 let text = str::from_utf8(text).unwrap();
-                tests.register_header(text, level as u32);
+                code.tests.register_header(text, level as u32);
             }
         }
     }
 
+    let mut code = TestableCode { tests: tests, doc: doc, searched: 0 };
     unsafe {
         let ob = hoedown_buffer_new(DEF_OUNIT);
         let renderer = hoedown_html_renderer_new(0, 0);
         (*renderer).blockcode = Some(block);
         (*renderer).header = Some(header);
         (*((*renderer).opaque as *mut hoedown_html_renderer_state)).opaque
-                = tests as *mut _ as *mut libc::c_void;
+                = &mut code as *mut _ as *mut libc::c_void;
+
+        let document = hoedown_document_new(renderer, HOEDOWN_EXTENSIONS, 16);
+        hoedown_document_render(document, ob, doc.as_ptr(),
+                                doc.len() as libc::size_t);
+        hoedown_document_free(document);
+
+        hoedown_html_renderer_free(renderer);
+        hoedown_buffer_free(ob);
+    }
+}
+
+/// Returns whether `doc` has a Rust code block, as would be tested by
+/// `rustdoc --test`.
+pub fn has_rust_code(doc: &str) -> bool {
+    extern fn block(_ob: *mut hoedown_buffer,
+                    text: *const hoedown_buffer,
+                    lang: *const hoedown_buffer,
+                    opaque: *mut libc::c_void) {
+        unsafe {
+            if text.is_null() { return }
+            let rust = lang.is_null() || {
+                let lang = (*lang).as_bytes();
+                LangString::parse(str::from_utf8(lang).unwrap()).rust
+            };
+            let opaque = opaque as *mut hoedown_html_renderer_state;
+            let found = &mut *((*opaque).opaque as *mut bool);
+            *found = *found || rust;
+        }
+    }
+
+    let mut found = false;
+    unsafe {
+        let ob = hoedown_buffer_new(DEF_OUNIT);
+        let renderer = hoedown_html_renderer_new(0, 0);
+        (*renderer).blockcode = Some(block);
+        (*((*renderer).opaque as *mut hoedown_html_renderer_state)).opaque
+                = &mut found as *mut _ as *mut libc::c_void;
 
         let document = hoedown_document_new(renderer, HOEDOWN_EXTENSIONS, 16);
         hoedown_document_render(document, ob, doc.as_ptr(),
@@ -426,15 +618,24 @@ pub fn find_testable_code(doc: &str, tests: &mut ::test::Collector) {
         hoedown_html_renderer_free(renderer);
         hoedown_buffer_free(ob);
     }
+    found
 }
 
+/// The attributes of a code block, from the tokens after its opening fence,
+/// like `rust,no_run` or `compile_fail,E0382`.
 #[derive(Eq, PartialEq, Clone, Debug)]
-struct LangString {
-    should_panic: bool,
-    no_run: bool,
-    ignore: bool,
-    rust: bool,
-    test_harness: bool,
+pub struct LangString {
+    pub should_panic: bool,
+    pub no_run: bool,
+    pub ignore: bool,
+    pub rust: bool,
+    pub test_harness: bool,
+    pub compile_fail: bool,
+    /// The error codes a `compile_fail` block must fail with.
+    pub error_codes: Vec<String>,
+    /// Arguments to pass to rustc when compiling the block, given as
+    /// `rustc-flag=ARG` tokens.
+    pub rustc_flags: Vec<String>,
 }
 
 impl LangString {
@@ -445,6 +646,9 @@ impl LangString {
             ignore: false,
             rust: true,  // NB This used to be `notrust = false`
             test_harness: false,
+            compile_fail: false,
+            error_codes: Vec::new(),
+            rustc_flags: Vec::new(),
         }
     }
 
@@ -454,7 +658,7 @@ impl LangString {
         let mut data = LangString::all_false();
 
         let tokens = string.split(|c: char|
-            !(c == '_' || c == '-' || c.is_alphanumeric())
+            !(c == '_' || c == '-' || c == '=' || c.is_alphanumeric())
         );
 
         for token in tokens {
@@ -465,6 +669,15 @@ impl LangString {
                 "ignore" => { data.ignore = true; seen_rust_tags = true; },
                 "rust" => { data.rust = true; seen_rust_tags = true; },
                 "test_harness" => { data.test_harness = true; seen_rust_tags = true; }
+                "compile_fail" => { data.compile_fail = true; seen_rust_tags = true; }
+                x if is_error_code(x) => {
+                    data.error_codes.push(x.to_string());
+                    seen_rust_tags = true;
+                }
+                x if x.starts_with("rustc-flag=") => {
+                    data.rustc_flags.push(x["rustc-flag=".len()..].to_string());
+                    seen_rust_tags = true;
+                }
                 _ => { seen_other_tags = true }
             }
         }
@@ -475,6 +688,11 @@ impl LangString {
     }
 }
 
+/// Returns whether `s` is an error code, like `E0382`.
+fn is_error_code(s: &str) -> bool {
+    s.len() == 5 && s.starts_with('E') && s[1..].chars().all(|c| c.is_digit(10))
+}
+
 /// By default this markdown renderer generates anchors for each header in the
 /// rendered document. The anchor name is the contents of the header separated
 /// by hyphens, and a thread-local map is used to disambiguate among duplicate
@@ -557,6 +775,8 @@ pub fn plain_summary_line(md: &str) -> String {
 mod tests {
     use super::{LangString, Markdown};
     use super::{collapse_whitespace, plain_summary_line};
+    use super::{add_item_link_definitions, is_item_path};
+    use std::collections::HashSet;
 
     #[test]
     fn test_lang_string_parse() {
@@ -568,6 +788,9 @@ mod tests {
                 ignore: ignore,
                 rust: rust,
                 test_harness: test_harness,
+                compile_fail: false,
+                error_codes: Vec::new(),
+                rustc_flags: Vec::new(),
             })
         }
 
@@ -585,6 +808,21 @@ mod tests {
         t("{.test_harness .rust}", false,        false,   false,   true,  true);
     }
 
+    #[test]
+    fn test_lang_string_parse_compile_fail_and_flags() {
+        let s = LangString::parse("compile_fail,E0382,E0499");
+        assert!(s.compile_fail && s.rust);
+        assert_eq!(s.error_codes, ["E0382", "E0499"]);
+
+        let s = LangString::parse("rust,rustc-flag=--cfg=foo rustc-flag=-Copt-level=2");
+        assert!(s.rust && !s.compile_fail);
+        assert_eq!(s.rustc_flags, ["--cfg=foo", "-Copt-level=2"]);
+
+        assert!(LangString::parse("E0382").rust);
+        assert!(!LangString::parse("E038").rust);
+        assert!(!LangString::parse("sh,flag=x").rust);
+    }
+
     #[test]
     fn issue_17736() {
         let markdown = "# title";
@@ -618,4 +856,35 @@ mod tests {
         t("\tfoo   bar\nbaz", "foo bar baz");
         t("foo   bar \n   baz\t\tqux\n", "foo bar baz qux");
     }
+
+    #[test]
+    fn test_is_item_path() {
+        assert!(is_item_path("Vec"));
+        assert!(is_item_path("self::foo"));
+        assert!(is_item_path("::std::vec::Vec::new"));
+        assert!(!is_item_path(""));
+        assert!(!is_item_path("::"));
+        assert!(!is_item_path("struct.Foo.html"));
+        assert!(!is_item_path("http://rust-lang.org"));
+        assert!(!is_item_path("Vec<T>"));
+    }
+
+    #[test]
+    fn test_add_item_link_definitions() {
+        fn t(input: &str, expected: &str, paths: &[&str]) {
+            let mut targets = HashSet::new();
+            assert_eq!(add_item_link_definitions(input, &mut targets), expected);
+            assert_eq!(targets, paths.iter().map(|s| s.to_string()).collect());
+        }
+
+        t("see [`Foo`]", "see [`Foo`]\n\n[`Foo`]: Foo\n", &["Foo"]);
+        t("see [`foo()`] and [a::B]",
+          "see [`foo()`] and [a::B]\n\n[`foo()`]: foo\n[a::B]: a::B\n",
+          &["foo", "a::B"]);
+        // Outside of a crate plain words don't resolve, so aren't links.
+        t("a [note]", "a [note]", &[]);
+        t("[`Foo`](bar) [`Foo`][x] ![`Foo`] [`Foo`]: x",
+          "[`Foo`](bar) [`Foo`][x] ![`Foo`] [`Foo`]: x", &[]);
+        t("[`Vec<T>`]", "[`Vec<T>`]", &[]);
+    }
 }
@@ -49,10 +49,12 @@ use std::path::{PathBuf, Path};
 use std::str;
 use std::sync::Arc;
 
+use core;
 use externalfiles::ExternalHtml;
 
 use serialize::json::{self, ToJson};
-use syntax::{abi, ast, ast_util, attr};
+use syntax::{abi, ast, ast_util, attr, diagnostic};
+use syntax::codemap::{self, BytePos, CodeMap, Pos};
 use rustc::util::nodemap::NodeSet;
 
 use clean::{self, SelfTy};
@@ -128,6 +130,7 @@ pub struct Implementor {
 /// Metadata about implementations for a type.
 #[derive(Clone)]
 pub struct Impl {
+    pub def_id: ast::DefId,
     pub impl_: clean::Impl,
     pub dox: Option<String>,
     pub stability: Option<clean::Stability>,
@@ -212,6 +215,15 @@ pub struct Cache {
     // yet when its implementation methods are being indexed. Caches such methods
     // and their parent id here and indexes them at the end of crate parsing.
     orphan_methods: Vec<(ast::NodeId, clean::Item)>,
+
+    // Lookup tables for the links written in documentation, see
+    // `resolve_item_link`. Variants share the path of their enum, so a path
+    // may have several ids.
+    ids_by_path: HashMap<Vec<String>, Vec<ast::DefId>>,
+    member_anchors: HashMap<ast::DefId, Vec<(String, String)>>,
+    // What each module imports, and the modules imported into every module.
+    imports: HashMap<ast::DefId, clean::ModuleImports>,
+    prelude: Vec<ast::DefId>,
 }
 
 /// Helper struct to render all source code to HTML pages
@@ -299,14 +311,141 @@ impl fmt::Display for IndexItemFunctionType {
 // TLS keys used to carry information around during rendering.
 
 thread_local!(static CACHE_KEY: RefCell<Arc<Cache>> = Default::default());
+thread_local!(static WORKSPACE_KEY: RefCell<Option<Workspace>> = RefCell::new(None));
 thread_local!(pub static CURRENT_LOCATION_KEY: RefCell<Vec<String>> =
                     RefCell::new(Vec::new()));
 
 /// Generates the documentation for `crate` into the directory `dst`
-pub fn run(mut krate: clean::Crate,
+pub fn run(krate: clean::Crate,
            external_html: &ExternalHtml,
            dst: PathBuf,
            passes: HashSet<String>) -> io::Result<()> {
+    let analysis = ::ANALYSISKEY.with(|a| a.clone());
+    let analysis = analysis.borrow();
+    let (mut cx, krate, cache, index) = try!(prepare(krate, external_html, dst, passes,
+                                                     analysis.as_ref()));
+
+    // Freeze the cache now that the index has been built. Put an Arc into TLS
+    // for future parallelization opportunities
+    let cache = Arc::new(cache);
+    CACHE_KEY.with(|v| *v.borrow_mut() = cache.clone());
+    CURRENT_LOCATION_KEY.with(|s| s.borrow_mut().clear());
+
+    try!(write_shared(&cx, &krate, &*cache, index));
+    let krate = try!(render_sources(&mut cx, krate));
+
+    // And finally render the whole crate's documentation
+    cx.krate(krate)
+}
+
+/// A crate to document with `run_workspace`.
+pub struct WorkspaceCrate {
+    pub krate: clean::Crate,
+    /// The analysis made when the crate was read in, if it was read from
+    /// source.
+    pub analysis: Option<core::CrateAnalysis>,
+    pub passes: HashSet<String>,
+}
+
+/// The crates being rendered by `run_workspace`, by name, and which of them
+/// is currently being rendered.
+struct Workspace {
+    crates: Vec<(String, Arc<Cache>)>,
+    current: usize,
+}
+
+/// Generates the documentation for several crates into the directory `dst`
+/// as one site.
+///
+/// This differs from calling `run` for each crate in that links between the
+/// crates always point into `dst`, implementations of a trait in one crate
+/// for a type in another are shown on the page of the type, and the search
+/// index and the implementors of each trait are written once for all of the
+/// crates, listing an impl once however many of them inline it.
+pub fn run_workspace(crates: Vec<WorkspaceCrate>,
+                     external_html: &ExternalHtml,
+                     dst: PathBuf) -> io::Result<()> {
+    let mut prepared = Vec::new();
+    for c in crates {
+        prepared.push(try!(prepare(c.krate, external_html, dst.clone(), c.passes,
+                                   c.analysis.as_ref())));
+    }
+    let names: Vec<String> = prepared.iter().map(|p| p.1.name.clone()).collect();
+
+    // Every crate of the workspace is documented in `dst`, so point the others
+    // at the pages it has, whatever they know about where it is.
+    let local_paths: Vec<Vec<(ast::NodeId, (Vec<String>, ItemType))>> = prepared.iter().map(|p| {
+        p.2.paths.iter().filter(|&(did, _)| ast_util::is_local(*did))
+                 .map(|(did, path)| (did.node, path.clone()))
+                 .collect()
+    }).collect();
+    for p in &mut prepared {
+        let cache = &mut p.2;
+        for (&n, &mut (ref name, ref mut location)) in &mut cache.extern_locations {
+            let i = match names.iter().position(|x| x == name) {
+                Some(i) => i,
+                None => continue,
+            };
+            *location = Local;
+            for &(node, ref path) in &local_paths[i] {
+                let did = ast::DefId { krate: n, node: node };
+                cache.external_paths.insert(did, path.0.clone());
+                cache.paths.insert(did, path.clone());
+            }
+        }
+        cache.index_paths();
+    }
+
+    let mut caches = Vec::new();
+    let mut rest = Vec::new();
+    for (cx, krate, cache, index) in prepared {
+        caches.push((krate.name.clone(), Arc::new(cache)));
+        rest.push((cx, krate, index));
+    }
+
+    {
+        try!(mkdir(&dst));
+        let _lock = ::flock::Lock::new(&dst.join(".lock"));
+        try!(write_static_files(&dst));
+
+        let indexes: Vec<String> = rest.iter().map(|r| r.2.clone()).collect();
+        try!(write_search_index(&dst, &names, &indexes));
+
+        let mut implementors = BTreeMap::new();
+        for &(ref name, ref cache) in &caches {
+            // Implementors are printed with the cache of their own crate.
+            CACHE_KEY.with(|v| *v.borrow_mut() = cache.clone());
+            try!(collect_implementors(&dst, name, cache, &names, &mut implementors));
+        }
+        try!(write_implementors(&names, implementors));
+    }
+
+    WORKSPACE_KEY.with(|w| {
+        *w.borrow_mut() = Some(Workspace { crates: caches.clone(), current: 0 });
+    });
+    for (i, (mut cx, krate, _)) in rest.into_iter().enumerate() {
+        CACHE_KEY.with(|v| *v.borrow_mut() = caches[i].1.clone());
+        CURRENT_LOCATION_KEY.with(|s| s.borrow_mut().clear());
+        WORKSPACE_KEY.with(|w| {
+            if let Some(ref mut w) = *w.borrow_mut() {
+                w.current = i;
+            }
+        });
+        let krate = try!(render_sources(&mut cx, krate));
+        try!(cx.krate(krate));
+    }
+    WORKSPACE_KEY.with(|w| *w.borrow_mut() = None);
+    Ok(())
+}
+
+/// Builds the context and cache for rendering `krate`, along with its entry
+/// in the search index.
+fn prepare(mut krate: clean::Crate,
+           external_html: &ExternalHtml,
+           dst: PathBuf,
+           passes: HashSet<String>,
+           analysis: Option<&core::CrateAnalysis>)
+           -> io::Result<(Context, clean::Crate, Cache, String)> {
     let src_root = match krate.src.parent() {
         Some(p) => p.to_path_buf(),
         None => PathBuf::new(),
@@ -367,15 +506,15 @@ pub fn run(mut krate: clean::Crate,
     }
 
     // Crawl the crate to build various caches used for the output
-    let analysis = ::ANALYSISKEY.with(|a| a.clone());
-    let analysis = analysis.borrow();
-    let public_items = analysis.as_ref().map(|a| a.public_items.clone());
+    let public_items = analysis.map(|a| a.public_items.clone());
     let public_items = public_items.unwrap_or(NodeSet());
     let paths: HashMap<ast::DefId, (Vec<String>, ItemType)> =
-      analysis.as_ref().map(|a| {
+      analysis.map(|a| {
         let paths = a.external_paths.borrow_mut().take().unwrap();
         paths.into_iter().map(|(k, (v, t))| (k, (v, ItemType::from_type_kind(t)))).collect()
       }).unwrap_or(HashMap::new());
+    let imports = analysis.and_then(|a| a.imports.borrow_mut().take())
+                          .unwrap_or(clean::Imports::default());
     let mut cache = Cache {
         impls: HashMap::new(),
         external_paths: paths.iter().map(|(&k, v)| (k, v.0.clone()))
@@ -391,12 +530,16 @@ pub fn run(mut krate: clean::Crate,
         privmod: false,
         public_items: public_items,
         orphan_methods: Vec::new(),
+        ids_by_path: HashMap::new(),
+        member_anchors: HashMap::new(),
+        imports: imports.modules,
+        prelude: imports.prelude,
         traits: mem::replace(&mut krate.external_traits, HashMap::new()),
-        deref_trait_did: analysis.as_ref().and_then(|a| a.deref_trait_did),
-        typarams: analysis.as_ref().map(|a| {
+        deref_trait_did: analysis.and_then(|a| a.deref_trait_did),
+        typarams: analysis.map(|a| {
             a.external_typarams.borrow_mut().take().unwrap()
         }).unwrap_or(HashMap::new()),
-        inlined: analysis.as_ref().map(|a| {
+        inlined: analysis.map(|a| {
             a.inlined.borrow_mut().take().unwrap()
         }).unwrap_or(HashSet::new()),
     };
@@ -427,18 +570,9 @@ pub fn run(mut krate: clean::Crate,
 
     // Build our search index
     let index = try!(build_index(&krate, &mut cache));
+    cache.index_paths();
 
-    // Freeze the cache now that the index has been built. Put an Arc into TLS
-    // for future parallelization opportunities
-    let cache = Arc::new(cache);
-    CACHE_KEY.with(|v| *v.borrow_mut() = cache.clone());
-    CURRENT_LOCATION_KEY.with(|s| s.borrow_mut().clear());
-
-    try!(write_shared(&cx, &krate, &*cache, index));
-    let krate = try!(render_sources(&mut cx, krate));
-
-    // And finally render the whole crate's documentation
-    cx.krate(krate)
+    Ok((cx, krate, cache, index))
 }
 
 fn build_index(krate: &clean::Crate, cache: &mut Cache) -> io::Result<String> {
@@ -549,61 +683,88 @@ fn write_shared(cx: &Context,
     try!(mkdir(&cx.dst));
     let _lock = ::flock::Lock::new(&cx.dst.join(".lock"));
 
+    try!(write_static_files(&cx.dst));
+
+    // Update the search index
+    let krates = [krate.name.clone()];
+    try!(write_search_index(&cx.dst, &krates, &[search_index]));
+
+    // Update the list of all implementors for traits
+    let mut implementors = BTreeMap::new();
+    try!(collect_implementors(&cx.dst, &krate.name, cache, &[], &mut implementors));
+    write_implementors(&krates, implementors)
+}
+
+fn write_static_files(dst: &Path) -> io::Result<()> {
     // Add all the static files. These may already exist, but we just
     // overwrite them anyway to make sure that they're fresh and up-to-date.
-    try!(write(cx.dst.join("jquery.js"),
+    try!(write(dst.join("jquery.js"),
                include_bytes!("static/jquery-2.1.4.min.js")));
-    try!(write(cx.dst.join("main.js"), include_bytes!("static/main.js")));
-    try!(write(cx.dst.join("playpen.js"), include_bytes!("static/playpen.js")));
-    try!(write(cx.dst.join("main.css"), include_bytes!("static/main.css")));
-    try!(write(cx.dst.join("normalize.css"),
+    try!(write(dst.join("main.js"), include_bytes!("static/main.js")));
+    try!(write(dst.join("playpen.js"), include_bytes!("static/playpen.js")));
+    try!(write(dst.join("main.css"), include_bytes!("static/main.css")));
+    try!(write(dst.join("normalize.css"),
                include_bytes!("static/normalize.css")));
-    try!(write(cx.dst.join("FiraSans-Regular.woff"),
+    try!(write(dst.join("FiraSans-Regular.woff"),
                include_bytes!("static/FiraSans-Regular.woff")));
-    try!(write(cx.dst.join("FiraSans-Medium.woff"),
+    try!(write(dst.join("FiraSans-Medium.woff"),
                include_bytes!("static/FiraSans-Medium.woff")));
-    try!(write(cx.dst.join("Heuristica-Italic.woff"),
+    try!(write(dst.join("Heuristica-Italic.woff"),
                include_bytes!("static/Heuristica-Italic.woff")));
-    try!(write(cx.dst.join("SourceSerifPro-Regular.woff"),
+    try!(write(dst.join("SourceSerifPro-Regular.woff"),
                include_bytes!("static/SourceSerifPro-Regular.woff")));
-    try!(write(cx.dst.join("SourceSerifPro-Bold.woff"),
+    try!(write(dst.join("SourceSerifPro-Bold.woff"),
                include_bytes!("static/SourceSerifPro-Bold.woff")));
-    try!(write(cx.dst.join("SourceCodePro-Regular.woff"),
+    try!(write(dst.join("SourceCodePro-Regular.woff"),
                include_bytes!("static/SourceCodePro-Regular.woff")));
-    try!(write(cx.dst.join("SourceCodePro-Semibold.woff"),
+    try!(write(dst.join("SourceCodePro-Semibold.woff"),
                include_bytes!("static/SourceCodePro-Semibold.woff")));
+    Ok(())
+}
 
-    fn collect(path: &Path, krate: &str,
-               key: &str) -> io::Result<Vec<String>> {
-        let mut ret = Vec::new();
-        if path.exists() {
-            for line in BufReader::new(try!(File::open(path))).lines() {
-                let line = try!(line);
-                if !line.starts_with(key) {
-                    continue
-                }
-                if line.starts_with(&format!("{}['{}']", key, krate)) {
-                    continue
-                }
-                ret.push(line.to_string());
+/// Reads the lines starting with `key` from the shared file at `path`, other
+/// than those for any of `krates`, which are about to be rewritten.
+fn collect(path: &Path, krates: &[String], key: &str) -> io::Result<Vec<String>> {
+    let mut ret = Vec::new();
+    if path.exists() {
+        for line in BufReader::new(try!(File::open(path))).lines() {
+            let line = try!(line);
+            if !line.starts_with(key) {
+                continue
+            }
+            if krates.iter().any(|k| line.starts_with(&format!("{}['{}']", key, k))) {
+                continue
             }
+            ret.push(line.to_string());
         }
-        return Ok(ret);
     }
+    return Ok(ret);
+}
 
-    // Update the search index
-    let dst = cx.dst.join("search-index.js");
-    let all_indexes = try!(collect(&dst, &krate.name, "searchIndex"));
+/// Writes the search index entries of `krates` into the search index in
+/// `dst`, keeping those of other crates already there.
+fn write_search_index(dst: &Path, krates: &[String], indexes: &[String]) -> io::Result<()> {
+    let dst = dst.join("search-index.js");
+    let all_indexes = try!(collect(&dst, krates, "searchIndex"));
     let mut w = try!(File::create(&dst));
     try!(writeln!(&mut w, "var searchIndex = {{}};"));
-    try!(writeln!(&mut w, "{}", search_index));
-    for index in &all_indexes {
+    for index in indexes.iter().chain(&all_indexes) {
         try!(writeln!(&mut w, "{}", *index));
     }
-    try!(writeln!(&mut w, "initSearch(searchIndex);"));
+    writeln!(&mut w, "initSearch(searchIndex);")
+}
 
-    // Update the list of all implementors for traits
-    let dst = cx.dst.join("implementors");
+/// Adds the line listing the implementors found in `krate` of each trait to
+/// `files`, keyed by the file of implementors of the trait.
+///
+/// Impls inlined from another of the crates in `workspace` are left out, as
+/// that crate lists them itself.
+fn collect_implementors(dst: &Path,
+                        krate: &str,
+                        cache: &Cache,
+                        workspace: &[String],
+                        files: &mut BTreeMap<PathBuf, Vec<String>>) -> io::Result<()> {
+    let dst = dst.join("implementors");
     try!(mkdir(&dst));
     for (&did, imps) in &cache.implementors {
         // Private modules can leak through to this phase of rustdoc, which
@@ -626,30 +787,47 @@ fn write_shared(cx: &Context,
         mydst.push(&format!("{}.{}.js",
                             remote_item_type.to_static_str(),
                             remote_path[remote_path.len() - 1]));
-        let all_implementors = try!(collect(&mydst, &krate.name,
-                                            "implementors"));
-
-        try!(mkdir(mydst.parent().unwrap()));
-        let mut f = BufWriter::new(try!(File::create(&mydst)));
-        try!(writeln!(&mut f, "(function() {{var implementors = {{}};"));
 
-        for implementor in &all_implementors {
-            try!(write!(&mut f, "{}", *implementor));
-        }
-
-        try!(write!(&mut f, r"implementors['{}'] = [", krate.name));
+        let mut line = format!(r"implementors['{}'] = [", krate);
+        let mut seen = HashSet::new();
         for imp in imps {
             // If the trait and implementation are in the same crate, then
             // there's no need to emit information about it (there's inlining
             // going on). If they're in different crates then the crate defining
             // the trait will be interested in our implementation.
             if imp.def_id.krate == did.krate { continue }
-            try!(write!(&mut f, r#""impl{} {}{} for {}","#,
-                        imp.generics,
-                        if imp.polarity == Some(clean::ImplPolarity::Negative) { "!" } else { "" },
-                        imp.trait_, imp.for_));
+            if !ast_util::is_local(imp.def_id) {
+                let from = crate_name(imp.def_id.krate, krate, cache);
+                if workspace.iter().any(|w| Some(&w[..]) == from) { continue }
+            }
+            if !seen.insert(imp.def_id) { continue }
+            line.push_str(&format!(r#""impl{} {}{} for {}","#,
+                                   imp.generics,
+                                   if imp.polarity == Some(clean::ImplPolarity::Negative) {
+                                       "!"
+                                   } else {
+                                       ""
+                                   },
+                                   imp.trait_, imp.for_));
+        }
+        line.push_str("];");
+        files.entry(mydst).or_insert(vec![]).push(line);
+    }
+    Ok(())
+}
+
+/// Writes the files of implementors collected by `collect_implementors`,
+/// keeping the implementors listed there by crates other than `krates`.
+fn write_implementors(krates: &[String],
+                      files: BTreeMap<PathBuf, Vec<String>>) -> io::Result<()> {
+    for (path, lines) in files {
+        let all_implementors = try!(collect(&path, krates, "implementors"));
+
+        let mut f = BufWriter::new(try!(File::create(&path)));
+        try!(writeln!(&mut f, "(function() {{var implementors = {{}};"));
+        for implementor in all_implementors.iter().chain(&lines) {
+            try!(writeln!(&mut f, "{}", *implementor));
         }
-        try!(writeln!(&mut f, r"];"));
         try!(writeln!(&mut f, "{}", r"
             if (window.register_implementors) {
                 window.register_implementors(implementors);
@@ -662,6 +840,25 @@ fn write_shared(cx: &Context,
     Ok(())
 }
 
+/// The name of the crate numbered `n` by the crate `krate`, which has the
+/// given cache.
+fn crate_name<'a>(n: ast::CrateNum, krate: &'a str, cache: &'a Cache) -> Option<&'a str> {
+    if n == ast::LOCAL_CRATE {
+        Some(krate)
+    } else {
+        cache.extern_locations.get(&n).map(|&(ref name, _)| &name[..])
+    }
+}
+
+/// The number the crate `krate`, which has the given cache, gives to the
+/// crate named `name`.
+fn crate_num(name: &str, krate: &str, cache: &Cache) -> Option<ast::CrateNum> {
+    if name == krate {
+        return Some(ast::LOCAL_CRATE)
+    }
+    cache.extern_locations.iter().find(|&(_, &(ref n, _))| *n == name).map(|(&n, _)| n)
+}
+
 fn render_sources(cx: &mut Context,
                   krate: clean::Crate) -> io::Result<clean::Crate> {
     info!("emitting source files");
@@ -700,6 +897,168 @@ fn shortty(item: &clean::Item) -> ItemType {
     ItemType::from_item(item)
 }
 
+/// Finds the URL of the item at `path`, written in documentation on the page
+/// being rendered. As in code there, the path may be relative to the module
+/// of the page, start with `self`, `super` or `::`, or start with a name the
+/// module imports or the prelude brings in. Paths relative to the crate root
+/// or starting with the name of a crate are found as well. Paths to methods,
+/// associated items, fields and variants link to their place on the page of
+/// their parent.
+///
+/// Items of other crates are only found if the crate being documented refers
+/// to them.
+pub fn resolve_item_link(path: &str) -> Option<String> {
+    let loc = CURRENT_LOCATION_KEY.with(|l| l.borrow().clone());
+    if loc.is_empty() {
+        return None
+    }
+    let (absolute, path) = if path.starts_with("::") {
+        (true, &path[2..])
+    } else {
+        (false, path)
+    };
+    let mut segments: Vec<String> = path.split("::").map(|s| s.to_string()).collect();
+    let cache = cache();
+
+    if absolute {
+        return resolve_in_path(&cache, &loc[..1], &segments)
+    }
+    if segments[0] == "self" || segments[0] == "super" {
+        let mut base = loc.clone();
+        if segments[0] == "self" {
+            segments.remove(0);
+        }
+        while !segments.is_empty() && segments[0] == "super" {
+            if base.len() == 1 {
+                return None
+            }
+            base.pop();
+            segments.remove(0);
+        }
+        if segments.is_empty() {
+            return None
+        }
+        return resolve_in_path(&cache, &base, &segments)
+    }
+    resolve_in_path(&cache, &loc, &segments).or_else(|| {
+        cache.prelude.iter().filter_map(|&did| {
+            resolve_in_module(&cache, did, &segments, 0)
+        }).next()
+    }).or_else(|| {
+        resolve_in_path(&cache, &loc[..1], &segments)
+    }).or_else(|| {
+        resolve_in_path(&cache, &[], &segments)
+    })
+}
+
+/// How many `*` imports are followed from a module to find a name, which also
+/// stops at modules importing each other.
+const MAX_GLOB_DEPTH: usize = 8;
+
+/// Finds the URL of the item at `segments` in the module at the fully
+/// qualified path `fqp`, looking through the module's imports if it is known.
+fn resolve_in_path(cache: &Cache, fqp: &[String], segments: &[String]) -> Option<String> {
+    let module = cache.ids_by_path.get(fqp).and_then(|dids| {
+        dids.iter().cloned().find(|did| {
+            cache.paths.get(did).map_or(false, |&(_, ty)| ty == ItemType::Module)
+        })
+    });
+    match module {
+        Some(did) => resolve_in_module(cache, did, segments, 0),
+        None => {
+            let mut fqp = fqp.to_vec();
+            fqp.extend(segments.iter().cloned());
+            item_url(cache, &fqp)
+        }
+    }
+}
+
+/// Finds the URL of the item at `segments` in the module `did`, among the
+/// module's own items, the names it imports, and, up to `MAX_GLOB_DEPTH`
+/// levels deep, the modules it imports with `*`.
+fn resolve_in_module(cache: &Cache, did: ast::DefId, segments: &[String],
+                     depth: usize) -> Option<String> {
+    if let Some(&(ref fqp, _)) = cache.paths.get(&did) {
+        let mut fqp = fqp.clone();
+        fqp.extend(segments.iter().cloned());
+        if let Some(url) = item_url(cache, &fqp) {
+            return Some(url)
+        }
+    }
+    let imports = match cache.imports.get(&did) {
+        Some(imports) => imports,
+        None => return None,
+    };
+    if let Some(&target) = imports.names.get(&segments[0]) {
+        let url = if segments.len() == 1 {
+            href(target).map(|(url, _, _)| url)
+        } else {
+            resolve_in_module(cache, target, &segments[1..], depth)
+        };
+        if url.is_some() {
+            return url
+        }
+    }
+    if depth >= MAX_GLOB_DEPTH {
+        return None
+    }
+    imports.globs.iter().filter_map(|&glob| {
+        resolve_in_module(cache, glob, segments, depth + 1)
+    }).next()
+}
+
+/// Finds the URL of the item at the fully qualified path `fqp`, or of a
+/// member of the type or trait at its parent path.
+fn item_url(cache: &Cache, fqp: &[String]) -> Option<String> {
+    if let Some(dids) = cache.ids_by_path.get(fqp) {
+        if let Some((url, _, _)) = href(dids[0]) {
+            return Some(url)
+        }
+    }
+    if fqp.len() < 2 {
+        return None
+    }
+    let (parent, name) = (&fqp[..fqp.len() - 1], &fqp[fqp.len() - 1]);
+    let dids = match cache.ids_by_path.get(parent) {
+        Some(dids) => dids,
+        None => return None,
+    };
+    for &did in dids {
+        if let Some(anchor) = member_anchor(cache, did, name) {
+            if let Some((url, _, _)) = href(did) {
+                return Some(format!("{}#{}", url, anchor))
+            }
+        }
+    }
+    None
+}
+
+/// Finds the anchor of the member `name` of the type or trait `did` on its
+/// page.
+fn member_anchor(cache: &Cache, did: ast::DefId, name: &str) -> Option<String> {
+    if let Some(t) = cache.traits.get(&did) {
+        return t.items.iter().find(|it| it.name.as_ref().map_or(false, |n| *n == name))
+                             .map(|it| format!("{}.{}", shortty(it), name))
+    }
+    if let Some(impls) = cache.impls.get(&did) {
+        for it in impls.iter().flat_map(|i| i.impl_.items.iter()) {
+            if it.name.as_ref().map_or(true, |n| *n != name) {
+                continue
+            }
+            let kind = match it.inner {
+                clean::MethodItem(..) | clean::TyMethodItem(..) => "method",
+                clean::TypedefItem(..) | clean::AssociatedTypeItem(..) => "assoc_type",
+                clean::ConstantItem(..) | clean::AssociatedConstItem(..) => "assoc_const",
+                _ => continue,
+            };
+            return Some(format!("{}.{}", kind, name))
+        }
+    }
+    cache.member_anchors.get(&did).and_then(|members| {
+        members.iter().find(|&&(ref n, _)| *n == name).map(|&(_, ref anchor)| anchor.clone())
+    })
+}
+
 /// Takes a path to a source file and cleans the path to it. This canonicalizes
 /// things like ".." to components which preserve the "top down" hierarchy of a
 /// static HTML tree. Each component in the cleaned path will be passed as an
@@ -849,6 +1208,17 @@ impl<'a> SourceCollector<'a> {
     }
 }
 
+impl Cache {
+    /// Fills in `ids_by_path` from `paths`.
+    fn index_paths(&mut self) {
+        let mut ids_by_path = HashMap::new();
+        for (&did, &(ref fqp, _)) in &self.paths {
+            ids_by_path.entry(fqp.clone()).or_insert(Vec::new()).push(did);
+        }
+        self.ids_by_path = ids_by_path;
+    }
+}
+
 impl DocFolder for Cache {
     fn fold_item(&mut self, item: clean::Item) -> Option<clean::Item> {
         // If this is a private module, we don't want it in the search index.
@@ -974,6 +1344,20 @@ impl DocFolder for Cache {
             }
         }
 
+        // Remember where fields and variants are on the page of their parent,
+        // for links to them from documentation.
+        if let (Some(name), Some(&parent)) = (item.name.as_ref(), self.parent_stack.last()) {
+            let kind = match item.inner {
+                clean::StructFieldItem(clean::TypedStructField(..)) => Some("structfield"),
+                clean::VariantItem(..) => Some("variant"),
+                _ => None,
+            };
+            if let Some(kind) = kind {
+                self.member_anchors.entry(parent).or_insert(Vec::new())
+                    .push((name.to_string(), format!("{}.{}", kind, name)));
+            }
+        }
+
         // Keep track of the fully qualified path for this item.
         let pushed = if item.name.is_some() {
             let n = item.name.as_ref().unwrap();
@@ -1049,7 +1433,7 @@ impl DocFolder for Cache {
         let ret = match self.fold_item_recur(item) {
             Some(item) => {
                 match item {
-                    clean::Item{ attrs, inner: clean::ImplItem(i), .. } => {
+                    clean::Item{ attrs, inner: clean::ImplItem(i), def_id, .. } => {
                         // extract relevant documentation for this impl
                         let dox = match attrs.into_iter().find(|a| {
                             match *a {
@@ -1086,6 +1470,7 @@ impl DocFolder for Cache {
 
                         if let Some(did) = did {
                             self.impls.entry(did).or_insert(vec![]).push(Impl {
+                                def_id: def_id,
                                 impl_: i,
                                 dox: dox,
                                 stability: item.stability.clone(),
@@ -1551,11 +1936,83 @@ fn document(w: &mut fmt::Formatter, item: &clean::Item) -> fmt::Result {
         try!(write!(w, "<div class='stability'>{}</div>", s));
     }
     if let Some(s) = item.doc_value() {
+        markdown::take_unresolved_links();
         try!(write!(w, "<div class='docblock'>{}</div>", Markdown(s)));
+        for path in markdown::take_unresolved_links() {
+            warn_unresolved_link(item, &path);
+        }
     }
     Ok(())
 }
 
+thread_local!(static DOC_DIAGNOSTICS: diagnostic::SpanHandler = {
+    let handler = diagnostic::Handler::new(diagnostic::Auto, None, true);
+    diagnostic::SpanHandler::new(handler, CodeMap::new())
+});
+
+/// Warns that the link to `path` in the documentation of `item` doesn't go
+/// anywhere, pointing at the item's doc comment. The crate's code map is gone
+/// by the time documentation is rendered, so the file of the comment is read
+/// again. Documentation rendered more than once is only warned about once.
+fn warn_unresolved_link(item: &clean::Item, path: &str) {
+    let name = item.name.as_ref().map(|s| &s[..]).unwrap_or("");
+    let msg = format!("unresolved link to `{}` in the documentation of `{}`", path, name);
+    DOC_DIAGNOSTICS.with(|diag| {
+        match codemap_span(&diag.cm, &item.doc_source) {
+            Some(sp) => diag.span_warn(sp, &msg),
+            None => diag.handler.warn(&msg),
+        }
+    });
+}
+
+/// Finds `span` in the code map `cm`, reading its file into the map if it
+/// isn't there yet.
+fn codemap_span(cm: &CodeMap, span: &clean::Span) -> Option<codemap::Span> {
+    if span.filename.is_empty() {
+        return None
+    }
+    let loaded = cm.files.borrow().iter().find(|fm| fm.name == span.filename).cloned();
+    let fm = match loaded {
+        Some(fm) => fm,
+        None => {
+            let fm = match cm.load_file(Path::new(&span.filename)) {
+                Ok(fm) => fm,
+                Err(..) => return None,
+            };
+            let src = fm.src.clone().unwrap();
+            fm.next_line(fm.start_pos);
+            for (i, c) in src.char_indices() {
+                let pos = fm.start_pos + BytePos::from_usize(i);
+                if c == '\n' && i + 1 < src.len() {
+                    fm.next_line(pos + BytePos(1));
+                } else if c.len_utf8() > 1 {
+                    fm.record_multibyte_char(pos, c.len_utf8());
+                }
+            }
+            fm
+        }
+    };
+    match (line_pos(&fm, span.loline, span.locol), line_pos(&fm, span.hiline, span.hicol)) {
+        (Some(lo), Some(hi)) => Some(codemap::mk_sp(lo, hi)),
+        _ => None,
+    }
+}
+
+/// The position of the character at column `col` of line `line` of `fm`,
+/// where lines are counted from 1 and columns from 0.
+fn line_pos(fm: &codemap::FileMap, line: usize, col: usize) -> Option<BytePos> {
+    if line == 0 {
+        return None
+    }
+    let begin = match fm.lines.borrow().get(line - 1) {
+        Some(&begin) => begin,
+        None => return None,
+    };
+    fm.get_line(line - 1).and_then(|text| {
+        text.char_indices().map(|(i, _)| i).chain(Some(text.len())).nth(col)
+    }).map(|offset| begin + BytePos::from_usize(offset))
+}
+
 fn item_module(w: &mut fmt::Formatter, cx: &Context,
                item: &clean::Item, items: &[clean::Item]) -> fmt::Result {
     try!(document(w, item));
@@ -2248,7 +2705,7 @@ fn render_assoc_items(w: &mut fmt::Formatter,
     let c = cache();
     let v = match c.impls.get(&it) {
         Some(v) => v,
-        None => return Ok(()),
+        None => return render_workspace_impls(w, it, &what),
     };
     let (non_trait, traits): (Vec<_>, _) = v.iter().partition(|i| {
         i.impl_.trait_.is_none()
@@ -2303,6 +2760,59 @@ fn render_assoc_items(w: &mut fmt::Formatter,
             }
         }
     }
+    render_workspace_impls(w, it, &what)
+}
+
+/// Renders the implementations of traits for `it` which are in the other
+/// crates of the workspace being rendered, if any.
+fn render_workspace_impls(w: &mut fmt::Formatter,
+                          it: ast::DefId,
+                          what: &AssocItemRender) -> fmt::Result {
+    if let AssocItemRender::DerefFor { .. } = *what {
+        return Ok(())
+    }
+    // The id of `it` in each of the other crates, with their caches.
+    let others: Vec<(ast::DefId, Arc<Cache>)> = WORKSPACE_KEY.with(|ws| {
+        let ws = ws.borrow();
+        let ws = match *ws {
+            Some(ref ws) => ws,
+            None => return vec![],
+        };
+        let (ref name, ref current) = ws.crates[ws.current];
+        let krate = match crate_name(it.krate, name, current) {
+            Some(krate) => krate,
+            None => return vec![],
+        };
+        ws.crates.iter().enumerate().filter(|&(i, _)| i != ws.current).filter_map(|(_, c)| {
+            let (ref other, ref cache) = *c;
+            crate_num(krate, other, cache).map(|n| {
+                (ast::DefId { krate: n, node: it.node }, cache.clone())
+            })
+        }).collect()
+    });
+
+    let mut rendered_header = false;
+    for (did, cache) in others {
+        let impls = match cache.impls.get(&did) {
+            Some(v) => v,
+            None => continue,
+        };
+        // Inherent impls are all in the crate of the type, and impls inlined
+        // from another crate are rendered as part of that crate.
+        for i in impls.iter().filter(|i| i.impl_.trait_.is_some() && ast_util::is_local(i.def_id)) {
+            if !rendered_header {
+                try!(write!(w, "<h2 id='workspace-implementations'>Trait \
+                                  Implementations in Other Crates</h2>"));
+                rendered_header = true;
+            }
+            // The impl refers to items as its own crate does, so render it
+            // with that crate's cache.
+            let prev = CACHE_KEY.with(|c| mem::replace(&mut *c.borrow_mut(), cache.clone()));
+            let res = render_impl(w, i, AssocItemLink::GotoSource(i.trait_did().unwrap()), true);
+            CACHE_KEY.with(|c| *c.borrow_mut() = prev);
+            try!(res);
+        }
+    }
     Ok(())
 }
 
@@ -22,6 +22,7 @@
 
 #![feature(box_patterns)]
 #![feature(box_syntax)]
+#![feature(catch_panic)]
 #![feature(dynamic_lib)]
 #![feature(libc)]
 #![feature(owned_ascii_ext)]
@@ -54,7 +55,7 @@ extern crate rustc_unicode;
 extern crate serialize as rustc_serialize; // used by deriving
 
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::fs::File;
 use std::io::{self, Read, Write};
@@ -65,7 +66,7 @@ use std::sync::mpsc::channel;
 
 use externalfiles::ExternalHtml;
 use serialize::Decodable;
-use serialize::json::{self, Json};
+use serialize::json::{self, Json, ToJson};
 use rustc::session::search_paths::SearchPaths;
 
 // reexported from `clean` so it can be easily updated with the mod itself
@@ -74,6 +75,7 @@ pub use clean::SCHEMA_VERSION;
 #[macro_use]
 pub mod externalfiles;
 
+pub mod api_json;
 pub mod clean;
 pub mod core;
 pub mod doctree;
@@ -108,6 +110,8 @@ const PASSES: &'static [Pass] = &[
      "concatenates all document attributes into one document attribute"),
     ("strip-private", passes::strip_private,
      "strips all private items from a crate which cannot be seen externally"),
+    ("doc-coverage", passes::doc_coverage,
+     "counts the documented items of a crate, by module and by kind of item"),
 ];
 
 const DEFAULT_PASSES: &'static [&'static str] = &[
@@ -145,7 +149,7 @@ pub fn opts() -> Vec<getopts::OptGroup> {
         optopt("r", "input-format", "the input type of the specified file",
                "[rust|json]"),
         optopt("w", "output-format", "the output type to write",
-               "[html|json]"),
+               "[html|json|api-json]"),
         optopt("o", "output", "where to place the output", "PATH"),
         optopt("", "crate-name", "specify the name of this crate", "NAME"),
         optmulti("L", "library-path", "directory to add to crate search path",
@@ -180,7 +184,15 @@ pub fn opts() -> Vec<getopts::OptGroup> {
                  "FILES"),
         optopt("", "markdown-playground-url",
                "URL to send code snippets to", "URL"),
-        optflag("", "markdown-no-toc", "don't include table of contents")
+        optflag("", "markdown-no-toc", "don't include table of contents"),
+        optflag("", "workspace", "document all of the given crates, in source or json \
+                                  form, together as one site"),
+        optflagopt("", "coverage", "instead of documenting the crate, print how much of it \
+                                    is documented, by module and by kind of item",
+                   "[table|json]"),
+        optopt("", "coverage-threshold", "with --coverage, fail if less than PERCENT of the \
+                                          items are documented",
+               "PERCENT")
     )
 }
 
@@ -191,7 +203,14 @@ pub fn usage(argv0: &str) {
 }
 
 pub fn main_args(args: &[String]) -> isize {
-    let matches = match getopts::getopts(&args[1..], &opts()) {
+    let expanded = match rustc_driver::args::expand(args[1..].to_vec()) {
+        Ok(args) => args,
+        Err(err) => {
+            println!("{}", err);
+            return 1;
+        }
+    };
+    let matches = match getopts::getopts(&expanded, &opts()) {
         Ok(m) => m,
         Err(err) => {
             println!("{}", err);
@@ -221,7 +240,7 @@ pub fn main_args(args: &[String]) -> isize {
     if matches.free.is_empty() {
         println!("expected an input file to act on");
         return 1;
-    } if matches.free.len() > 1 {
+    } if matches.free.len() > 1 && !matches.opt_present("workspace") {
         println!("only one input file may be specified");
         return 1;
     }
@@ -260,6 +279,29 @@ pub fn main_args(args: &[String]) -> isize {
     };
     let crate_name = matches.opt_str("crate-name");
 
+    if matches.opt_present("coverage") && (should_test || markdown_input ||
+                                           matches.opt_present("workspace")) {
+        println!("--coverage can only be used to document one crate");
+        return 1;
+    }
+    if matches.opt_present("coverage-threshold") && !matches.opt_present("coverage") {
+        println!("--coverage-threshold can only be used with --coverage");
+        return 1;
+    }
+
+    if matches.opt_present("workspace") {
+        if should_test || markdown_input || matches.opt_str("w").map_or(false, |w| w != "html") {
+            println!("--workspace can only be used to generate html");
+            return 1;
+        }
+        if crate_name.is_some() {
+            println!("--crate-name cannot be used with --workspace");
+            return 1;
+        }
+        return document_workspace(&matches.free, externs, &matches, &external_html,
+                                  output.unwrap_or(PathBuf::from("doc")));
+    }
+
     match (should_test, markdown_input) {
         (true, true) => {
             return markdown::test(input, libs, externs, test_args)
@@ -282,6 +324,9 @@ pub fn main_args(args: &[String]) -> isize {
         }
     };
     let Output { krate, json_plugins, passes, } = out;
+    if matches.opt_present("coverage") {
+        return coverage(&krate, &matches);
+    }
     info!("going to format");
     match matches.opt_str("w").as_ref().map(|s| &**s) {
         Some("html") | None => {
@@ -299,6 +344,12 @@ pub fn main_args(args: &[String]) -> isize {
                 Err(e) => panic!("failed to write json: {}", e),
             }
         }
+        Some("api-json") => {
+            match api_json::run(&krate, &output.unwrap_or(PathBuf::from("doc.json"))) {
+                Ok(()) => {}
+                Err(e) => panic!("failed to write json: {}", e),
+            }
+        }
         Some(s) => {
             println!("unknown output format: {}", s);
             return 1;
@@ -308,6 +359,69 @@ pub fn main_args(args: &[String]) -> isize {
     return 0;
 }
 
+/// Prints the documentation coverage of `krate` for `--coverage`, failing if
+/// it is below `--coverage-threshold`.
+fn coverage(krate: &clean::Crate, matches: &getopts::Matches) -> isize {
+    let threshold = match matches.opt_str("coverage-threshold") {
+        Some(s) => match s.parse::<f64>() {
+            Ok(t) => Some(t),
+            Err(_) => {
+                println!("--coverage-threshold expects a percentage, not `{}`", s);
+                return 1;
+            }
+        },
+        None => None,
+    };
+    let coverage = passes::Coverage::new(krate);
+    match matches.opt_str("coverage").as_ref().map(|s| &**s) {
+        Some("table") | None => print!("{}", coverage),
+        Some("json") => println!("{}", coverage.to_json().pretty()),
+        Some(s) => {
+            println!("unknown coverage format: {}", s);
+            return 1;
+        }
+    }
+    match threshold {
+        Some(t) if coverage.total.percent() < t => {
+            println!("error: {:.1}% of the items of `{}` are documented, less than \
+                      the threshold of {}%", coverage.total.percent(), krate.name, t);
+            1
+        }
+        _ => 0,
+    }
+}
+
+/// Reads in each of `inputs` and renders them together into `dst`, for
+/// `--workspace`.
+fn document_workspace(inputs: &[String],
+                      externs: core::Externs,
+                      matches: &getopts::Matches,
+                      external_html: &ExternalHtml,
+                      dst: PathBuf) -> isize {
+    let mut crates = Vec::new();
+    for input in inputs {
+        let out = match acquire_input(input, externs.clone(), matches) {
+            Ok(out) => out,
+            Err(s) => {
+                println!("input error: {}", s);
+                return 1;
+            }
+        };
+        // Reading in the next crate replaces the analysis of this one, which
+        // is needed again when rendering it.
+        let analysis = ANALYSISKEY.with(|a| a.borrow_mut().take());
+        crates.push(html::render::WorkspaceCrate {
+            krate: out.krate,
+            analysis: analysis,
+            passes: out.passes.into_iter().collect(),
+        });
+    }
+    match html::render::run_workspace(crates, external_html, dst) {
+        Ok(()) => 0,
+        Err(e) => panic!("failed to generate documentation: {}", e),
+    }
+}
+
 /// Looks inside the command line arguments to extract the relevant input format
 /// and files and then generates the necessary rustdoc output for formatting.
 fn acquire_input(input: &str,
@@ -486,9 +600,13 @@ fn json_input(input: &str) -> Result<Output, String> {
                 }
                 None => return Err("malformed json".to_string()),
             };
-            // FIXME: this should read from the "plugins" field, but currently
-            //      Json doesn't implement decodable...
-            let plugin_output = Vec::new();
+            let plugin_output = match obj.remove(&"plugins".to_string()) {
+                Some(Json::Object(plugins)) => {
+                    plugins.into_iter().map(|(name, json)| Some((name, json))).collect()
+                }
+                Some(..) => return Err("malformed json".to_string()),
+                None => Vec::new(),
+            };
             Ok(Output { krate: krate, json_plugins: plugin_output, passes: Vec::new(), })
         }
         Ok(..) => {
@@ -507,29 +625,16 @@ fn json_output(krate: clean::Crate, res: Vec<plugins::PluginJson> ,
     //   "crate": { parsed crate ... },
     //   "plugins": { output of plugins ... }
     // }
-    let mut json = std::collections::BTreeMap::new();
-    json.insert("schema".to_string(), Json::String(SCHEMA_VERSION.to_string()));
-    let plugins_json = res.into_iter()
-                          .filter_map(|opt| {
-                              match opt {
-                                  None => None,
-                                  Some((string, json)) => {
-                                      Some((string.to_string(), json))
-                                  }
-                              }
-                          }).collect();
-
-    // FIXME #8335: yuck, Rust -> str -> JSON round trip! No way to .encode
-    // straight to the Rust JSON representation.
-    let crate_json_str = format!("{}", json::as_json(&krate));
-    let crate_json = match json::from_str(&crate_json_str) {
-        Ok(j) => j,
-        Err(e) => panic!("Rust generated JSON is invalid: {:?}", e)
-    };
-
-    json.insert("crate".to_string(), crate_json);
-    json.insert("plugins".to_string(), Json::Object(plugins_json));
+    let plugins_json: BTreeMap<String, Json> = res.into_iter()
+                                                  .filter_map(|opt| opt)
+                                                  .collect();
 
+    // The crate is encoded straight to the file rather than to a `Json`
+    // value, so the object is written out by hand. Keys are in the order a
+    // `BTreeMap` would put them.
     let mut file = try!(File::create(&dst));
-    write!(&mut file, "{}", Json::Object(json))
+    write!(&mut file, "{{\"crate\":{},\"plugins\":{},\"schema\":{}}}",
+           json::as_json(&krate),
+           Json::Object(plugins_json),
+           Json::String(SCHEMA_VERSION.to_string()))
 }
@@ -148,6 +148,7 @@ pub fn test(input: &str, libs: SearchPaths, externs: core::Externs,
     opts.no_crate_inject = true;
     let mut collector = Collector::new(input.to_string(), libs, externs,
                                        true, opts);
+    collector.set_position(input.to_string(), 1);
     find_testable_code(&input_str, &mut collector);
     test_args.insert(0, "rustdoctest".to_string());
     testing::test_main(&test_args, collector.tests);
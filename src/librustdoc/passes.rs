@@ -8,9 +8,11 @@
 // option. This file may not be copied, modified, or distributed
 // except according to those terms.
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use rustc::util::nodemap::NodeSet;
+use serialize::json::{Json, ToJson};
 use std::cmp;
+use std::fmt;
 use std::string::String;
 use std::usize;
 use syntax::ast;
@@ -18,6 +20,8 @@ use syntax::ast_util;
 
 use clean;
 use clean::Item;
+use html::item_type::ItemType;
+use html::markdown;
 use plugins;
 use fold;
 use fold::DocFolder;
@@ -307,6 +311,170 @@ pub fn collapse_docs(krate: clean::Crate) -> plugins::PluginResult {
     (krate, None)
 }
 
+/// Counts how many of the items of a crate are documented. The counts are
+/// the output of the pass, for `--passes doc-coverage -w json`.
+pub fn doc_coverage(krate: clean::Crate) -> plugins::PluginResult {
+    let coverage = Coverage::new(&krate);
+    (krate, Some(("doc-coverage".to_string(), coverage.to_json())))
+}
+
+/// The number of items, documented items and items with code examples in
+/// some part of a crate.
+#[derive(Clone, Copy, Default)]
+pub struct Counts {
+    pub items: usize,
+    pub documented: usize,
+    pub examples: usize,
+}
+
+impl Counts {
+    /// The percentage of the items which are documented.
+    pub fn percent(&self) -> f64 {
+        if self.items == 0 {
+            100.0
+        } else {
+            self.documented as f64 * 100.0 / self.items as f64
+        }
+    }
+}
+
+impl ToJson for Counts {
+    fn to_json(&self) -> Json {
+        let mut obj = BTreeMap::new();
+        obj.insert("items".to_string(), self.items.to_json());
+        obj.insert("documented".to_string(), self.documented.to_json());
+        obj.insert("examples".to_string(), self.examples.to_json());
+        obj.insert("percent".to_string(), self.percent().to_json());
+        Json::Object(obj)
+    }
+}
+
+/// The documentation coverage of a crate, in total, by module and by kind of
+/// item.
+///
+/// Items of trait impls aren't counted, as they are documented by the
+/// trait, nor are imports. Private and hidden items are counted unless they
+/// have been stripped by an earlier pass.
+pub struct Coverage {
+    pub total: Counts,
+    pub modules: BTreeMap<String, Counts>,
+    pub kinds: BTreeMap<String, Counts>,
+}
+
+impl Coverage {
+    pub fn new(krate: &clean::Crate) -> Coverage {
+        let mut coverage = Coverage {
+            total: Counts::default(),
+            modules: BTreeMap::new(),
+            kinds: BTreeMap::new(),
+        };
+        if let Some(ref module) = krate.module {
+            coverage.count(module, &krate.name);
+        }
+        coverage
+    }
+
+    /// Counts `item` and the items in it, which are in the module `module`.
+    fn count(&mut self, item: &Item, module: &str) {
+        match item.inner {
+            clean::ImplItem(ref i) => {
+                if i.trait_.is_none() {
+                    for it in &i.items {
+                        self.count(it, module);
+                    }
+                }
+                return
+            }
+            clean::ExternCrateItem(..) | clean::ImportItem(..) |
+            clean::StructFieldItem(clean::HiddenStructField) => return,
+            _ => {}
+        }
+
+        // The crate root has no name, and counts as its own module.
+        let path = match (&item.inner, item.name.as_ref()) {
+            (&clean::ModuleItem(ref m), _) if m.is_crate => module.to_string(),
+            (&clean::ModuleItem(_), Some(name)) => format!("{}::{}", module, name),
+            _ => module.to_string(),
+        };
+        if item.name.is_some() {
+            let doc = item.doc_value().unwrap_or("");
+            let documented = !doc.trim().is_empty();
+            let example = documented && markdown::has_rust_code(doc);
+            let kind = ItemType::from_item(item).to_static_str().to_string();
+            for counts in vec![&mut self.total,
+                               self.modules.entry(path.clone()).or_insert(Counts::default()),
+                               self.kinds.entry(kind).or_insert(Counts::default())] {
+                counts.items += 1;
+                if documented {
+                    counts.documented += 1;
+                }
+                if example {
+                    counts.examples += 1;
+                }
+            }
+        }
+
+        let items = match item.inner {
+            clean::ModuleItem(ref m) => &m.items,
+            clean::StructItem(ref s) => &s.fields,
+            clean::EnumItem(ref e) => &e.variants,
+            clean::VariantItem(clean::Variant {
+                kind: clean::StructVariant(ref s)
+            }) => &s.fields,
+            clean::TraitItem(ref t) => &t.items,
+            _ => return,
+        };
+        for it in items {
+            self.count(it, &path);
+        }
+    }
+}
+
+impl ToJson for Coverage {
+    fn to_json(&self) -> Json {
+        fn counts(map: &BTreeMap<String, Counts>) -> Json {
+            Json::Object(map.iter().map(|(k, v)| (k.clone(), v.to_json())).collect())
+        }
+        let mut obj = BTreeMap::new();
+        obj.insert("total".to_string(), self.total.to_json());
+        obj.insert("modules".to_string(), counts(&self.modules));
+        obj.insert("kinds".to_string(), counts(&self.kinds));
+        Json::Object(obj)
+    }
+}
+
+/// Formats the coverage as a table, with a line for each module and each
+/// kind of item, and the total.
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn rule(f: &mut fmt::Formatter) -> fmt::Result {
+            writeln!(f, "+-{:-<40}-+-{:->8}-+-{:->10}-+-{:->8}-+-{:->8}-+", "", "", "", "", "")
+        }
+        fn header(f: &mut fmt::Formatter, name: &str) -> fmt::Result {
+            try!(rule(f));
+            try!(writeln!(f, "| {:<40} | {:>8} | {:>10} | {:>8} | {:>8} |",
+                          name, "Items", "Documented", "Percent", "Examples"));
+            rule(f)
+        }
+        fn line(f: &mut fmt::Formatter, name: &str, c: &Counts) -> fmt::Result {
+            writeln!(f, "| {:<40} | {:>8} | {:>10} | {:>7.1}% | {:>8} |",
+                     name, c.items, c.documented, c.percent(), c.examples)
+        }
+
+        try!(header(f, "Module"));
+        for (name, c) in &self.modules {
+            try!(line(f, name, c));
+        }
+        try!(header(f, "Kind"));
+        for (name, c) in &self.kinds {
+            try!(line(f, name, c));
+        }
+        try!(rule(f));
+        try!(line(f, "Total", &self.total));
+        rule(f)
+    }
+}
+
 pub fn unindent(s: &str) -> String {
     let lines = s.lines_any().collect::<Vec<&str> >();
     let mut saw_first_line = false;
@@ -9,16 +9,19 @@
 // except according to those terms.
 
 use std::cell::{RefCell, Cell};
+use std::cmp;
 use std::collections::{HashSet, HashMap};
 use std::dynamic_lib::DynamicLibrary;
 use std::env;
 use std::ffi::OsString;
+use std::fs::File;
 use std::io::prelude::*;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::str;
 use std::sync::{Arc, Mutex};
+use std::thread;
 
 use testing;
 use rustc_lint;
@@ -34,7 +37,7 @@ use core;
 use clean;
 use clean::Clean;
 use fold::DocFolder;
-use html::markdown;
+use html::markdown::{self, LangString};
 use passes;
 use visit_ast::RustdocVisitor;
 
@@ -153,29 +156,10 @@ fn scrape_test_config(krate: &::syntax::ast::Crate) -> TestOptions {
 }
 
 fn runtest(test: &str, cratename: &str, libs: SearchPaths,
-           externs: core::Externs,
-           should_panic: bool, no_run: bool, as_test_harness: bool,
-           opts: &TestOptions) {
+           externs: core::Externs, info: &LangString, opts: &TestOptions) {
     // the test harness wants its own `main` & top level functions, so
     // never wrap the test in `fn main() { ... }`
-    let test = maketest(test, Some(cratename), as_test_harness, opts);
-    let input = config::Input::Str(test.to_string());
-
-    let sessopts = config::Options {
-        maybe_sysroot: Some(env::current_exe().unwrap().parent().unwrap()
-                                              .parent().unwrap().to_path_buf()),
-        search_paths: libs,
-        crate_types: vec!(config::CrateTypeExecutable),
-        output_types: vec!(config::OutputTypeExe),
-        externs: externs,
-        cg: config::CodegenOptions {
-            prefer_dynamic: true,
-            .. config::basic_codegen_options()
-        },
-        test: as_test_harness,
-        unstable_features: get_unstable_features_setting(),
-        ..config::basic_options().clone()
-    };
+    let test = maketest(test, Some(cratename), info.test_harness, opts);
 
     // Shuffle around a few input and output handles here. We're going to pass
     // an explicit handle into rustc to collect output messages, but we also
@@ -187,13 +171,6 @@ fn runtest(test: &str, cratename: &str, libs: SearchPaths,
     //
     // The basic idea is to not use a default Handler for rustc, and then also
     // not print things by default to the actual stderr.
-    struct Sink(Arc<Mutex<Vec<u8>>>);
-    impl Write for Sink {
-        fn write(&mut self, data: &[u8]) -> io::Result<usize> {
-            Write::write(&mut *self.0.lock().unwrap(), data)
-        }
-        fn flush(&mut self) -> io::Result<()> { Ok(()) }
-    }
     struct Bomb(Arc<Mutex<Vec<u8>>>, Box<Write+Send>);
     impl Drop for Bomb {
         fn drop(&mut self) {
@@ -201,48 +178,190 @@ fn runtest(test: &str, cratename: &str, libs: SearchPaths,
         }
     }
     let data = Arc::new(Mutex::new(Vec::new()));
-    let emitter = diagnostic::EmitterWriter::new(box Sink(data.clone()), None);
     let old = io::set_panic(box Sink(data.clone()));
-    let _bomb = Bomb(data, old.unwrap_or(box io::stdout()));
+    let _bomb = Bomb(data.clone(), old.unwrap_or(box io::stdout()));
 
-    // Compile the code
-    let codemap = CodeMap::new();
-    let diagnostic_handler = diagnostic::Handler::with_emitter(true, box emitter);
-    let span_diagnostic_handler =
-        diagnostic::SpanHandler::new(diagnostic_handler, codemap);
+    let outdir = TempDir::new("rustdoctest").ok().expect("rustdoc needs a tempdir");
+    let libdir = compile(test, libs, externs, info.test_harness, &info.rustc_flags,
+                         info.no_run && !info.compile_fail, outdir.path(), data.clone());
+    let libdir = match libdir {
+        Some(_) if info.compile_fail => {
+            panic!("test compiled successfully, but it's marked `compile_fail`")
+        }
+        Some(libdir) => libdir,
+        None if info.compile_fail => {
+            let messages = String::from_utf8_lossy(&data.lock().unwrap()).into_owned();
+            let missing = info.error_codes.iter().filter(|code| {
+                !messages.contains(&code[..])
+            }).cloned().collect::<Vec<String>>();
+            if !missing.is_empty() {
+                panic!("test failed to compile, but not with the error codes {}",
+                       missing.join(", "));
+            }
+            return
+        }
+        None => panic!("couldn't compile the test"),
+    };
 
-    let sess = session::build_session_(sessopts,
-                                       None,
-                                       span_diagnostic_handler);
-    rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
+    if info.no_run { return }
+    run_test_exe(&outdir.path().join("rust_out"), None, &libdir, info.should_panic);
+}
 
-    let outdir = TempDir::new("rustdoctest").ok().expect("rustdoc needs a tempdir");
-    let out = Some(outdir.path().to_path_buf());
-    let cfg = config::build_configuration(&sess);
-    let libdir = sess.target_filesearch(PathKind::All).get_lib_path();
-    let mut control = driver::CompileController::basic();
-    if no_run {
-        control.after_analysis.stop = Compilation::Stop;
+/// A writer collecting rustc's messages, and the messages of panics, so that
+/// they end up in the output of the test.
+struct Sink(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Sink {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        Write::write(&mut *self.0.lock().unwrap(), data)
     }
-    driver::compile_input(sess, cfg, &input, &out, &None, None, control);
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
 
-    if no_run { return }
+/// Compiles the test program `test` into `outdir`, writing rustc's messages
+/// to `messages`, and only checking it if `check_only` is set. Returns the
+/// path of the target's libraries, needed to run it, if it compiled.
+fn compile(test: String, libs: SearchPaths, externs: core::Externs,
+           as_test_harness: bool, rustc_flags: &[String], check_only: bool,
+           outdir: &Path, messages: Arc<Mutex<Vec<u8>>>) -> Option<PathBuf> {
+    let (cfgs, cg) = parse_rustc_flags(rustc_flags);
+    let outdir = outdir.to_path_buf();
+
+    // rustc gives up on the first phase with errors by panicking, so it runs
+    // on a thread of its own. The session's options aren't `Send`, so they
+    // are made there too.
+    thread::spawn(move || {
+        io::set_panic(box Sink(messages.clone()));
+        let sessopts = config::Options {
+            maybe_sysroot: Some(env::current_exe().unwrap().parent().unwrap()
+                                                  .parent().unwrap().to_path_buf()),
+            search_paths: libs,
+            crate_types: vec!(config::CrateTypeExecutable),
+            output_types: vec!(config::OutputTypeExe),
+            externs: externs,
+            cfg: config::parse_cfgspecs(cfgs),
+            cg: config::CodegenOptions {
+                prefer_dynamic: true,
+                .. cg
+            },
+            test: as_test_harness,
+            unstable_features: get_unstable_features_setting(),
+            ..config::basic_options().clone()
+        };
 
-    // Run the code!
-    //
+        let input = config::Input::Str(test);
+        let emitter = diagnostic::EmitterWriter::new(box Sink(messages), None);
+        let codemap = CodeMap::new();
+        let diagnostic_handler = diagnostic::Handler::with_emitter(true, box emitter);
+        let span_diagnostic_handler =
+            diagnostic::SpanHandler::new(diagnostic_handler, codemap);
+
+        let sess = session::build_session_(sessopts,
+                                           None,
+                                           span_diagnostic_handler);
+        rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
+
+        let out = Some(outdir);
+        let cfg = config::build_configuration(&sess);
+        let libdir = sess.target_filesearch(PathKind::All).get_lib_path();
+        let mut control = driver::CompileController::basic();
+        if check_only {
+            control.after_analysis.stop = Compilation::Stop;
+        }
+        driver::compile_input(sess, cfg, &input, &out, &None, None, control);
+        libdir
+    }).join().ok()
+}
+
+/// Reads the `rustc-flag=` arguments of a test, which may be `--cfg` specs
+/// and codegen options, into the `--cfg` specs and the codegen options to
+/// compile it with. A flag rustc wouldn't accept fails the test.
+fn parse_rustc_flags(flags: &[String]) -> (Vec<String>, config::CodegenOptions) {
+    let mut cfgs = Vec::new();
+    let mut cg = config::basic_codegen_options();
+    let mut flags = flags.iter();
+    while let Some(flag) = flags.next() {
+        let (name, value) = match flag.find('=') {
+            Some(i) if flag.starts_with("--") => (&flag[..i], Some(&flag[i + 1..])),
+            _ if flag.starts_with("-C") && flag.len() > 2 => ("-C", Some(&flag[2..])),
+            _ => (&flag[..], None),
+        };
+        let value = match value.or_else(|| flags.next().map(|s| &s[..])) {
+            Some(value) => value,
+            None => panic!("the rustc flag `{}` of the test needs a value", name),
+        };
+        match name {
+            "--cfg" => {
+                if !is_cfg_spec(value) {
+                    panic!("invalid `--cfg` spec for the test: `{}`", value);
+                }
+                cfgs.push(value.to_string());
+            }
+            "-C" | "--codegen" => set_codegen_option(&mut cg, value),
+            _ => panic!("unsupported rustc flag for the test: `{}`, only `--cfg` and \
+                         codegen options may be given", flag),
+        }
+    }
+    (cfgs, cg)
+}
+
+/// Returns whether `spec` is a `--cfg` spec, `name` or `name="value"`.
+fn is_cfg_spec(spec: &str) -> bool {
+    let (name, value) = match spec.find('=') {
+        Some(i) => (&spec[..i], Some(&spec[i + 1..])),
+        None => (spec, None),
+    };
+    let is_ident = name.chars().enumerate().all(|(i, c)| {
+        c == '_' || c.is_alphabetic() || (i > 0 && c.is_numeric())
+    });
+    !name.is_empty() && is_ident && value.map_or(true, |value| {
+        value.len() >= 2 && value.starts_with('"') && value.ends_with('"')
+    })
+}
+
+/// Sets the codegen option `option`, written `key=value` or `key`, in `cg`
+/// the way `-C` does.
+fn set_codegen_option(cg: &mut config::CodegenOptions, option: &str) {
+    let mut parts = option.splitn(2, '=');
+    let key = parts.next().unwrap();
+    let value = parts.next();
+    let name = key.replace("-", "_");
+    let option = config::CG_OPTIONS.iter().find(|&&(candidate, _, _, _)| candidate == name);
+    let (setter, type_desc) = match option {
+        Some(&(_, setter, type_desc, _)) => (setter, type_desc),
+        None => panic!("unknown codegen option for the test: `{}`", key),
+    };
+    if !setter(cg, value) {
+        match (value, type_desc) {
+            (Some(value), Some(type_desc)) => {
+                panic!("incorrect value `{}` for codegen option `{}` of the test - {} \
+                        was expected", value, key, type_desc)
+            }
+            (Some(..), None) => panic!("codegen option `{}` of the test takes no value", key),
+            (None, _) => panic!("codegen option `{}` of the test requires a value", key),
+        }
+    }
+}
+
+/// Runs the compiled test `exe`, or the test number `batched` in it if it's
+/// a batch of tests, and checks that it fails if and only if it should panic.
+fn run_test_exe(exe: &Path, batched: Option<usize>, libdir: &Path, should_panic: bool) {
     // We're careful to prepend the *target* dylib search path to the child's
     // environment to ensure that the target loads the right libraries at
     // runtime. It would be a sad day if the *host* libraries were loaded as a
     // mistake.
-    let mut cmd = Command::new(&outdir.path().join("rust_out"));
+    let mut cmd = Command::new(exe);
     let var = DynamicLibrary::envvar();
     let newpath = {
         let path = env::var_os(var).unwrap_or(OsString::new());
         let mut path = env::split_paths(&path).collect::<Vec<_>>();
-        path.insert(0, libdir.clone());
+        path.insert(0, libdir.to_path_buf());
         env::join_paths(path).unwrap()
     };
     cmd.env(var, &newpath);
+    if let Some(i) = batched {
+        cmd.env(BATCHED_TEST_VAR, &i.to_string());
+    }
 
     match cmd.output() {
         Err(e) => panic!("couldn't run the test: {}{}", e,
@@ -261,6 +380,89 @@ fn runtest(test: &str, cratename: &str, libs: SearchPaths,
     }
 }
 
+/// The environment variable telling a batch of tests which of them to run.
+const BATCHED_TEST_VAR: &'static str = "RUSTDOC_BATCHED_TEST";
+
+/// Tests which are compiled together into one program, to save running
+/// rustc for each of them. The program is compiled when the first of them
+/// runs, and each test then runs it with the variable `BATCHED_TEST_VAR`
+/// set to its number.
+///
+/// If the tests don't compile together, for example because one of them
+/// doesn't compile, each is compiled on its own instead, so that its own
+/// errors are reported.
+struct Batch {
+    tests: Mutex<Vec<String>>,
+    /// The directory of the compiled program and the path of the target's
+    /// libraries, once the tests have been compiled.
+    compiled: Mutex<Option<Option<(TempDir, PathBuf)>>>,
+}
+
+impl Batch {
+    /// Returns whether the test `test` can be compiled as part of a batch.
+    /// Tests which set crate attributes or are compiled differently can't,
+    /// nor can those which may not compile.
+    fn can_add(test: &str, info: &LangString) -> bool {
+        !info.ignore && !info.compile_fail && !info.test_harness &&
+        info.rustc_flags.is_empty() && !test.contains("#!") &&
+        !test.contains("extern crate") && !test.contains("fn main")
+    }
+
+    /// Adds `test` to the batch, returning its number.
+    fn add(&self, test: String) -> usize {
+        let mut tests = self.tests.lock().unwrap();
+        tests.push(test);
+        tests.len() - 1
+    }
+
+    /// Compiles the batch if it hasn't been yet, returning the path of the
+    /// program and of the target's libraries if it compiled.
+    fn compile(&self, cratename: &str, libs: SearchPaths, externs: core::Externs,
+               opts: &TestOptions) -> Option<(PathBuf, PathBuf)> {
+        let mut compiled = self.compiled.lock().unwrap();
+        if compiled.is_none() {
+            let program = batch_program(&self.tests.lock().unwrap(), cratename, opts);
+            let outdir = TempDir::new("rustdoctest").ok().expect("rustdoc needs a tempdir");
+            // The errors of the tests are reported when they're compiled on
+            // their own.
+            let messages = Arc::new(Mutex::new(Vec::new()));
+            let libdir = compile(program, libs, externs, false, &[], false, outdir.path(),
+                                 messages);
+            *compiled = Some(libdir.map(|libdir| (outdir, libdir)));
+        }
+        compiled.as_ref().unwrap().as_ref().map(|&(ref outdir, ref libdir)| {
+            (outdir.path().join("rust_out"), libdir.clone())
+        })
+    }
+}
+
+/// Makes the program running the batched tests `tests`, each in a function
+/// of its own, chosen by the variable `BATCHED_TEST_VAR`.
+fn batch_program(tests: &[String], cratename: &str, opts: &TestOptions) -> String {
+    let mut prog = String::new();
+    for attr in &opts.attrs {
+        prog.push_str(&format!("#![{}]\n", attr));
+    }
+    if !opts.no_crate_inject && cratename != "std" &&
+       tests.iter().any(|test| test.contains(cratename)) {
+        prog.push_str(&format!("extern crate {};\n", cratename));
+    }
+    for (i, test) in tests.iter().enumerate() {
+        prog.push_str(&format!("fn test_{}() {{\n    {}\n}}\n", i,
+                               test.replace("\n", "\n    ")));
+    }
+    prog.push_str(&format!("fn main() {{\n    match &*::std::env::var(\"{}\").unwrap() {{\n",
+                           BATCHED_TEST_VAR));
+    for i in 0..tests.len() {
+        prog.push_str(&format!("        \"{0}\" => test_{0}(),\n", i));
+    }
+    prog.push_str("        _ => {}\n    }\n}\n");
+
+    info!("final batch program: {}", prog);
+
+    prog
+}
+
 pub fn maketest(s: &str, cratename: Option<&str>, dont_insert_main: bool,
                 opts: &TestOptions) -> String {
     let (crate_attrs, everything_else) = partition_source(s);
@@ -335,6 +537,20 @@ pub struct Collector {
     current_header: Option<String>,
     cratename: String,
     opts: TestOptions,
+    position: Option<Position>,
+    /// The lines of the source files read by `doc_start`.
+    sources: HashMap<String, Vec<String>>,
+    batch: Arc<Batch>,
+}
+
+/// Where the documentation being searched for tests is, to report tests by
+/// their place in the source.
+struct Position {
+    file: String,
+    line: usize,
+    /// Whether `line` is the line the documentation starts on, rather than
+    /// just the line of the documented item.
+    exact: bool,
 }
 
 impl Collector {
@@ -350,44 +566,113 @@ impl Collector {
             current_header: None,
             cratename: cratename,
             opts: opts,
+            position: None,
+            sources: HashMap::new(),
+            batch: Arc::new(Batch {
+                tests: Mutex::new(Vec::new()),
+                compiled: Mutex::new(None),
+            }),
         }
     }
 
-    pub fn add_test(&mut self, test: String,
-                    should_panic: bool, no_run: bool, should_ignore: bool,
-                    as_test_harness: bool) {
-        let name = if self.use_headers {
+    /// Sets the file and the line of it on which the documentation to be
+    /// searched for tests starts.
+    pub fn set_position(&mut self, file: String, line: usize) {
+        self.position = Some(Position { file: file, line: line, exact: true });
+    }
+
+    /// Adds the test `test`, found on the line `line` of the documentation,
+    /// counting from 0.
+    pub fn add_test(&mut self, test: String, line: usize, info: LangString) {
+        let mut name = if self.use_headers {
             let s = self.current_header.as_ref().map(|s| &**s).unwrap_or("");
             format!("{}_{}", s, self.cnt)
         } else {
             format!("{}_{}", self.names.join("::"), self.cnt)
         };
+        if let Some(ref pos) = self.position {
+            let line = if pos.exact { pos.line + line } else { pos.line };
+            name.push_str(&format!(" ({}:{})", pos.file, line));
+        }
         self.cnt += 1;
         let libs = self.libs.clone();
         let externs = self.externs.clone();
         let cratename = self.cratename.to_string();
         let opts = self.opts.clone();
+        let batch = self.batch.clone();
+        let batched = if Batch::can_add(&test, &info) {
+            Some(batch.add(test.clone()))
+        } else {
+            None
+        };
         debug!("Creating test {}: {}", name, test);
         self.tests.push(testing::TestDescAndFn {
             desc: testing::TestDesc {
                 name: testing::DynTestName(name),
-                ignore: should_ignore,
+                ignore: info.ignore,
                 // compiler failures are test failures
                 should_panic: testing::ShouldPanic::No,
+                timeout: None,
             },
             testfn: testing::DynTestFn(Box::new(move|| {
+                if let Some(i) = batched {
+                    let compiled = batch.compile(&cratename, libs.clone(), externs.clone(),
+                                                 &opts);
+                    if let Some((exe, libdir)) = compiled {
+                        if !info.no_run {
+                            run_test_exe(&exe, Some(i), &libdir, info.should_panic);
+                        }
+                        return
+                    }
+                }
                 runtest(&test,
                         &cratename,
                         libs,
                         externs,
-                        should_panic,
-                        no_run,
-                        as_test_harness,
+                        &info,
                         &opts);
             }))
         });
     }
 
+    /// Finds the line of the file of `span` on which the documentation `doc`
+    /// of the item at `span` starts, looking for its first line among the doc
+    /// comments just above the item, then among those inside it.
+    fn doc_start(&mut self, span: &clean::Span, doc: &str) -> Option<usize> {
+        let (skipped, first) = match doc.lines().enumerate().find(|&(_, l)| {
+            !l.trim().is_empty()
+        }) {
+            Some((i, l)) => (i, l.trim()),
+            None => return None,
+        };
+        if !self.sources.contains_key(&span.filename) {
+            let mut contents = String::new();
+            let lines = match File::open(&span.filename)
+                                   .and_then(|mut f| f.read_to_string(&mut contents)) {
+                Ok(_) => contents.lines().map(|l| l.to_string()).collect(),
+                Err(_) => Vec::new(),
+            };
+            self.sources.insert(span.filename.clone(), lines);
+        }
+        let lines = &self.sources[&span.filename];
+        let is_first = |line: &str| {
+            let line = line.trim();
+            (line.starts_with("///") || line.starts_with("//!")) && line[3..].trim() == first
+        };
+
+        // Lines are counted from 1, so the item starts at index `loline - 1`.
+        let item = cmp::min(span.loline.saturating_sub(1), lines.len());
+        let mut above = (0..item).rev().take_while(|&i| {
+            let line = lines[i].trim();
+            line.is_empty() || line.starts_with("//") || line.starts_with("#")
+        });
+        let found = match above.find(|&i| is_first(&lines[i][..])) {
+            Some(i) => Some(i),
+            None => (item..cmp::min(span.hiline, lines.len())).find(|&i| is_first(&lines[i][..])),
+        };
+        found.and_then(|i| (i + 1).checked_sub(skipped))
+    }
+
     pub fn register_header(&mut self, name: &str, level: u32) {
         if self.use_headers && level == 1 {
             // we use these headings as test names, so it's good if
@@ -418,6 +703,18 @@ impl DocFolder for Collector {
         match item.doc_value() {
             Some(doc) => {
                 self.cnt = 0;
+                self.position = Some(match self.doc_start(&item.source, doc) {
+                    Some(line) => Position {
+                        file: item.source.filename.clone(),
+                        line: line,
+                        exact: true,
+                    },
+                    None => Position {
+                        file: item.source.filename.clone(),
+                        line: item.source.loline,
+                        exact: false,
+                    },
+                });
                 markdown::find_testable_code(doc, &mut *self);
             }
             None => {}
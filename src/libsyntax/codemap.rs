@@ -343,8 +343,11 @@ pub struct MultiByteChar {
 pub struct FileMap {
     /// The name of the file that the source came from, source that doesn't
     /// originate from files has names between angle brackets by convention,
-    /// e.g. `<anon>`
+    /// e.g. `<anon>`. This is after `--remap-path-prefix` is applied.
     pub name: FileName,
+    /// The name of the file as it was loaded, which relative paths in `mod`
+    /// items and `include!` are resolved against
+    pub unmapped_name: FileName,
     /// The complete source code
     pub src: Option<Rc<String>>,
     /// The start position of this source in the CodeMap
@@ -360,6 +363,8 @@ pub struct FileMap {
 impl Encodable for FileMap {
     fn encode<S: Encoder>(&self, s: &mut S) -> Result<(), S::Error> {
         s.emit_struct("FileMap", 5, |s| {
+            // Only the remapped name is exported, so `unmapped_name` does not
+            // leak into crate metadata.
             try! { s.emit_struct_field("name", 0, |s| self.name.encode(s)) };
             try! { s.emit_struct_field("start_pos", 1, |s| self.start_pos.encode(s)) };
             try! { s.emit_struct_field("end_pos", 2, |s| self.end_pos.encode(s)) };
@@ -464,6 +469,7 @@ impl Decodable for FileMap {
                 d.read_struct_field("multibyte_chars", 4, |d| Decodable::decode(d))
             };
             Ok(FileMap {
+                unmapped_name: name.clone(),
                 name: name,
                 start_pos: start_pos,
                 end_pos: end_pos,
@@ -562,6 +568,34 @@ impl FileLoader for RealFileLoader {
     }
 }
 
+/// Replaces prefixes of file names, for `--remap-path-prefix`. Prefixes are
+/// matched textually, as with GCC's `-fdebug-prefix-map`, and when more than
+/// one matches, the one given last wins.
+#[derive(Clone)]
+pub struct FilePathMapping {
+    mapping: Vec<(String, String)>,
+}
+
+impl FilePathMapping {
+    pub fn empty() -> FilePathMapping {
+        FilePathMapping { mapping: vec![] }
+    }
+
+    pub fn new(mapping: Vec<(String, String)>) -> FilePathMapping {
+        FilePathMapping { mapping: mapping }
+    }
+
+    /// Returns `path` with its prefix replaced, and whether a prefix matched.
+    pub fn map_prefix(&self, path: &str) -> (String, bool) {
+        for &(ref from, ref to) in self.mapping.iter().rev() {
+            if path.starts_with(&from[..]) {
+                return (format!("{}{}", to, &path[from.len()..]), true);
+            }
+        }
+        (path.to_string(), false)
+    }
+}
+
 // _____________________________________________________________________________
 // CodeMap
 //
@@ -569,26 +603,37 @@ impl FileLoader for RealFileLoader {
 pub struct CodeMap {
     pub files: RefCell<Vec<Rc<FileMap>>>,
     expansions: RefCell<Vec<ExpnInfo>>,
-    file_loader: Box<FileLoader>
+    file_loader: Box<FileLoader>,
+    path_mapping: FilePathMapping,
 }
 
 impl CodeMap {
     pub fn new() -> CodeMap {
+        CodeMap::with_file_loader(Box::new(RealFileLoader))
+    }
+
+    pub fn with_file_loader(file_loader: Box<FileLoader>) -> CodeMap {
         CodeMap {
             files: RefCell::new(Vec::new()),
             expansions: RefCell::new(Vec::new()),
-            file_loader: Box::new(RealFileLoader)
+            file_loader: file_loader,
+            path_mapping: FilePathMapping::empty(),
         }
     }
 
-    pub fn with_file_loader(file_loader: Box<FileLoader>) -> CodeMap {
+    pub fn with_path_mapping(path_mapping: FilePathMapping) -> CodeMap {
         CodeMap {
             files: RefCell::new(Vec::new()),
             expansions: RefCell::new(Vec::new()),
-            file_loader: file_loader
+            file_loader: Box::new(RealFileLoader),
+            path_mapping: path_mapping,
         }
     }
 
+    pub fn path_mapping(&self) -> &FilePathMapping {
+        &self.path_mapping
+    }
+
     pub fn file_exists(&self, path: &Path) -> bool {
         self.file_loader.file_exists(path)
     }
@@ -620,9 +665,11 @@ impl CodeMap {
         }
 
         let end_pos = start_pos + src.len();
+        let (name, _) = self.path_mapping.map_prefix(&filename);
 
         let filemap = Rc::new(FileMap {
-            name: filename,
+            name: name,
+            unmapped_name: filename,
             src: Some(Rc::new(src)),
             start_pos: Pos::from_usize(start_pos),
             end_pos: Pos::from_usize(end_pos),
@@ -663,6 +710,7 @@ impl CodeMap {
         }
 
         let filemap = Rc::new(FileMap {
+            unmapped_name: filename.clone(),
             name: filename,
             src: None,
             start_pos: start_pos,
@@ -750,6 +798,12 @@ impl CodeMap {
         self.lookup_char_pos(sp.lo).file.name.to_string()
     }
 
+    /// The name of the file containing `sp`, before `--remap-path-prefix`
+    /// was applied to it.
+    pub fn span_to_unmapped_filename(&self, sp: Span) -> FileName {
+        self.lookup_char_pos(sp.lo).file.unmapped_name.to_string()
+    }
+
     pub fn span_to_lines(&self, sp: Span) -> FileLinesResult {
         if sp.lo > sp.hi {
             return Err(SpanLinesError::IllFormedSpan(sp));
@@ -838,7 +892,7 @@ impl CodeMap {
 
     pub fn get_filemap(&self, filename: &str) -> Rc<FileMap> {
         for fm in self.files.borrow().iter() {
-            if filename == fm.name {
+            if filename == fm.unmapped_name {
                 return fm.clone();
             }
         }
@@ -1227,4 +1281,24 @@ mod tests {
 
         assert_eq!(sstr, "blork.rs:2:1: 2:12");
     }
+
+    #[test]
+    fn t10() {
+        // Test map_prefix: the pair given last wins, even over a longer
+        // prefix, and a path that matches no prefix is left alone
+        let mapping = FilePathMapping::new(vec![
+            ("/home/user/src".to_string(), "/src".to_string()),
+            ("/home/user".to_string(), "/user".to_string()),
+            ("/tmp".to_string(), "/scratch".to_string()),
+        ]);
+
+        assert_eq!(mapping.map_prefix("/home/user/src/lib.rs"),
+                   ("/user/src/lib.rs".to_string(), true));
+        assert_eq!(mapping.map_prefix("/tmp/lib.rs"),
+                   ("/scratch/lib.rs".to_string(), true));
+        assert_eq!(mapping.map_prefix("/opt/lib.rs"),
+                   ("/opt/lib.rs".to_string(), false));
+        assert_eq!(FilePathMapping::empty().map_prefix("/tmp/lib.rs"),
+                   ("/tmp/lib.rs".to_string(), false));
+    }
 }
@@ -13,10 +13,11 @@ pub use self::RenderSpan::*;
 pub use self::ColorConfig::*;
 use self::Destination::*;
 
-use codemap::{self, COMMAND_LINE_SP, COMMAND_LINE_EXPN, Pos, Span};
+use codemap::{self, BytePos, COMMAND_LINE_SP, COMMAND_LINE_EXPN, NO_EXPANSION, Pos, Span};
 use diagnostics;
 
 use std::cell::{RefCell, Cell};
+use std::collections::{BTreeMap, HashMap};
 use std::{cmp, error, fmt};
 use std::io::prelude::*;
 use std::io;
@@ -63,6 +64,30 @@ impl RenderSpan {
     }
 }
 
+/// A suggested edit of the code, recorded by `span_suggestion` so that it can
+/// be applied to the source rather than only rendered.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CodeSuggestion {
+    pub msg: String,
+    /// The code to replace.
+    pub span: Span,
+    /// The code to splice in place of `span`, possibly empty.
+    pub replacement: String,
+    pub applicability: Applicability,
+}
+
+/// Whether a suggested edit can be made to the code without a human looking
+/// at it first.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Applicability {
+    /// The edit is what was meant, and the code compiles with it, as far as
+    /// this error is concerned. `-Z apply-suggestions` makes it.
+    MachineApplicable,
+    /// The edit shows what to do, but may not be what was meant or contains
+    /// placeholders to fill in. It is only rendered.
+    Unspecified,
+}
+
 #[derive(Clone, Copy)]
 pub enum ColorConfig {
     Auto,
@@ -119,6 +144,7 @@ impl error::Error for ExplicitBug {
 pub struct SpanHandler {
     pub handler: Handler,
     pub cm: codemap::CodeMap,
+    suggestions: RefCell<Vec<CodeSuggestion>>,
 }
 
 impl SpanHandler {
@@ -126,6 +152,7 @@ impl SpanHandler {
         SpanHandler {
             handler: handler,
             cm: cm,
+            suggestions: RefCell::new(Vec::new()),
         }
     }
     pub fn span_fatal(&self, sp: Span, msg: &str) -> FatalError {
@@ -163,7 +190,27 @@ impl SpanHandler {
     ///
     /// See `diagnostic::RenderSpan::Suggestion` for more information.
     pub fn span_suggestion(&self, sp: Span, msg: &str, suggestion: String) {
-        self.handler.custom_emit(&self.cm, Suggestion(sp, suggestion), msg, Help);
+        self.span_suggestion_with_applicability(sp, msg, suggestion,
+                                                Applicability::Unspecified)
+    }
+    /// Like `span_suggestion`, saying whether the edit may be made without
+    /// a human looking at it. The suggestion is only recorded if it is
+    /// printed, not if it belongs to a diagnostic left out as a duplicate.
+    pub fn span_suggestion_with_applicability(&self, sp: Span, msg: &str, suggestion: String,
+                                              applicability: Applicability) {
+        let replacement = suggestion.clone();
+        if self.handler.custom_emit(&self.cm, Suggestion(sp, suggestion), msg, Help) {
+            self.suggestions.borrow_mut().push(CodeSuggestion {
+                msg: msg.to_string(),
+                span: sp,
+                replacement: replacement,
+                applicability: applicability,
+            });
+        }
+    }
+    /// The suggestions made so far, in the order they were emitted.
+    pub fn suggestions(&self) -> Vec<CodeSuggestion> {
+        self.suggestions.borrow().clone()
     }
     pub fn fileline_note(&self, sp: Span, msg: &str) {
         self.handler.custom_emit(&self.cm, FileLine(sp), msg, Note);
@@ -183,13 +230,30 @@ impl SpanHandler {
     }
 }
 
+// An error or warning, by (is error, span, message).
+type DiagnosticKey = (bool, Option<(BytePos, BytePos)>, String);
+// A note or help, by (span, message).
+type NoteKey = (Option<(BytePos, BytePos)>, String);
+
 /// A handler deals with errors; certain errors
 /// (fatal, bug, unimpl) may cause immediate exit,
 /// others log errors for later reporting.
+///
+/// Identical errors and warnings at the same span are only reported once,
+/// together with the notes that follow them.
 pub struct Handler {
     err_count: Cell<usize>,
+    warn_count: Cell<usize>,
     emit: RefCell<Box<Emitter + Send>>,
-    pub can_emit_warnings: bool
+    pub can_emit_warnings: bool,
+    /// The number of errors after which to abort, if any.
+    error_limit: Cell<Option<usize>>,
+    /// Errors and warnings emitted so far, by (is error, span, message),
+    /// with the notes and helps attached to each of them.
+    emitted: RefCell<HashMap<DiagnosticKey, Vec<NoteKey>>>,
+    /// The error or warning that notes and helps are attached to, and
+    /// whether it was left out as a duplicate.
+    current: RefCell<Option<(DiagnosticKey, bool)>>,
 }
 
 impl Handler {
@@ -202,24 +266,46 @@ impl Handler {
     pub fn with_emitter(can_emit_warnings: bool, e: Box<Emitter + Send>) -> Handler {
         Handler {
             err_count: Cell::new(0),
+            warn_count: Cell::new(0),
             emit: RefCell::new(e),
-            can_emit_warnings: can_emit_warnings
+            can_emit_warnings: can_emit_warnings,
+            error_limit: Cell::new(None),
+            emitted: RefCell::new(HashMap::new()),
+            current: RefCell::new(None),
         }
     }
+    /// Makes the next error after `limit` of them abort compilation.
+    pub fn set_error_limit(&self, limit: Option<usize>) {
+        self.error_limit.set(limit);
+    }
     pub fn fatal(&self, msg: &str) -> ! {
         self.emit.borrow_mut().emit(None, msg, None, Fatal);
         panic!(FatalError);
     }
     pub fn err(&self, msg: &str) {
-        self.emit.borrow_mut().emit(None, msg, None, Error);
+        if self.should_emit(None, msg, Error) {
+            self.emit.borrow_mut().emit(None, msg, None, Error);
+        }
         self.bump_err_count();
     }
+    /// Counts an error just emitted, unless it was left out as a duplicate.
     pub fn bump_err_count(&self) {
-        self.err_count.set(self.err_count.get() + 1);
+        let duplicate = match *self.current.borrow() {
+            Some((_, duplicate)) => duplicate,
+            None => false,
+        };
+        if !duplicate {
+            self.err_count.set(self.err_count.get() + 1);
+        }
     }
     pub fn err_count(&self) -> usize {
         self.err_count.get()
     }
+    /// The number of warnings emitted, not counting those left out as
+    /// duplicates.
+    pub fn warn_count(&self) -> usize {
+        self.warn_count.get()
+    }
     pub fn has_errors(&self) -> bool {
         self.err_count.get() > 0
     }
@@ -236,13 +322,19 @@ impl Handler {
         self.fatal(&s[..]);
     }
     pub fn warn(&self, msg: &str) {
-        self.emit.borrow_mut().emit(None, msg, None, Warning);
+        if self.should_emit(None, msg, Warning) {
+            self.emit.borrow_mut().emit(None, msg, None, Warning);
+        }
     }
     pub fn note(&self, msg: &str) {
-        self.emit.borrow_mut().emit(None, msg, None, Note);
+        if self.should_emit(None, msg, Note) {
+            self.emit.borrow_mut().emit(None, msg, None, Note);
+        }
     }
     pub fn help(&self, msg: &str) {
-        self.emit.borrow_mut().emit(None, msg, None, Help);
+        if self.should_emit(None, msg, Help) {
+            self.emit.borrow_mut().emit(None, msg, None, Help);
+        }
     }
     pub fn bug(&self, msg: &str) -> ! {
         self.emit.borrow_mut().emit(None, msg, None, Bug);
@@ -256,6 +348,7 @@ impl Handler {
                 msg: &str,
                 lvl: Level) {
         if lvl == Warning && !self.can_emit_warnings { return }
+        if !self.should_emit(cmsp.map(|(_, sp)| sp), msg, lvl) { return }
         self.emit.borrow_mut().emit(cmsp, msg, None, lvl);
     }
     pub fn emit_with_code(&self,
@@ -264,12 +357,66 @@ impl Handler {
                           code: &str,
                           lvl: Level) {
         if lvl == Warning && !self.can_emit_warnings { return }
+        if !self.should_emit(cmsp.map(|(_, sp)| sp), msg, lvl) { return }
         self.emit.borrow_mut().emit(cmsp, msg, Some(code), lvl);
     }
+    /// Returns whether the diagnostic was emitted, rather than left out.
     pub fn custom_emit(&self, cm: &codemap::CodeMap,
-                       sp: RenderSpan, msg: &str, lvl: Level) {
-        if lvl == Warning && !self.can_emit_warnings { return }
+                       sp: RenderSpan, msg: &str, lvl: Level) -> bool {
+        if lvl == Warning && !self.can_emit_warnings { return false }
+        if !self.should_emit(Some(sp.span()), msg, lvl) { return false }
         self.emit.borrow_mut().custom_emit(cm, sp, msg, lvl);
+        true
+    }
+
+    /// Decides whether a diagnostic is worth emitting, and aborts when an
+    /// error goes over the error limit.
+    fn should_emit(&self, sp: Option<Span>, msg: &str, lvl: Level) -> bool {
+        match lvl {
+            Error | Warning => {
+                if lvl == Error {
+                    if let Some(limit) = self.error_limit.get() {
+                        if self.err_count.get() >= limit {
+                            self.fatal(&format!("aborting after {} errors because the \
+                                                 error limit was reached", limit));
+                        }
+                    }
+                }
+                let key = (lvl == Error, sp.map(|sp| (sp.lo, sp.hi)), msg.to_string());
+                let mut emitted = self.emitted.borrow_mut();
+                let duplicate = emitted.contains_key(&key);
+                if !duplicate {
+                    emitted.insert(key.clone(), vec![]);
+                    if lvl == Warning {
+                        self.warn_count.set(self.warn_count.get() + 1);
+                    }
+                }
+                *self.current.borrow_mut() = Some((key, duplicate));
+                !duplicate
+            }
+            Note | Help => {
+                // A note or help is left out only if it is one of those
+                // which came with the first copy of a duplicate diagnostic.
+                let note = (sp.map(|sp| (sp.lo, sp.hi)), msg.to_string());
+                let mut emitted = self.emitted.borrow_mut();
+                let mut current = self.current.borrow_mut();
+                match *current {
+                    Some((ref key, false)) => {
+                        emitted.get_mut(key).unwrap().push(note);
+                        return true;
+                    }
+                    Some((ref key, true)) if emitted[key].contains(&note) => return false,
+                    _ => {}
+                }
+                // Anything else stands on its own.
+                *current = None;
+                true
+            }
+            Fatal | Bug => {
+                *self.current.borrow_mut() = None;
+                true
+            }
+        }
     }
 }
 
@@ -806,10 +953,57 @@ pub fn expect<T, M>(diag: &SpanHandler, opt: Option<T>, msg: M) -> T where
     }
 }
 
+/// Rewrites the sources in `cm` with the given suggestions applied, returning
+/// the unmapped name and new contents of every file that a suggestion applies to.
+///
+/// Only machine-applicable suggestions are applied. Those inside macro
+/// expansions, or overlapping an earlier suggestion in the same file, are
+/// skipped since there is no single right way to apply them.
+pub fn apply_suggestions(cm: &codemap::CodeMap,
+                         suggestions: &[CodeSuggestion]) -> Vec<(String, String)> {
+    let mut edits = BTreeMap::new();
+    for suggestion in suggestions {
+        let sp = suggestion.span;
+        if suggestion.applicability != Applicability::MachineApplicable ||
+           sp.expn_id != NO_EXPANSION || sp.lo > sp.hi {
+            continue;
+        }
+        let lo = cm.lookup_byte_offset(sp.lo);
+        let hi = cm.lookup_byte_offset(sp.hi);
+        if lo.fm.unmapped_name != hi.fm.unmapped_name || lo.fm.src.is_none() {
+            continue;
+        }
+        // The file is written where it was read from, whatever name
+        // `--remap-path-prefix` gives it in diagnostics.
+        edits.entry(lo.fm.unmapped_name.clone()).or_insert((lo.fm.clone(), vec![]))
+             .1.push((lo.pos.to_usize(), hi.pos.to_usize(), &suggestion.replacement[..]));
+    }
+
+    edits.into_iter().map(|(name, (fm, mut edits))| {
+        let src = fm.src.as_ref().unwrap();
+        edits.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+        edits.dedup();
+
+        let mut out = String::with_capacity(src.len());
+        let mut pos = 0;
+        for (lo, hi, replacement) in edits {
+            if lo < pos {
+                continue;
+            }
+            out.push_str(&src[pos..lo]);
+            out.push_str(replacement);
+            pos = hi;
+        }
+        out.push_str(&src[pos..]);
+        (name, out)
+    }).collect()
+}
+
 #[cfg(test)]
 mod test {
     use super::{EmitterWriter, highlight_lines, Level};
-    use codemap::{mk_sp, CodeMap, BytePos};
+    use super::{apply_suggestions, Applicability, CodeSuggestion, Handler, SpanHandler};
+    use codemap::{mk_sp, CodeMap, BytePos, FilePathMapping};
     use std::sync::{Arc, Mutex};
     use std::io::{self, Write};
     use std::str::from_utf8;
@@ -865,4 +1059,91 @@ mod test {
                          dummy.txt:11 \n\
                          dummy.txt:12 \n".as_bytes());
     }
+
+    #[test]
+    fn test_apply_suggestions() {
+        let cm = CodeMap::new();
+        let content = "use std::fmt;\nfn main() { let x = 1; x = 2; }\n";
+        cm.new_filemap("dummy.rs".to_string(), content.to_string());
+        let suggest = |lo, hi, replacement: &str| CodeSuggestion {
+            msg: String::new(),
+            span: mk_sp(BytePos(lo), BytePos(hi)),
+            replacement: replacement.to_string(),
+            applicability: Applicability::MachineApplicable,
+        };
+        let suggestions = [
+            suggest(30, 31, "mut x"),
+            suggest(0, 14, ""),
+            // Overlaps the edit above, so it is dropped.
+            suggest(4, 7, "core"),
+            // Duplicates are only applied once.
+            suggest(30, 31, "mut x"),
+            // Suggestions which may not be right are only shown.
+            CodeSuggestion {
+                applicability: Applicability::Unspecified,
+                ..suggest(40, 45, "y")
+            },
+        ];
+
+        let files = apply_suggestions(&cm, &suggestions);
+        assert_eq!(files, vec![("dummy.rs".to_string(),
+                                "fn main() { let mut x = 1; x = 2; }\n".to_string())]);
+    }
+
+    #[test]
+    fn test_apply_suggestions_to_remapped_file() {
+        let mapping = FilePathMapping::new(vec![("src/".to_string(), "/remapped/".to_string())]);
+        let cm = CodeMap::with_path_mapping(mapping);
+        cm.new_filemap("src/dummy.rs".to_string(), "fn main() { let x = 1; }\n".to_string());
+        let suggestions = [CodeSuggestion {
+            msg: String::new(),
+            span: mk_sp(BytePos(16), BytePos(17)),
+            replacement: "_x".to_string(),
+            applicability: Applicability::MachineApplicable,
+        }];
+
+        // The file is written back where it was read from.
+        let files = apply_suggestions(&cm, &suggestions);
+        assert_eq!(files, vec![("src/dummy.rs".to_string(),
+                                "fn main() { let _x = 1; }\n".to_string())]);
+    }
+
+    #[test]
+    fn test_duplicate_diagnostics_are_emitted_once() {
+        struct Sink(Arc<Mutex<Vec<u8>>>);
+        impl Write for Sink {
+            fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+                Write::write(&mut *self.0.lock().unwrap(), data)
+            }
+            fn flush(&mut self) -> io::Result<()> { Ok(()) }
+        }
+        let data = Arc::new(Mutex::new(Vec::new()));
+        let ew = EmitterWriter::new(Box::new(Sink(data.clone())), None);
+        let handler = Handler::with_emitter(true, Box::new(ew));
+        let cm = CodeMap::new();
+        let fm = cm.new_filemap("dummy.rs".to_string(), "let x = y;\n".to_string());
+        fm.next_line(BytePos(0));
+        let sp = mk_sp(BytePos(8), BytePos(9));
+        let sh = SpanHandler::new(handler, cm);
+
+        for _ in 0..2 {
+            sh.span_err(sp, "unresolved name `y`");
+            sh.span_note(sp, "the note goes with its error");
+            sh.span_suggestion_with_applicability(sp, "use `z`:", "z".to_string(),
+                                                  Applicability::MachineApplicable);
+        }
+        // Only the notes which came with the first copy are left out.
+        sh.span_err(sp, "unresolved name `y`");
+        sh.span_note(sp, "this note is new");
+        sh.span_err(mk_sp(BytePos(4), BytePos(5)), "unresolved name `y`");
+
+        assert_eq!(sh.handler.err_count(), 2);
+        // Nor is the suggestion of the copy recorded.
+        assert_eq!(sh.suggestions().len(), 1);
+        let vec = data.lock().unwrap().clone();
+        let output = from_utf8(&vec).unwrap();
+        assert_eq!(output.matches("error: unresolved name").count(), 2);
+        assert_eq!(output.matches("note: the note goes").count(), 1);
+        assert_eq!(output.matches("note: this note is new").count(), 1);
+    }
 }
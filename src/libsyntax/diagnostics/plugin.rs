@@ -183,14 +183,18 @@ pub fn expand_build_diagnostic_array<'cx>(ecx: &'cx mut ExtCtxt,
     // Construct the output expression.
     let (count, expr) =
         with_registered_diagnostics(|diagnostics| {
+            // Codes without a long description are included with an empty
+            // one, so that the registry knows about every code.
             let descriptions: Vec<P<ast::Expr>> =
-                diagnostics.iter().filter_map(|(code, info)| {
-                    info.description.map(|description| {
-                        ecx.expr_tuple(span, vec![
-                            ecx.expr_str(span, token::get_name(*code)),
-                            ecx.expr_str(span, token::get_name(description))
-                        ])
-                    })
+                diagnostics.iter().map(|(code, info)| {
+                    let description = match info.description {
+                        Some(description) => token::get_name(description),
+                        None => token::intern_and_get_ident(""),
+                    };
+                    ecx.expr_tuple(span, vec![
+                        ecx.expr_str(span, token::get_name(*code)),
+                        ecx.expr_str(span, description)
+                    ])
                 }).collect();
             (descriptions.len(), ecx.expr_vec(span, descriptions))
         });
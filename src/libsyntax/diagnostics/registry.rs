@@ -10,6 +10,9 @@
 
 use std::collections::HashMap;
 
+/// The error codes known to the compiler, with their long explanations.
+///
+/// Codes registered without an explanation have an empty description.
 #[derive(Clone)]
 pub struct Registry {
     descriptions: HashMap<&'static str, &'static str>
@@ -21,6 +24,19 @@ impl Registry {
     }
 
     pub fn find_description(&self, code: &str) -> Option<&'static str> {
-        self.descriptions.get(code).cloned()
+        self.descriptions.get(code).cloned().and_then(explanation)
     }
+
+    /// Every registered code and its explanation, if any, sorted by code.
+    pub fn all_descriptions(&self) -> Vec<(&'static str, Option<&'static str>)> {
+        let mut all: Vec<_> = self.descriptions.iter().map(|(&code, &description)| {
+            (code, explanation(description))
+        }).collect();
+        all.sort_by(|a, b| a.0.cmp(b.0));
+        all
+    }
+}
+
+fn explanation(description: &'static str) -> Option<&'static str> {
+    if description.is_empty() { None } else { Some(description) }
 }
@@ -158,6 +158,10 @@ const KNOWN_FEATURES: &'static [(&'static str, &'static str, Status)] = &[
 
     // Allows using #[prelude_import] on glob `use` items.
     ("prelude_import", "1.2.0", Active),
+
+    // Allows overriding the harness timeout of a single test with
+    // #[timeout = N].
+    ("test_timeout", "1.3.0", Active),
 ];
 // (changing above list without updating src/doc/reference.md makes @cmr sad)
 
@@ -246,6 +250,9 @@ pub const KNOWN_ATTRIBUTES: &'static [(&'static str, AttributeType)] = &[
     ("fundamental", Gated("fundamental",
                           "the `#[fundamental]` attribute \
                            is an experimental feature")),
+    ("timeout", Gated("test_timeout",
+                      "the `#[timeout]` attribute \
+                       is an experimental feature")),
 
     // FIXME: #14408 whitelist docs since rustdoc looks at them
     ("doc", Whitelisted),
@@ -0,0 +1,246 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! A JSON emitter for errors.
+//!
+//! This works by converting errors to a simplified structural format (see the
+//! structs at the start of the file) and then serialising them. Notes and
+//! helps following an error or a warning are attached to it as children, so
+//! each top-level diagnostic is printed as a single JSON object on its own
+//! line once the next one starts.
+//!
+//! The format of the JSON output should be considered *unstable*.
+
+use codemap::{CodeMap, Span, COMMAND_LINE_EXPN};
+use diagnostic::{Emitter, Level, Bug, Fatal, Note, Help};
+use diagnostic::{RenderSpan, FullSpan, EndSpan, Suggestion, FileLine};
+use diagnostics::registry::Registry;
+
+use serialize::json::as_json;
+use std::io::{self, Write};
+
+pub struct JsonEmitter {
+    dst: Box<Write + Send>,
+    registry: Option<Registry>,
+    pending: Option<Diagnostic>,
+}
+
+impl JsonEmitter {
+    pub fn stderr(registry: Option<Registry>) -> JsonEmitter {
+        JsonEmitter::new(Box::new(io::stderr()), registry)
+    }
+
+    pub fn new(dst: Box<Write + Send>, registry: Option<Registry>) -> JsonEmitter {
+        JsonEmitter {
+            dst: dst,
+            registry: registry,
+            pending: None,
+        }
+    }
+
+    fn push(&mut self, level: Level, diag: Diagnostic) {
+        match level {
+            Note | Help if self.pending.is_some() => {
+                self.pending.as_mut().unwrap().children.push(diag);
+                return;
+            }
+            _ => {}
+        }
+
+        self.flush_or_panic();
+        self.pending = Some(diag);
+
+        // Fatal errors and bugs unwind right after being emitted.
+        if level == Fatal || level == Bug {
+            self.flush_or_panic();
+        }
+    }
+
+    fn flush_pending(&mut self) -> io::Result<()> {
+        match self.pending.take() {
+            Some(diag) => {
+                try!(write!(&mut self.dst, "{}\n", as_json(&diag)));
+                self.dst.flush()
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn flush_or_panic(&mut self) {
+        if let Err(e) = self.flush_pending() {
+            panic!("failed to print diagnostics: {:?}", e);
+        }
+    }
+}
+
+impl Emitter for JsonEmitter {
+    fn emit(&mut self, cmsp: Option<(&CodeMap, Span)>,
+            msg: &str, code: Option<&str>, level: Level) {
+        let spans = match cmsp {
+            Some((cm, sp)) => DiagnosticSpan::from_span(cm, sp, sp, None).into_iter().collect(),
+            None => vec![],
+        };
+        let diag = Diagnostic::new(msg, code, level, spans, self.registry.as_ref());
+        self.push(level, diag);
+    }
+
+    fn custom_emit(&mut self, cm: &CodeMap, sp: RenderSpan, msg: &str, level: Level) {
+        let span = match sp {
+            FullSpan(sp) | FileLine(sp) => DiagnosticSpan::from_span(cm, sp, sp, None),
+            EndSpan(sp) => {
+                let end = Span { lo: sp.hi, hi: sp.hi, expn_id: sp.expn_id };
+                DiagnosticSpan::from_span(cm, sp, end, None)
+            }
+            Suggestion(sp, suggestion) => DiagnosticSpan::from_span(cm, sp, sp, Some(suggestion)),
+        };
+        let diag = Diagnostic::new(msg, None, level, span.into_iter().collect(), None);
+        self.push(level, diag);
+    }
+}
+
+impl Drop for JsonEmitter {
+    fn drop(&mut self) {
+        // There is nobody left to report a failure to.
+        let _ = self.flush_pending();
+    }
+}
+
+// The following data types are provided just for serialisation.
+
+#[derive(RustcEncodable)]
+struct Diagnostic {
+    /// The primary error message.
+    message: String,
+    code: Option<DiagnosticCode>,
+    /// "error: internal compiler error", "error", "warning", "note", "help".
+    level: String,
+    spans: Vec<DiagnosticSpan>,
+    /// Notes and helps attached to this diagnostic.
+    children: Vec<Diagnostic>,
+}
+
+#[derive(RustcEncodable)]
+struct DiagnosticSpan {
+    file_name: String,
+    /// Byte offsets into the file, `byte_end` excluded.
+    byte_start: u32,
+    byte_end: u32,
+    /// 1-based.
+    line_start: usize,
+    line_end: usize,
+    /// 1-based, in characters.
+    column_start: usize,
+    column_end: usize,
+    /// If we are suggesting a replacement, this will contain text
+    /// that should be sliced in atop this span.
+    suggested_replacement: Option<String>,
+}
+
+#[derive(RustcEncodable)]
+struct DiagnosticCode {
+    /// The code itself.
+    code: String,
+    /// An explanation for the code.
+    explanation: Option<&'static str>,
+}
+
+impl Diagnostic {
+    fn new(msg: &str, code: Option<&str>, level: Level,
+           spans: Vec<DiagnosticSpan>, registry: Option<&Registry>) -> Diagnostic {
+        Diagnostic {
+            message: msg.to_string(),
+            code: code.map(|code| DiagnosticCode {
+                code: code.to_string(),
+                explanation: registry.and_then(|registry| registry.find_description(code)),
+            }),
+            level: level.to_string(),
+            spans: spans,
+            children: vec![],
+        }
+    }
+}
+
+impl DiagnosticSpan {
+    /// The span cited is `sp`, but it is reported as covering `lo` to `hi`
+    /// of `range`, which differs for `EndSpan`s.
+    fn from_span(cm: &CodeMap, sp: Span, range: Span,
+                 suggestion: Option<String>) -> Option<DiagnosticSpan> {
+        // Command line options and dummy spans have no source to point at.
+        if sp.expn_id == COMMAND_LINE_EXPN || cm.files.borrow().is_empty() {
+            return None;
+        }
+
+        let start = cm.lookup_char_pos(range.lo);
+        let end = cm.lookup_char_pos(range.hi);
+        Some(DiagnosticSpan {
+            file_name: start.file.name.clone(),
+            byte_start: cm.lookup_byte_offset(range.lo).pos.0,
+            byte_end: cm.lookup_byte_offset(range.hi).pos.0,
+            line_start: start.line,
+            line_end: end.line,
+            column_start: start.col.0 + 1,
+            column_end: end.col.0 + 1,
+            suggested_replacement: suggestion,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::JsonEmitter;
+    use codemap::{mk_sp, CodeMap, BytePos};
+    use diagnostic::{Emitter, Suggestion, Error, Note, Help};
+    use serialize::json::{self, Json};
+    use std::io::{self, Write};
+    use std::str::from_utf8;
+    use std::sync::{Arc, Mutex};
+
+    struct Sink(Arc<Mutex<Vec<u8>>>);
+    impl Write for Sink {
+        fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+            Write::write(&mut *self.0.lock().unwrap(), data)
+        }
+        fn flush(&mut self) -> io::Result<()> { Ok(()) }
+    }
+
+    #[test]
+    fn notes_are_attached_to_the_preceding_error() {
+        let data = Arc::new(Mutex::new(Vec::new()));
+        let cm = CodeMap::new();
+        let content = "let x = 1;\nx = 2;\n";
+        let file = cm.new_filemap("dummy.rs".to_string(), content.to_string());
+        file.next_line(BytePos(11));
+        let assign = mk_sp(BytePos(11), BytePos(16));
+        let decl = mk_sp(BytePos(4), BytePos(5));
+
+        {
+            let mut emitter = JsonEmitter::new(Box::new(Sink(data.clone())), None);
+            emitter.emit(Some((&cm, assign)), "re-assignment of immutable variable `x`",
+                         Some("E0384"), Error);
+            emitter.emit(Some((&cm, decl)), "prior assignment occurs here", None, Note);
+            emitter.custom_emit(&cm, Suggestion(decl, "mut x".to_string()),
+                                "make this binding mutable", Help);
+        }
+
+        let output = data.lock().unwrap().clone();
+        let output = from_utf8(&output).unwrap();
+        assert_eq!(output.lines().count(), 1);
+
+        let diag = json::from_str(output).unwrap();
+        assert_eq!(diag["level"], Json::String("error".to_string()));
+        assert_eq!(diag["code"]["code"], Json::String("E0384".to_string()));
+        assert_eq!(diag["spans"][0]["line_start"], Json::U64(2));
+        assert_eq!(diag["spans"][0]["column_start"], Json::U64(1));
+        assert_eq!(diag["spans"][0]["byte_end"], Json::U64(16));
+        assert_eq!(diag["children"][0]["level"], Json::String("note".to_string()));
+        assert_eq!(diag["children"][1]["spans"][0]["suggested_replacement"],
+                   Json::String("mut x".to_string()));
+    }
+}
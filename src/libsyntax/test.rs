@@ -47,7 +47,8 @@ struct Test {
     path: Vec<ast::Ident> ,
     bench: bool,
     ignore: bool,
-    should_panic: ShouldPanic
+    should_panic: ShouldPanic,
+    timeout: Option<u64>,
 }
 
 struct TestCtxt<'a> {
@@ -134,7 +135,8 @@ impl<'a> fold::Folder for TestHarnessGenerator<'a> {
                         path: self.cx.path.clone(),
                         bench: is_bench_fn(&self.cx, &*i),
                         ignore: is_ignored(&*i),
-                        should_panic: should_panic(&*i)
+                        should_panic: should_panic(&*i),
+                        timeout: timeout(&self.cx, &*i),
                     };
                     self.cx.testfns.push(test);
                     self.tests.push(i.ident);
@@ -398,6 +400,22 @@ fn should_panic(i: &ast::Item) -> ShouldPanic {
     }
 }
 
+fn timeout(cx: &TestCtxt, i: &ast::Item) -> Option<u64> {
+    i.attrs.iter().find(|attr| attr.check_name("timeout")).and_then(|attr| {
+        match attr.node.value.node {
+            ast::MetaNameValue(_, ref lit) => match lit.node {
+                ast::LitInt(secs, ast::UnsuffixedIntLit(_)) if secs > 0 => return Some(secs),
+                _ => {}
+            },
+            _ => {}
+        }
+        cx.span_diagnostic.span_err(attr.span, "malformed `#[timeout]` attribute, \
+                                                expected a number of seconds, \
+                                                e.g. `#[timeout = 60]`");
+        None
+    })
+}
+
 /*
 
 We're going to be building a module that looks more or less like:
@@ -642,13 +660,22 @@ fn mk_test_desc_and_fn_rec(cx: &TestCtxt, test: &Test) -> P<ast::Expr> {
         }
     };
 
+    let timeout_expr = match test.timeout {
+        Some(secs) => {
+            let secs = ecx.expr_lit(span, ast::LitInt(secs, ast::UnsignedIntLit(ast::TyU64)));
+            ecx.expr_some(span, secs)
+        }
+        None => ecx.expr_none(span),
+    };
+
     // self::test::TestDesc { ... }
     let desc_expr = ecx.expr_struct(
         span,
         test_path("TestDesc"),
         vec![field("name", name_expr),
              field("ignore", ignore_expr),
-             field("should_panic", fail_expr)]);
+             field("should_panic", fail_expr),
+             field("timeout", timeout_expr)]);
 
 
     let mut visible_path = match cx.toplevel_reexport {
@@ -39,8 +39,10 @@
 #![feature(duration)]
 #![feature(duration_span)]
 #![feature(fnbox)]
+#![feature(hashmap_hasher)]
 #![feature(iter_cmp)]
 #![feature(libc)]
+#![feature(park_timeout)]
 #![feature(rt)]
 #![feature(rustc_private)]
 #![feature(set_stdio)]
@@ -54,8 +56,10 @@ extern crate libc;
 
 pub use self::TestFn::*;
 pub use self::ColorConfig::*;
+pub use self::OutputFormat::*;
 pub use self::TestResult::*;
 pub use self::TestName::*;
+pub use self::MetricChange::*;
 use self::TestEvent::*;
 use self::NamePadding::*;
 use self::OutputLocation::*;
@@ -63,6 +67,7 @@ use self::OutputLocation::*;
 use stats::Stats;
 use getopts::{OptGroup, optflag, optopt};
 use serialize::Encodable;
+use serialize::json::{self, Json, ToJson};
 use std::boxed::FnBox;
 use term::Terminal;
 use term::color::{Color, RED, YELLOW, GREEN, CYAN};
@@ -70,13 +75,18 @@ use term::color::{Color, RED, YELLOW, GREEN, CYAN};
 use std::any::Any;
 use std::cmp;
 use std::collections::BTreeMap;
+use std::collections::hash_state::{HashState, RandomState};
 use std::env;
+use std::f64;
 use std::fmt;
-use std::fs::File;
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher, SipHasher};
 use std::io::prelude::*;
 use std::io;
 use std::iter::repeat;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::mpsc::{channel, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
@@ -85,8 +95,9 @@ use std::time::Duration;
 // to be used by rustc to compile tests in libtest
 pub mod test {
     pub use {Bencher, TestName, TestResult, TestDesc,
-             TestDescAndFn, TestOpts, TrFailed, TrIgnored, TrOk,
-             Metric, MetricMap,
+             TestDescAndFn, TestOpts, TrFailed, TrIgnored, TrOk, TrTimedOut,
+             Metric, MetricMap, MetricChange, MetricDiff,
+             MetricAdded, MetricRemoved, Improvement, Regression, LikelyNoise,
              StaticTestFn, StaticTestName, DynTestName, DynTestFn,
              run_test, test_main, test_main_static, filter_tests,
              parse_opts, StaticBenchFn, ShouldPanic};
@@ -208,6 +219,9 @@ pub struct TestDesc {
     pub name: TestName,
     pub ignore: bool,
     pub should_panic: ShouldPanic,
+    /// Number of seconds after which this test is reported as timed out,
+    /// overriding `--test-timeout`.
+    pub timeout: Option<u64>,
 }
 
 unsafe impl Send for TestDesc {}
@@ -233,6 +247,18 @@ impl Metric {
 #[derive(PartialEq)]
 pub struct MetricMap(BTreeMap<String,Metric>);
 
+/// Analysis of a single change in metric
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum MetricChange {
+    LikelyNoise,
+    MetricAdded,
+    MetricRemoved,
+    Improvement(f64),
+    Regression(f64)
+}
+
+pub type MetricDiff = BTreeMap<String,MetricChange>;
+
 impl Clone for MetricMap {
     fn clone(&self) -> MetricMap {
         let MetricMap(ref map) = *self;
@@ -282,14 +308,41 @@ pub enum ColorConfig {
     NeverColor,
 }
 
+#[derive(Copy, Clone, PartialEq)]
+pub enum OutputFormat {
+    PrettyFormat,
+    JsonFormat,
+    JunitFormat,
+}
+
+/// The percentage by which a benchmark may regress against the baseline
+/// before the run fails, unless `--regression-threshold` says otherwise.
+pub const DEFAULT_REGRESSION_THRESHOLD: f64 = 5.0;
+
 pub struct TestOpts {
     pub filter: Option<String>,
+    pub filter_exact: bool,
     pub run_ignored: bool,
     pub run_tests: bool,
     pub bench_benchmarks: bool,
     pub logfile: Option<PathBuf>,
     pub nocapture: bool,
     pub color: ColorConfig,
+    pub format: OutputFormat,
+    pub junit_xml: Option<PathBuf>,
+    pub test_timeout: Option<Duration>,
+    pub isolate: bool,
+    /// Run only the `i`th of `n` deterministic slices of the tests, counting
+    /// from 1.
+    pub shard: Option<(usize, usize)>,
+    /// Run the tests in an order derived from this seed instead of sorted
+    /// by name.
+    pub shuffle_seed: Option<u64>,
+    pub save_baseline: Option<PathBuf>,
+    pub compare_baseline: Option<PathBuf>,
+    /// Regressions against `compare_baseline` larger than this percentage
+    /// fail the run.
+    pub regression_threshold: f64,
 }
 
 impl TestOpts {
@@ -297,12 +350,22 @@ impl TestOpts {
     fn new() -> TestOpts {
         TestOpts {
             filter: None,
+            filter_exact: false,
             run_ignored: false,
             run_tests: false,
             bench_benchmarks: false,
             logfile: None,
             nocapture: false,
             color: AutoColor,
+            format: PrettyFormat,
+            junit_xml: None,
+            test_timeout: None,
+            isolate: false,
+            shard: None,
+            shuffle_seed: None,
+            save_baseline: None,
+            compare_baseline: None,
+            regression_threshold: DEFAULT_REGRESSION_THRESHOLD,
         }
     }
 }
@@ -312,6 +375,7 @@ pub type OptRes = Result<TestOpts, String>;
 
 fn optgroups() -> Vec<getopts::OptGroup> {
     vec!(getopts::optflag("", "ignored", "Run ignored tests"),
+      getopts::optflag("", "exact", "Exactly match filters rather than by substring"),
       getopts::optflag("", "test", "Run tests and not benchmarks"),
       getopts::optflag("", "bench", "Run benchmarks instead of tests"),
       getopts::optflag("h", "help", "Display this message (longer with --help)"),
@@ -322,7 +386,29 @@ fn optgroups() -> Vec<getopts::OptGroup> {
       getopts::optopt("", "color", "Configure coloring of output:
             auto   = colorize if stdout is a tty and tests are run on serially (default);
             always = always colorize output;
-            never  = never colorize output;", "auto|always|never"))
+            never  = never colorize output;", "auto|always|never"),
+      getopts::optopt("", "format", "Configure formatting of output:
+            pretty = print human-readable test results (default);
+            json   = print one JSON object per test event;
+            junit  = print a JUnit XML report once all tests have run;", "pretty|json|junit"),
+      getopts::optopt("", "junit-xml", "Also write a JUnit XML report to the \
+                                        specified file", "PATH"),
+      getopts::optopt("", "test-timeout", "Report tests still running after \
+                                           SECS seconds as timed out", "SECS"),
+      getopts::optflag("", "isolate", "Run each test in its own process, so that \
+                                       crashing tests do not abort the whole run"),
+      getopts::optopt("", "shard", "Only run the Ith of N deterministic slices \
+                                    of the tests", "I/N"),
+      getopts::optflag("", "shuffle", "Run tests in a random order"),
+      getopts::optopt("", "shuffle-seed", "Run tests in a random order derived \
+                                           from SEED (implies --shuffle)", "SEED"),
+      getopts::optopt("", "save-baseline", "Save the benchmark results to the \
+                                            specified file", "PATH"),
+      getopts::optopt("", "compare-baseline", "Compare the benchmark results to \
+                                               those saved in the specified file", "PATH"),
+      getopts::optopt("", "regression-threshold", "Fail if a benchmark regressed by \
+                                                   more than PCT percent against the \
+                                                   baseline (default 5)", "PCT"))
 }
 
 fn usage(binary: &str) {
@@ -335,6 +421,18 @@ only those tests that match are run.
 By default, all tests are run in parallel. This can be altered with the
 RUST_TEST_THREADS environment variable when running tests (set it to 1).
 
+Tests run in the order of their names unless --shuffle is given. The seed
+used to shuffle them is printed, and passing it to --shuffle-seed reproduces
+the same order.
+
+Benchmark results can be saved with --save-baseline and compared against on
+a later run with --compare-baseline. Changes within the median absolute
+deviation of either run are considered noise.
+
+With --isolate, the test binary re-executes itself once per test, and a test
+that aborts or is killed by a signal is reported as a failure instead of
+ending the run. A test process still running when its timeout is up is killed.
+
 All tests have their standard output and standard error captured by default.
 This can be overridden with the --nocapture flag or the RUST_TEST_NOCAPTURE=1
 environment variable. Logging is not captured by default.
@@ -352,7 +450,9 @@ Test Attributes:
     #[ignore]      - When applied to a function which is already attributed as a
                      test, then the test runner will ignore these tests during
                      normal test runs. Running with --ignored will run these
-                     tests."#,
+                     tests.
+    #[timeout = N] - Report this test as timed out after N seconds instead of
+                     the duration given by --test-timeout."#,
              usage = getopts::usage(&message, &optgroups()));
 }
 
@@ -373,6 +473,7 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
         None
     };
 
+    let filter_exact = matches.opt_present("exact");
     let run_ignored = matches.opt_present("ignored");
 
     let logfile = matches.opt_str("logfile");
@@ -397,19 +498,96 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
                                             v))),
     };
 
+    let format = match matches.opt_str("format").as_ref().map(|s| &**s) {
+        Some("pretty") | None => PrettyFormat,
+        Some("json") => JsonFormat,
+        Some("junit") => JunitFormat,
+
+        Some(v) => return Some(Err(format!("argument for --format must be \
+                                            pretty, json, or junit (was {})",
+                                            v))),
+    };
+
+    let junit_xml = matches.opt_str("junit-xml").map(|s| PathBuf::from(&s));
+
+    let test_timeout = match matches.opt_str("test-timeout") {
+        Some(s) => match s.parse::<u64>() {
+            Ok(secs) if secs > 0 => Some(Duration::from_secs(secs)),
+            _ => return Some(Err(format!("argument for --test-timeout must be \
+                                          a positive number of seconds (was {})",
+                                          s))),
+        },
+        None => None,
+    };
+
+    let isolate = matches.opt_present("isolate");
+
+    let shard = match matches.opt_str("shard") {
+        Some(s) => match parse_shard(&s) {
+            Some(shard) => Some(shard),
+            None => return Some(Err(format!("argument for --shard must be I/N \
+                                             with 1 <= I <= N (was {})", s))),
+        },
+        None => None,
+    };
+
+    let shuffle_seed = match matches.opt_str("shuffle-seed") {
+        Some(s) => match s.parse::<u64>() {
+            Ok(seed) => Some(seed),
+            Err(_) => return Some(Err(format!("argument for --shuffle-seed must be \
+                                               a number (was {})", s))),
+        },
+        None if matches.opt_present("shuffle") => {
+            Some(RandomState::new().hasher().finish())
+        }
+        None => None,
+    };
+
+    let save_baseline = matches.opt_str("save-baseline").map(|s| PathBuf::from(&s));
+    let compare_baseline = matches.opt_str("compare-baseline").map(|s| PathBuf::from(&s));
+
+    let regression_threshold = match matches.opt_str("regression-threshold") {
+        Some(s) => match s.parse::<f64>() {
+            Ok(pct) if pct >= 0.0 => pct,
+            _ => return Some(Err(format!("argument for --regression-threshold must be \
+                                          a non-negative percentage (was {})", s))),
+        },
+        None => DEFAULT_REGRESSION_THRESHOLD,
+    };
+
     let test_opts = TestOpts {
         filter: filter,
+        filter_exact: filter_exact,
         run_ignored: run_ignored,
         run_tests: run_tests,
         bench_benchmarks: bench_benchmarks,
         logfile: logfile,
         nocapture: nocapture,
         color: color,
+        format: format,
+        junit_xml: junit_xml,
+        test_timeout: test_timeout,
+        isolate: isolate,
+        shard: shard,
+        shuffle_seed: shuffle_seed,
+        save_baseline: save_baseline,
+        compare_baseline: compare_baseline,
+        regression_threshold: regression_threshold,
     };
 
     Some(Ok(test_opts))
 }
 
+fn parse_shard(s: &str) -> Option<(usize, usize)> {
+    let mut parts = s.splitn(2, '/');
+    let index: Option<usize> = parts.next().and_then(|i| i.parse().ok());
+    let count: Option<usize> = parts.next().and_then(|n| n.parse().ok());
+    match (index, count) {
+        (Some(i), Some(n)) if 1 <= i && i <= n => Some((i, n)),
+        _ => None,
+    }
+}
+
 #[derive(Clone, PartialEq)]
 pub struct BenchSamples {
     ns_iter_summ: stats::Summary,
@@ -420,6 +598,7 @@ pub struct BenchSamples {
 pub enum TestResult {
     TrOk,
     TrFailed,
+    TrTimedOut,
     TrIgnored,
     TrMetrics(MetricMap),
     TrBench(BenchSamples),
@@ -434,8 +613,12 @@ enum OutputLocation<T> {
 
 struct ConsoleTestState<T> {
     log_out: Option<File>,
+    // Where JSON events go instead of `out` in a child run by `--isolate`.
+    result_out: Option<File>,
     out: OutputLocation<T>,
     use_color: bool,
+    format: OutputFormat,
+    shuffle_seed: Option<u64>,
     total: usize,
     passed: usize,
     failed: usize,
@@ -443,6 +626,9 @@ struct ConsoleTestState<T> {
     measured: usize,
     metrics: MetricMap,
     failures: Vec<(TestDesc, Vec<u8> )> ,
+    test_timeout: Option<Duration>,
+    slow_tests: Vec<(TestDesc, Duration)>,
+    junit: Option<JunitReporter>,
     max_name_len: usize, // number of columns to fill when aligning names
 }
 
@@ -453,6 +639,15 @@ impl<T: Write> ConsoleTestState<T> {
             Some(ref path) => Some(try!(File::create(path))),
             None => None
         };
+        let result_out = match env::var_os(ISOLATED_RESULT_FILE) {
+            Some(path) => Some(try!(File::create(path))),
+            None => None
+        };
+        let junit = match (opts.format, opts.junit_xml.as_ref()) {
+            (_, Some(path)) => Some(JunitReporter::new(Some(try!(File::create(path))))),
+            (JunitFormat, None) => Some(JunitReporter::new(None)),
+            _ => None
+        };
         let out = match term::stdout() {
             None => Raw(io::stdout()),
             Some(t) => Pretty(t)
@@ -461,7 +656,10 @@ impl<T: Write> ConsoleTestState<T> {
         Ok(ConsoleTestState {
             out: out,
             log_out: log_out,
+            result_out: result_out,
             use_color: use_color(opts),
+            format: opts.format,
+            shuffle_seed: opts.shuffle_seed,
             total: 0,
             passed: 0,
             failed: 0,
@@ -469,6 +667,9 @@ impl<T: Write> ConsoleTestState<T> {
             measured: 0,
             metrics: MetricMap::new(),
             failures: Vec::new(),
+            test_timeout: opts.test_timeout,
+            slow_tests: Vec::new(),
+            junit: junit,
             max_name_len: 0,
         })
     }
@@ -481,6 +682,10 @@ impl<T: Write> ConsoleTestState<T> {
         self.write_pretty("FAILED", term::color::RED)
     }
 
+    pub fn write_timed_out(&mut self) -> io::Result<()> {
+        self.write_pretty("TIMEOUT", term::color::RED)
+    }
+
     pub fn write_ignored(&mut self) -> io::Result<()> {
         self.write_pretty("ignored", term::color::YELLOW)
     }
@@ -527,14 +732,45 @@ impl<T: Write> ConsoleTestState<T> {
         }
     }
 
+    pub fn write_json(&mut self, event: json::Object) -> io::Result<()> {
+        let line = format!("{}\n", Json::Object(event));
+        match self.result_out {
+            Some(ref mut f) => f.write_all(line.as_bytes()),
+            None => self.write_plain(&line),
+        }
+    }
+
     pub fn write_run_start(&mut self, len: usize) -> io::Result<()> {
         self.total = len;
+        if self.format == JsonFormat {
+            let mut event = json_event("suite", "started");
+            event.insert("test_count".to_string(), len.to_json());
+            if let Some(seed) = self.shuffle_seed {
+                event.insert("shuffle_seed".to_string(), seed.to_json());
+            }
+            return self.write_json(event);
+        }
+        if self.format == JunitFormat {
+            return Ok(());
+        }
         let noun = if len != 1 { "tests" } else { "test" };
-        self.write_plain(&format!("\nrunning {} {}\n", len, noun))
+        try!(self.write_plain(&format!("\nrunning {} {}\n", len, noun)));
+        match self.shuffle_seed {
+            Some(seed) => self.write_plain(&format!("shuffle seed: {}\n", seed)),
+            None => Ok(()),
+        }
     }
 
     pub fn write_test_start(&mut self, test: &TestDesc,
                             align: NamePadding) -> io::Result<()> {
+        if self.format == JsonFormat {
+            let mut event = json_event("test", "started");
+            event.insert("name".to_string(), test.name.to_string().to_json());
+            return self.write_json(event);
+        }
+        if self.format == JunitFormat {
+            return Ok(());
+        }
         let name = test.padded_name(self.max_name_len, align);
         self.write_plain(&format!("test {} ... ", name))
     }
@@ -543,6 +779,7 @@ impl<T: Write> ConsoleTestState<T> {
         try!(match *result {
             TrOk => self.write_ok(),
             TrFailed => self.write_failed(),
+            TrTimedOut => self.write_timed_out(),
             TrIgnored => self.write_ignored(),
             TrMetrics(ref mm) => {
                 try!(self.write_metric());
@@ -559,6 +796,50 @@ impl<T: Write> ConsoleTestState<T> {
         self.write_plain("\n")
     }
 
+    pub fn write_json_result(&mut self, test: &TestDesc, result: &TestResult,
+                             stdout: &[u8], exec_time: Duration) -> io::Result<()> {
+        let name = test.name.to_string().to_json();
+        if !stdout.is_empty() {
+            let mut event = json_event("test", "output");
+            event.insert("name".to_string(), name.clone());
+            event.insert("output".to_string(),
+                         String::from_utf8_lossy(stdout).to_json());
+            try!(self.write_json(event));
+        }
+        let mut event = match *result {
+            TrOk => json_event("test", "ok"),
+            TrFailed => json_event("test", "failed"),
+            TrTimedOut => json_event("test", "timeout"),
+            TrIgnored => json_event("test", "ignored"),
+            TrMetrics(ref mm) => {
+                let MetricMap(ref mm) = *mm;
+                let mut metrics = json::Object::new();
+                for (k, v) in mm {
+                    let mut metric = json::Object::new();
+                    metric.insert("value".to_string(), v.value.to_json());
+                    metric.insert("noise".to_string(), v.noise.to_json());
+                    metrics.insert(k.clone(), Json::Object(metric));
+                }
+                let mut event = json_event("metric", "measured");
+                event.insert("metrics".to_string(), Json::Object(metrics));
+                event
+            }
+            TrBench(ref bs) => {
+                let mut event = json_event("bench", "measured");
+                event.insert("median".to_string(), bs.ns_iter_summ.median.to_json());
+                event.insert("deviation".to_string(),
+                             (bs.ns_iter_summ.max - bs.ns_iter_summ.min).to_json());
+                event.insert("median_abs_dev".to_string(),
+                             bs.ns_iter_summ.median_abs_dev.to_json());
+                event.insert("mb_s".to_string(), bs.mb_s.to_json());
+                event
+            }
+        };
+        event.insert("name".to_string(), name);
+        event.insert("exec_time".to_string(), duration_secs(exec_time).to_json());
+        self.write_json(event)
+    }
+
     pub fn write_log(&mut self, test: &TestDesc,
                      result: &TestResult) -> io::Result<()> {
         match self.log_out {
@@ -567,6 +848,7 @@ impl<T: Write> ConsoleTestState<T> {
                 let s = format!("{} {}\n", match *result {
                         TrOk => "ok".to_string(),
                         TrFailed => "failed".to_string(),
+                        TrTimedOut => "timeout".to_string(),
                         TrIgnored => "ignored".to_string(),
                         TrMetrics(ref mm) => mm.fmt_metrics(),
                         TrBench(ref bs) => fmt_bench_samples(bs)
@@ -602,13 +884,110 @@ impl<T: Write> ConsoleTestState<T> {
         Ok(())
     }
 
+    // Tests taking more than half of their timeout (or a minute, if there
+    // is none) are worth pointing out even though they passed.
+    pub fn record_exec_time(&mut self, test: &TestDesc, exec_time: Duration) {
+        let threshold = match test.timeout.map(Duration::from_secs).or(self.test_timeout) {
+            Some(timeout) => timeout / 2,
+            None => Duration::from_secs(SLOW_TEST_SECS),
+        };
+        if exec_time > threshold {
+            self.slow_tests.push((test.clone(), exec_time));
+        }
+    }
+
+    pub fn write_slow_tests(&mut self) -> io::Result<()> {
+        try!(self.write_plain("\nslow tests:\n"));
+        let mut slow_tests = self.slow_tests.iter()
+                                            .map(|&(ref t, d)| (d, t.name.to_string()))
+                                            .collect::<Vec<_>>();
+        slow_tests.sort_by(|a, b| b.cmp(a));
+        for &(exec_time, ref name) in &slow_tests {
+            try!(self.write_plain(&format!("    {} ({:.3}s)\n", name,
+                                           duration_secs(exec_time))));
+        }
+        Ok(())
+    }
+
+    pub fn write_baseline_comparison(&mut self, old: &MetricMap, diff: &MetricDiff,
+                                     threshold: f64) -> io::Result<bool> {
+        let regressions = diff.values().filter(|c| match **c {
+            Regression(pct) => pct > threshold,
+            _ => false,
+        }).count();
+        let success = regressions == 0;
+
+        match self.format {
+            JunitFormat => return Ok(success),
+            JsonFormat => {
+                for event in self.metrics.json_comparison(old, diff) {
+                    try!(self.write_json(event));
+                }
+                return Ok(success);
+            }
+            PrettyFormat => {}
+        }
+
+        try!(self.write_plain("\nbenchmark comparison:\n"));
+        for (name, change) in diff {
+            try!(self.write_plain(&format!("    {} ... ", name)));
+            try!(match *change {
+                LikelyNoise => self.write_plain("within noise"),
+                MetricAdded => self.write_plain("added"),
+                MetricRemoved => self.write_plain("removed"),
+                Improvement(pct) => {
+                    try!(self.write_pretty("improved", term::color::GREEN));
+                    self.write_plain(&format!(" by {:.2}%", pct))
+                }
+                Regression(pct) => {
+                    let color = if pct > threshold {
+                        term::color::RED
+                    } else {
+                        term::color::YELLOW
+                    };
+                    try!(self.write_pretty("regressed", color));
+                    self.write_plain(&format!(" by {:.2}%", pct))
+                }
+            });
+            try!(self.write_plain("\n"));
+        }
+
+        try!(self.write_plain("\nbenchmark result: "));
+        if success {
+            try!(self.write_ok());
+        } else {
+            try!(self.write_failed());
+        }
+        self.write_plain(&format!(". {} regressed by more than {}%\n\n",
+                                  regressions, threshold))
+            .map(|()| success)
+    }
+
     pub fn write_run_finish(&mut self) -> io::Result<bool> {
         assert!(self.passed + self.failed + self.ignored + self.measured == self.total);
 
         let success = self.failed == 0;
+        if let Some(junit) = self.junit.take() {
+            try!(junit.write_report(self));
+        }
+        if self.format == JunitFormat {
+            return Ok(success);
+        }
+        if self.format == JsonFormat {
+            let mut event = json_event("suite", if success { "ok" } else { "failed" });
+            event.insert("passed".to_string(), self.passed.to_json());
+            event.insert("failed".to_string(), self.failed.to_json());
+            event.insert("ignored".to_string(), self.ignored.to_json());
+            event.insert("measured".to_string(), self.measured.to_json());
+            try!(self.write_json(event));
+            return Ok(success);
+        }
         if !success {
             try!(self.write_failures());
         }
+        if !self.slow_tests.is_empty() {
+            try!(self.write_slow_tests());
+        }
 
         try!(self.write_plain("\ntest result: "));
         if success {
@@ -624,6 +1003,159 @@ impl<T: Write> ConsoleTestState<T> {
     }
 }
 
+/// Collects test results and renders them as a JUnit XML report once the
+/// run has finished, for consumption by CI dashboards.
+struct JunitReporter {
+    out: Option<File>,
+    cases: Vec<(TestDesc, TestResult, Vec<u8>, Duration)>,
+}
+
+impl JunitReporter {
+    fn new(out: Option<File>) -> JunitReporter {
+        JunitReporter {
+            out: out,
+            cases: Vec::new(),
+        }
+    }
+
+    fn record(&mut self, test: &TestDesc, result: &TestResult,
+              stdout: &[u8], exec_time: Duration) {
+        self.cases.push((test.clone(), result.clone(), stdout.to_vec(), exec_time));
+    }
+
+    fn write_report<T: Write>(self, st: &mut ConsoleTestState<T>) -> io::Result<()> {
+        let suite = env::args().next().as_ref()
+                               .and_then(|a| Path::new(a).file_stem())
+                               .map(|s| s.to_string_lossy().into_owned())
+                               .unwrap_or("test".to_string());
+        let report = self.render(&suite, st);
+        match self.out {
+            Some(mut f) => f.write_all(report.as_bytes()),
+            None => st.write_plain(&report),
+        }
+    }
+
+    fn render<T>(&self, suite: &str, st: &ConsoleTestState<T>) -> String {
+        use std::fmt::Write;
+        let mut out = String::new();
+        let total_time = self.cases.iter().fold(Duration::new(0, 0), |a, c| a + c.3);
+
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n");
+        out.write_fmt(format_args!("  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" \
+                                    errors=\"0\" skipped=\"{}\" time=\"{:.3}\">\n",
+                                   xml_escape(suite), st.total, st.failed,
+                                   st.ignored, duration_secs(total_time))).unwrap();
+        // The seed is needed to reproduce the order of a shuffled run.
+        if let Some(seed) = st.shuffle_seed {
+            out.write_fmt(format_args!("    <properties>\n      <property name=\"shuffle_seed\" \
+                                        value=\"{}\"/>\n    </properties>\n",
+                                       seed)).unwrap();
+        }
+        for &(ref desc, ref result, ref stdout, exec_time) in &self.cases {
+            let name = desc.name.as_slice();
+            let (classname, name) = match name.rfind("::") {
+                Some(i) => (&name[..i], &name[i + 2..]),
+                None => (suite, name),
+            };
+            out.write_fmt(format_args!("    <testcase classname=\"{}\" name=\"{}\" \
+                                        time=\"{:.3}\">\n",
+                                       xml_escape(classname), xml_escape(name),
+                                       duration_secs(exec_time))).unwrap();
+            let output = String::from_utf8_lossy(stdout);
+            match *result {
+                TrOk => {}
+                TrIgnored => out.push_str("      <skipped/>\n"),
+                TrFailed => {
+                    let message = panic_message(&output).unwrap_or("test failed");
+                    out.write_fmt(format_args!("      <failure type=\"panic\" \
+                                                message=\"{}\"/>\n",
+                                               xml_escape(message))).unwrap();
+                }
+                TrTimedOut => {
+                    out.push_str("      <failure type=\"timeout\" \
+                                  message=\"test timed out\"/>\n");
+                }
+                TrMetrics(ref mm) => {
+                    let MetricMap(ref mm) = *mm;
+                    out.push_str("      <properties>\n");
+                    for (k, v) in mm {
+                        write_property(&mut out, &format!("{}.value", k), v.value);
+                        write_property(&mut out, &format!("{}.noise", k), v.noise);
+                    }
+                    out.push_str("      </properties>\n");
+                }
+                TrBench(ref bs) => {
+                    let summ = &bs.ns_iter_summ;
+                    out.push_str("      <properties>\n");
+                    write_property(&mut out, "ns_iter.median", summ.median);
+                    write_property(&mut out, "ns_iter.min", summ.min);
+                    write_property(&mut out, "ns_iter.max", summ.max);
+                    write_property(&mut out, "ns_iter.median_abs_dev", summ.median_abs_dev);
+                    write_property(&mut out, "mb_s", bs.mb_s as f64);
+                    out.push_str("      </properties>\n");
+                }
+            }
+            if !output.is_empty() {
+                out.write_fmt(format_args!("      <system-out>{}</system-out>\n",
+                                           xml_escape(&output))).unwrap();
+            }
+            out.push_str("    </testcase>\n");
+        }
+        out.push_str("  </testsuite>\n</testsuites>\n");
+        return out;
+
+        fn write_property(out: &mut String, name: &str, value: f64) {
+            out.write_fmt(format_args!("        <property name=\"{}\" value=\"{}\"/>\n",
+                                       xml_escape(name), value)).unwrap();
+        }
+    }
+}
+
+// Pull the message of the panic that failed a test out of its captured
+// output, as printed by the panic handler.
+fn panic_message(output: &str) -> Option<&str> {
+    let marker = "panicked at '";
+    output.lines().rev().filter_map(|line| {
+        line.find(marker).and_then(|start| {
+            let msg = &line[start + marker.len()..];
+            msg.rfind("', ").map(|end| &msg[..end])
+        })
+    }).next()
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Other control characters are not allowed in XML 1.0 at all.
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if (c as u32) < 0x20 => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const SLOW_TEST_SECS: u64 = 60;
+
+// Build the skeleton of a `--format json` event; every event carries the
+// kind of object it describes and what happened to it.
+fn json_event(kind: &str, event: &str) -> json::Object {
+    let mut obj = json::Object::new();
+    obj.insert("type".to_string(), kind.to_json());
+    obj.insert("event".to_string(), event.to_json());
+    obj
+}
+
+fn duration_secs(d: Duration) -> f64 {
+    d.secs() as f64 + d.extra_nanos() as f64 / 1_000_000_000.0
+}
+
 // Format a number with thousands separators
 fn fmt_thousands_sep(mut n: usize, sep: char) -> String {
     use std::fmt::Write;
@@ -672,9 +1204,20 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn> ) -> io::Res
         match (*event).clone() {
             TeFiltered(ref filtered_tests) => st.write_run_start(filtered_tests.len()),
             TeWait(ref test, padding) => st.write_test_start(test, padding),
-            TeResult(test, result, stdout) => {
+            TeResult(test, result, stdout, exec_time) => {
                 try!(st.write_log(&test, &result));
-                try!(st.write_result(&result));
+                match st.format {
+                    PrettyFormat => try!(st.write_result(&result)),
+                    JsonFormat => try!(st.write_json_result(&test, &result,
+                                                            &stdout, exec_time)),
+                    JunitFormat => {}
+                }
+                if let Some(ref mut junit) = st.junit {
+                    junit.record(&test, &result, &stdout, exec_time);
+                }
+                if result == TrOk {
+                    st.record_exec_time(&test, exec_time);
+                }
                 match result {
                     TrOk => st.passed += 1,
                     TrIgnored => st.ignored += 1,
@@ -694,10 +1237,10 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn> ) -> io::Res
                     TrBench(bs) => {
                         st.metrics.insert_metric(test.name.as_slice(),
                                                  bs.ns_iter_summ.median,
-                                                 bs.ns_iter_summ.max - bs.ns_iter_summ.min);
+                                                 bs.ns_iter_summ.median_abs_dev);
                         st.measured += 1
                     }
-                    TrFailed => {
+                    TrFailed | TrTimedOut => {
                         st.failed += 1;
                         st.failures.push((test, stdout));
                     }
@@ -721,28 +1264,41 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn> ) -> io::Res
         },
         None => {}
     }
+    // The old baseline is loaded up front, as saving the new one may
+    // overwrite it.
+    let baseline = match opts.compare_baseline {
+        Some(ref path) => Some(try!(MetricMap::load(path))),
+        None => None,
+    };
     try!(run_tests(opts, tests, |x| callback(&x, &mut st)));
-    return st.write_run_finish();
-}
 
-#[test]
-fn should_sort_failures_before_printing_them() {
-    let test_a = TestDesc {
-        name: StaticTestName("a"),
-        ignore: false,
-        should_panic: ShouldPanic::No
-    };
+    // The comparison comes before the summary, which ends the JSON stream.
+    let mut success = true;
+    if let Some(ref old) = baseline {
+        let diff = st.metrics.compare_to_old(old);
+        success = try!(st.write_baseline_comparison(old, &diff, opts.regression_threshold));
+    }
+    if !try!(st.write_run_finish()) {
+        success = false;
+    }
 
-    let test_b = TestDesc {
-        name: StaticTestName("b"),
-        ignore: false,
-        should_panic: ShouldPanic::No
-    };
+    if let Some(ref path) = opts.save_baseline {
+        try!(st.metrics.save(path));
+    }
+    return Ok(success);
+}
 
-    let mut st = ConsoleTestState {
+// A console in the given format writing to memory, for the tests below to
+// check what is written.
+#[cfg(test)]
+fn test_console(format: OutputFormat) -> ConsoleTestState<Vec<u8>> {
+    ConsoleTestState {
         log_out: None,
+        result_out: None,
         out: Raw(Vec::new()),
         use_color: false,
+        format: format,
+        shuffle_seed: None,
         total: 0,
         passed: 0,
         failed: 0,
@@ -750,20 +1306,148 @@ fn should_sort_failures_before_printing_them() {
         measured: 0,
         max_name_len: 10,
         metrics: MetricMap::new(),
-        failures: vec!((test_b, Vec::new()), (test_a, Vec::new()))
-    };
+        failures: Vec::new(),
+        test_timeout: None,
+        slow_tests: Vec::new(),
+        junit: None,
+    }
+}
 
-    st.write_failures().unwrap();
-    let s = match st.out {
-        Raw(ref m) => String::from_utf8_lossy(&m[..]),
+// What has been written to a console made by `test_console`.
+#[cfg(test)]
+fn test_console_output(st: &ConsoleTestState<Vec<u8>>) -> String {
+    match st.out {
+        Raw(ref m) => String::from_utf8_lossy(&m[..]).into_owned(),
         Pretty(_) => unreachable!()
+    }
+}
+
+#[test]
+fn should_sort_failures_before_printing_them() {
+    let test_a = TestDesc {
+        name: StaticTestName("a"),
+        ignore: false,
+        should_panic: ShouldPanic::No,
+        timeout: None,
     };
 
+    let test_b = TestDesc {
+        name: StaticTestName("b"),
+        ignore: false,
+        should_panic: ShouldPanic::No,
+        timeout: None,
+    };
+
+    let mut st = test_console(PrettyFormat);
+    st.failures = vec!((test_b, Vec::new()), (test_a, Vec::new()));
+
+    st.write_failures().unwrap();
+    let s = test_console_output(&st);
+
     let apos = s.find("a").unwrap();
     let bpos = s.find("b").unwrap();
     assert!(apos < bpos);
 }
 
+#[test]
+fn should_write_one_json_object_per_event() {
+    let test = TestDesc {
+        name: StaticTestName("a"),
+        ignore: false,
+        should_panic: ShouldPanic::No,
+        timeout: None,
+    };
+
+    let mut st = test_console(JsonFormat);
+
+    st.write_run_start(1).unwrap();
+    st.write_test_start(&test, PadNone).unwrap();
+    st.write_json_result(&test, &TrFailed, b"oops", Duration::new(1, 0)).unwrap();
+    let s = test_console_output(&st);
+
+    let events = s.lines().map(|l| json::from_str(l).unwrap()).collect::<Vec<_>>();
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[0]["event"], "started".to_json());
+    assert_eq!(events[0]["test_count"], 1usize.to_json());
+    assert_eq!(events[2]["event"], "output".to_json());
+    assert_eq!(events[2]["output"], "oops".to_json());
+    assert_eq!(events[3]["type"], "test".to_json());
+    assert_eq!(events[3]["event"], "failed".to_json());
+    assert_eq!(events[3]["name"], "a".to_json());
+    assert_eq!(events[3]["exec_time"], 1.0f64.to_json());
+}
+
+#[test]
+fn should_report_failures_in_junit_xml() {
+    let test = TestDesc {
+        name: StaticTestName("foo::<bar>"),
+        ignore: false,
+        should_panic: ShouldPanic::No,
+        timeout: None,
+    };
+
+    let mut st = test_console(JunitFormat);
+    st.shuffle_seed = Some(42);
+    st.total = 1;
+    st.failed = 1;
+
+    let mut junit = JunitReporter::new(None);
+    junit.record(&test, &TrFailed,
+                 b"thread 'foo::<bar>' panicked at 'x < y', src/foo.rs:1\n",
+                 Duration::from_millis(1500));
+    junit.write_report(&mut st).unwrap();
+    let s = test_console_output(&st);
+
+    assert!(s.contains("tests=\"1\" failures=\"1\""));
+    assert!(s.contains("<property name=\"shuffle_seed\" value=\"42\"/>"));
+    assert!(s.contains("<testcase classname=\"foo\" name=\"&lt;bar&gt;\" time=\"1.500\">"));
+    assert!(s.contains("<failure type=\"panic\" message=\"x &lt; y\"/>"));
+    assert!(s.contains("<system-out>thread &apos;foo::&lt;bar&gt;&apos; panicked"));
+}
+
+// Plays the part of an isolated child process: `noise` is what the test
+// printed itself, and the events go to a result file as with
+// `ISOLATED_RESULT_FILE`. Returns what the parent reads back.
+#[cfg(test)]
+fn run_isolated_child(kind: &str, result: TestResult, stdout: &[u8],
+                      noise: &str) -> Option<(TestResult, Vec<u8>)> {
+    let test = TestDesc {
+        name: StaticTestName("a"),
+        ignore: false,
+        should_panic: ShouldPanic::No,
+        timeout: None,
+    };
+    let path = env::temp_dir().join(format!("rust-test-isolated-{}.json", kind));
+
+    {
+        let mut st = test_console(JsonFormat);
+        st.result_out = Some(File::create(&path).unwrap());
+        st.write_run_start(1).unwrap();
+        st.write_plain(noise).unwrap();
+        st.write_test_start(&test, PadNone).unwrap();
+        st.write_plain(noise).unwrap();
+        st.write_json_result(&test, &result, stdout, Duration::new(0, 0)).unwrap();
+        assert_eq!(test_console_output(&st), format!("{}{}", noise, noise));
+    }
+
+    let mut events = String::new();
+    File::open(&path).unwrap().read_to_string(&mut events).unwrap();
+    fs::remove_file(&path).unwrap();
+    calc_isolated_result(&events)
+}
+
+#[test]
+fn should_read_isolated_results_from_result_file() {
+    assert!(run_isolated_child("passing", TrOk, b"", "") == Some((TrOk, Vec::new())));
+    assert!(run_isolated_child("failing", TrFailed, b"oops\n", "") ==
+            Some((TrFailed, b"oops\n".to_vec())));
+    // A partial line printed by a test run with --nocapture must not
+    // corrupt its result.
+    assert!(run_isolated_child("noisy", TrOk, b"", "partial {\"type\":\"test\"") ==
+            Some((TrOk, Vec::new())));
+    assert!(calc_isolated_result("").is_none());
+}
+
 fn use_color(opts: &TestOpts) -> bool {
     match opts.color {
         AutoColor => !opts.nocapture && stdout_isatty(),
@@ -795,10 +1479,10 @@ fn stdout_isatty() -> bool {
 enum TestEvent {
     TeFiltered(Vec<TestDesc> ),
     TeWait(TestDesc, NamePadding),
-    TeResult(TestDesc, TestResult, Vec<u8> ),
+    TeResult(TestDesc, TestResult, Vec<u8>, Duration),
 }
 
-pub type MonitorMsg = (TestDesc, TestResult, Vec<u8> );
+pub type MonitorMsg = (TestDesc, TestResult, Vec<u8>, Duration);
 
 
 fn run_tests<F>(opts: &TestOpts,
@@ -848,11 +1532,11 @@ fn run_tests<F>(opts: &TestOpts,
             pending += 1;
         }
 
-        let (desc, result, stdout) = rx.recv().unwrap();
+        let (desc, result, stdout, exec_time) = rx.recv().unwrap();
         if concurrency != 1 {
             try!(callback(TeWait(desc.clone(), PadNone)));
         }
-        try!(callback(TeResult(desc, result, stdout)));
+        try!(callback(TeResult(desc, result, stdout, exec_time)));
         pending -= 1;
     }
 
@@ -862,8 +1546,8 @@ fn run_tests<F>(opts: &TestOpts,
         for b in filtered_benchs_and_metrics {
             try!(callback(TeWait(b.desc.clone(), b.testfn.padding())));
             run_test(opts, false, b, tx.clone());
-            let (test, result, stdout) = rx.recv().unwrap();
-            try!(callback(TeResult(test, result, stdout)));
+            let (test, result, stdout, exec_time) = rx.recv().unwrap();
+            try!(callback(TeResult(test, result, stdout, exec_time)));
         }
     }
     Ok(())
@@ -912,7 +1596,11 @@ pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescA
         None => filtered,
         Some(ref filter) => {
             filtered.into_iter().filter(|test| {
-                test.desc.name.as_slice().contains(&filter[..])
+                if opts.filter_exact {
+                    test.desc.name.as_slice() == &filter[..]
+                } else {
+                    test.desc.name.as_slice().contains(&filter[..])
+                }
             }).collect()
         }
     };
@@ -938,6 +1626,28 @@ pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescA
     // Sort the tests alphabetically
     filtered.sort_by(|t1, t2| t1.desc.name.as_slice().cmp(t2.desc.name.as_slice()));
 
+    // Keep every nth test, so that the shards stay balanced
+    filtered = match opts.shard {
+        None => filtered,
+        Some((index, count)) => {
+            filtered.into_iter().enumerate()
+                    .filter(|&(i, _)| i % count == index - 1)
+                    .map(|(_, test)| test)
+                    .collect()
+        }
+    };
+
+    // Shuffle the tests, in a way that only depends on the seed and the
+    // names of the tests
+    if let Some(seed) = opts.shuffle_seed {
+        fn shuffle_key(seed: u64, test: &TestDescAndFn) -> u64 {
+            let mut hasher = SipHasher::new_with_keys(seed, 0);
+            test.desc.name.as_slice().hash(&mut hasher);
+            hasher.finish()
+        }
+        filtered.sort_by(|t1, t2| shuffle_key(seed, t1).cmp(&shuffle_key(seed, t2)));
+    }
+
     filtered
 }
 
@@ -965,13 +1675,23 @@ pub fn run_test(opts: &TestOpts,
     let TestDescAndFn {desc, testfn} = test;
 
     if force_ignore || desc.ignore {
-        monitor_ch.send((desc, TrIgnored, Vec::new())).unwrap();
+        monitor_ch.send((desc, TrIgnored, Vec::new(), Duration::new(0, 0))).unwrap();
         return;
     }
 
+    if opts.isolate {
+        match testfn {
+            StaticTestFn(..) | DynTestFn(..) => {
+                return run_test_isolated(opts, desc, monitor_ch);
+            }
+            _ => {}
+        }
+    }
+
     fn run_test_inner(desc: TestDesc,
                       monitor_ch: Sender<MonitorMsg>,
                       nocapture: bool,
+                      timeout: Option<Duration>,
                       testfn: Box<FnBox() + Send>) {
         struct Sink(Arc<Mutex<Vec<u8>>>);
         impl Write for Sink {
@@ -989,46 +1709,269 @@ pub fn run_test(opts: &TestOpts,
                 StaticTestName(name) => name.to_string(),
             });
 
-            let result_guard = cfg.spawn(move || {
-                if !nocapture {
-                    io::set_print(box Sink(data2.clone()));
-                    io::set_panic(box Sink(data2));
-                }
-                testfn()
-            }).unwrap();
-            let test_result = calc_result(&desc, result_guard.join());
-            let stdout = data.lock().unwrap().to_vec();
-            monitor_ch.send((desc.clone(), test_result, stdout)).unwrap();
+            // Whichever of the test and its watchdog finishes first reports
+            // the result. There is no way to kill a thread, so a test which
+            // timed out is simply left running in the background.
+            let reported = Arc::new(AtomicBool::new(false));
+            let watchdog = timeout.map(|timeout| {
+                let reported = reported.clone();
+                let desc = desc.clone();
+                let data = data.clone();
+                let monitor_ch = monitor_ch.clone();
+                thread::spawn(move || {
+                    let mut waited = Duration::new(0, 0);
+                    while waited < timeout && !reported.load(Ordering::SeqCst) {
+                        let remaining = timeout - waited;
+                        waited = waited + Duration::span(|| thread::park_timeout(remaining));
+                    }
+                    if !reported.swap(true, Ordering::SeqCst) {
+                        let stdout = data.lock().unwrap().to_vec();
+                        monitor_ch.send((desc, TrTimedOut, stdout, waited)).unwrap();
+                    }
+                })
+            });
+
+            let mut task_result = None;
+            let exec_time = Duration::span(|| {
+                let result_guard = cfg.spawn(move || {
+                    if !nocapture {
+                        io::set_print(box Sink(data2.clone()));
+                        io::set_panic(box Sink(data2));
+                    }
+                    testfn()
+                }).unwrap();
+                task_result = Some(result_guard.join());
+            });
+            if !reported.swap(true, Ordering::SeqCst) {
+                let test_result = calc_result(&desc, task_result.unwrap());
+                let stdout = data.lock().unwrap().to_vec();
+                monitor_ch.send((desc.clone(), test_result, stdout, exec_time)).unwrap();
+            }
+            if let Some(watchdog) = watchdog {
+                watchdog.thread().unpark();
+            }
         });
     }
 
     match testfn {
         DynBenchFn(bencher) => {
-            let bs = ::bench::benchmark(|harness| bencher.run(harness));
-            monitor_ch.send((desc, TrBench(bs), Vec::new())).unwrap();
+            let mut bs = None;
+            let exec_time = Duration::span(|| {
+                bs = Some(::bench::benchmark(|harness| bencher.run(harness)));
+            });
+            monitor_ch.send((desc, TrBench(bs.unwrap()), Vec::new(), exec_time)).unwrap();
             return;
         }
         StaticBenchFn(benchfn) => {
-            let bs = ::bench::benchmark(|harness| (benchfn.clone())(harness));
-            monitor_ch.send((desc, TrBench(bs), Vec::new())).unwrap();
+            let mut bs = None;
+            let exec_time = Duration::span(|| {
+                bs = Some(::bench::benchmark(|harness| (benchfn.clone())(harness)));
+            });
+            monitor_ch.send((desc, TrBench(bs.unwrap()), Vec::new(), exec_time)).unwrap();
             return;
         }
         DynMetricFn(f) => {
             let mut mm = MetricMap::new();
-            f.call_box((&mut mm,));
-            monitor_ch.send((desc, TrMetrics(mm), Vec::new())).unwrap();
+            let exec_time = Duration::span(|| f.call_box((&mut mm,)));
+            monitor_ch.send((desc, TrMetrics(mm), Vec::new(), exec_time)).unwrap();
             return;
         }
         StaticMetricFn(f) => {
             let mut mm = MetricMap::new();
-            f(&mut mm);
-            monitor_ch.send((desc, TrMetrics(mm), Vec::new())).unwrap();
+            let exec_time = Duration::span(|| f(&mut mm));
+            monitor_ch.send((desc, TrMetrics(mm), Vec::new(), exec_time)).unwrap();
             return;
         }
-        DynTestFn(f) => run_test_inner(desc, monitor_ch, opts.nocapture, f),
-        StaticTestFn(f) => run_test_inner(desc, monitor_ch, opts.nocapture,
-                                          Box::new(move|| f()))
+        DynTestFn(f) => {
+            let timeout = desc.timeout.map(Duration::from_secs).or(opts.test_timeout);
+            run_test_inner(desc, monitor_ch, opts.nocapture, timeout, f)
+        }
+        StaticTestFn(f) => {
+            let timeout = desc.timeout.map(Duration::from_secs).or(opts.test_timeout);
+            run_test_inner(desc, monitor_ch, opts.nocapture, timeout,
+                           Box::new(move|| f()))
+        }
+    }
+}
+
+// The environment variable naming the file an isolated test process writes
+// its `--format json` events to, keeping them apart from whatever the test
+// itself prints on stdout.
+const ISOLATED_RESULT_FILE: &'static str = "RUST_TEST_RESULT_FILE";
+
+// Runs a single test in a fresh copy of the test binary, which reports back
+// with `--format json` through the file named by `ISOLATED_RESULT_FILE`.
+fn run_test_isolated(opts: &TestOpts, desc: TestDesc, monitor_ch: Sender<MonitorMsg>) {
+    let run_ignored = opts.run_ignored;
+    let nocapture = opts.nocapture;
+    // The timeout is enforced here rather than by the test process, which
+    // cannot stop a test that hangs.
+    let timeout = desc.timeout.map(Duration::from_secs).or(opts.test_timeout);
+
+    thread::spawn(move || {
+        let result_path = {
+            let mut hasher = RandomState::new().hasher();
+            desc.name.as_slice().hash(&mut hasher);
+            env::temp_dir().join(format!("rust-test-{:016x}.json", hasher.finish()))
+        };
+        let mut output = None;
+        let exec_time = Duration::span(|| {
+            output = Some(env::current_exe().and_then(|exe| {
+                let mut cmd = Command::new(exe);
+                cmd.arg(desc.name.as_slice())
+                   .arg("--exact")
+                   .arg("--format").arg("json")
+                   .env(ISOLATED_RESULT_FILE, &result_path)
+                   .stdin(Stdio::null())
+                   .stdout(Stdio::piped())
+                   .stderr(Stdio::piped());
+                if run_ignored {
+                    cmd.arg("--ignored");
+                }
+                if nocapture {
+                    cmd.arg("--nocapture");
+                }
+                cmd.spawn().and_then(|child| wait_for_test_process(child, nocapture, timeout))
+            }));
+        });
+        let mut events = String::new();
+        let _ = File::open(&result_path).and_then(|mut f| f.read_to_string(&mut events));
+        let _ = fs::remove_file(&result_path);
+        let (result, stdout) = match output.unwrap() {
+            Ok(None) => (TrTimedOut, Vec::new()),
+            Ok(Some((status, stderr))) => {
+                calc_isolated_result(&events).unwrap_or_else(|| {
+                    // The test process died before it could report anything.
+                    let mut stdout = stderr;
+                    let msg = format!("test process {}\n", exit_description(&status));
+                    stdout.extend(msg.as_bytes().iter().cloned());
+                    (TrFailed, stdout)
+                })
+            }
+            Err(e) => (TrFailed, format!("failed to run isolated test: {}\n", e).into_bytes()),
+        };
+        monitor_ch.send((desc, result, stdout, exec_time)).unwrap();
+    });
+}
+
+// Waits for an isolated test process to finish, returning its exit status and
+// what it wrote to stderr, or kills it and returns `None` if it is still
+// running after `timeout`. With `nocapture`, its output is passed on as it
+// comes.
+//
+// `Child` can only be waited on without a time limit, so the process is taken
+// to have finished once it has closed both its stdout and its stderr.
+fn wait_for_test_process(mut child: process::Child,
+                         nocapture: bool,
+                         timeout: Option<Duration>)
+                         -> io::Result<Option<(process::ExitStatus, Vec<u8>)>> {
+    fn read<R, W>(pipe: Option<R>, mut echo: Option<W>, closed: Arc<AtomicUsize>)
+                  -> thread::JoinHandle<Vec<u8>>
+        where R: Read + Send + 'static, W: Write + Send + 'static
+    {
+        let parent = thread::current();
+        thread::spawn(move || {
+            let mut data = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let mut buf = [0; 4096];
+                loop {
+                    match pipe.read(&mut buf) {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => {
+                            if let Some(ref mut echo) = echo {
+                                let _ = echo.write_all(&buf[..n]);
+                            }
+                            data.extend(buf[..n].iter().cloned());
+                        }
+                    }
+                }
+            }
+            closed.fetch_add(1, Ordering::SeqCst);
+            parent.unpark();
+            data
+        })
+    }
+
+    let closed = Arc::new(AtomicUsize::new(0));
+    let (stdout_echo, stderr_echo) = if nocapture {
+        (Some(io::stdout()), Some(io::stderr()))
+    } else {
+        (None, None)
+    };
+    read(child.stdout.take(), stdout_echo, closed.clone());
+    let stderr = read(child.stderr.take(), stderr_echo, closed.clone());
+
+    let mut waited = Duration::new(0, 0);
+    while closed.load(Ordering::SeqCst) < 2 {
+        match timeout {
+            Some(timeout) if waited >= timeout => {
+                // Whatever still holds the pipes open may outlive the test
+                // process, so they are not waited on any further.
+                let _ = child.kill();
+                try!(child.wait());
+                return Ok(None);
+            }
+            Some(timeout) => {
+                let remaining = timeout - waited;
+                waited = waited + Duration::span(|| thread::park_timeout(remaining));
+            }
+            None => thread::park(),
+        }
     }
+    let status = try!(child.wait());
+    Ok(Some((status, stderr.join().unwrap_or(Vec::new()))))
+}
+
+// Reads the result of an isolated test back from the events it wrote, or
+// returns `None` if it never got as far as reporting one.
+fn calc_isolated_result(events: &str) -> Option<(TestResult, Vec<u8>)> {
+    let mut result = None;
+    let mut stdout = Vec::new();
+    for line in events.lines() {
+        let event = match json::from_str(line) {
+            Ok(Json::Object(event)) => event,
+            _ => continue,
+        };
+        let field = |name: &str| event.get(name).and_then(|v| v.as_string()).unwrap_or("");
+        match (field("type"), field("event")) {
+            ("test", "output") => stdout.extend(field("output").as_bytes().iter().cloned()),
+            ("test", "ok") => result = Some(TrOk),
+            ("test", "failed") => result = Some(TrFailed),
+            ("test", "timeout") => result = Some(TrTimedOut),
+            ("test", "ignored") => result = Some(TrIgnored),
+            _ => {}
+        }
+    }
+    result.map(|result| (result, stdout))
+}
+
+#[cfg(unix)]
+fn exit_description(status: &process::ExitStatus) -> String {
+    use std::os::unix::process::ExitStatusExt;
+    let signal = match status.signal() {
+        Some(signal) => signal,
+        None => return format!("exited with {}", status),
+    };
+    let name = match signal {
+        libc::SIGHUP => "SIGHUP",
+        libc::SIGINT => "SIGINT",
+        libc::SIGQUIT => "SIGQUIT",
+        libc::SIGILL => "SIGILL",
+        libc::SIGABRT => "SIGABRT",
+        libc::SIGFPE => "SIGFPE",
+        libc::SIGKILL => "SIGKILL",
+        libc::SIGSEGV => "SIGSEGV",
+        libc::SIGPIPE => "SIGPIPE",
+        libc::SIGALRM => "SIGALRM",
+        libc::SIGTERM => "SIGTERM",
+        _ => return format!("was killed by signal {}", signal),
+    };
+    format!("was killed by signal {} ({})", signal, name)
+}
+
+#[cfg(windows)]
+fn exit_description(status: &process::ExitStatus) -> String {
+    format!("exited with {}", status)
 }
 
 fn calc_result(desc: &TestDesc, task_result: Result<(), Box<Any+Send>>) -> TestResult {
@@ -1073,6 +2016,98 @@ impl MetricMap {
         map.insert(name.to_string(), m);
     }
 
+    /// Load a MetricMap from a file.
+    pub fn load(p: &Path) -> io::Result<MetricMap> {
+        let mut s = String::new();
+        try!(File::open(p).and_then(|mut f| f.read_to_string(&mut s)));
+        match json::decode(&s) {
+            Ok(map) => Ok(MetricMap(map)),
+            Err(e) => Err(io::Error::new(io::ErrorKind::InvalidData,
+                                         format!("malformed metrics in {}: {}",
+                                                 p.display(), e))),
+        }
+    }
+
+    /// Write the MetricMap to a file.
+    pub fn save(&self, p: &Path) -> io::Result<()> {
+        let MetricMap(ref map) = *self;
+        let mut file = try!(File::create(p));
+        write!(&mut file, "{}\n", json::as_pretty_json(map))
+    }
+
+    /// Compare against another MetricMap. The noise of a metric is its
+    /// median absolute deviation for benchmarks; a change smaller than the
+    /// noise of either the old or the new metric is considered noise.
+    pub fn compare_to_old(&self, old: &MetricMap) -> MetricDiff {
+        let mut diff : MetricDiff = BTreeMap::new();
+        let MetricMap(ref selfmap) = *self;
+        let MetricMap(ref old) = *old;
+        for (k, vold) in old {
+            let r = match selfmap.get(k) {
+                None => MetricRemoved,
+                Some(v) => {
+                    let delta = v.value - vold.value;
+                    let noise = vold.noise.abs().max(v.noise.abs());
+                    if delta.abs() <= noise {
+                        LikelyNoise
+                    } else {
+                        let pct = delta.abs() / vold.value.max(f64::EPSILON) * 100.0;
+                        if vold.noise >= 0.0 {
+                            // When 'noise' is positive, 'better' is 'smaller'.
+                            if delta < 0.0 {
+                                Improvement(pct)
+                            } else {
+                                Regression(pct)
+                            }
+                        } else {
+                            // When 'noise' is negative, 'better' is 'bigger'.
+                            if delta > 0.0 {
+                                Improvement(pct)
+                            } else {
+                                Regression(pct)
+                            }
+                        }
+                    }
+                }
+            };
+            diff.insert((*k).clone(), r);
+        }
+        for k in selfmap.keys() {
+            if !diff.contains_key(k) {
+                diff.insert((*k).clone(), MetricAdded);
+            }
+        }
+        diff
+    }
+
+    // One `--format json` event per compared metric.
+    fn json_comparison(&self, old: &MetricMap, diff: &MetricDiff) -> Vec<json::Object> {
+        let MetricMap(ref new) = *self;
+        let MetricMap(ref old) = *old;
+        diff.iter().map(|(name, change)| {
+            let mut event = json_event("bench", "compared");
+            event.insert("name".to_string(), name.to_json());
+            if let Some(m) = old.get(name) {
+                event.insert("old".to_string(), m.value.to_json());
+            }
+            if let Some(m) = new.get(name) {
+                event.insert("new".to_string(), m.value.to_json());
+            }
+            let (change, pct) = match *change {
+                LikelyNoise => ("noise", None),
+                MetricAdded => ("added", None),
+                MetricRemoved => ("removed", None),
+                Improvement(pct) => ("improvement", Some(pct)),
+                Regression(pct) => ("regression", Some(pct)),
+            };
+            event.insert("change".to_string(), change.to_json());
+            if let Some(pct) = pct {
+                event.insert("percent".to_string(), pct.to_json());
+            }
+            event
+        }).collect()
+    }
+
     pub fn fmt_metrics(&self) -> String {
         let MetricMap(ref mm) = *self;
         let v : Vec<String> = mm.iter()
@@ -1236,11 +2271,14 @@ pub mod bench {
 
 #[cfg(test)]
 mod tests {
-    use test::{TrFailed, TrIgnored, TrOk, filter_tests, parse_opts,
+    use test::{TrFailed, TrIgnored, TrOk, TrTimedOut, filter_tests, parse_opts,
                TestDesc, TestDescAndFn, TestOpts, run_test,
-               MetricMap,
+               MetricMap, MetricAdded, MetricRemoved, Improvement, Regression,
+               LikelyNoise,
                StaticTestName, DynTestName, DynTestFn, ShouldPanic};
     use std::sync::mpsc::channel;
+    use std::thread;
+    use std::time::Duration;
 
     #[test]
     pub fn do_not_run_ignored_tests() {
@@ -1250,12 +2288,13 @@ mod tests {
                 name: StaticTestName("whatever"),
                 ignore: true,
                 should_panic: ShouldPanic::No,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move|| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res != TrOk);
     }
 
@@ -1267,12 +2306,13 @@ mod tests {
                 name: StaticTestName("whatever"),
                 ignore: true,
                 should_panic: ShouldPanic::No,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move|| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrIgnored);
     }
 
@@ -1283,13 +2323,14 @@ mod tests {
             desc: TestDesc {
                 name: StaticTestName("whatever"),
                 ignore: false,
-                should_panic: ShouldPanic::Yes(None)
+                should_panic: ShouldPanic::Yes(None),
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move|| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrOk);
     }
 
@@ -1300,13 +2341,14 @@ mod tests {
             desc: TestDesc {
                 name: StaticTestName("whatever"),
                 ignore: false,
-                should_panic: ShouldPanic::Yes(Some("error message"))
+                should_panic: ShouldPanic::Yes(Some("error message")),
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move|| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrOk);
     }
 
@@ -1317,13 +2359,14 @@ mod tests {
             desc: TestDesc {
                 name: StaticTestName("whatever"),
                 ignore: false,
-                should_panic: ShouldPanic::Yes(Some("foobar"))
+                should_panic: ShouldPanic::Yes(Some("foobar")),
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move|| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrFailed);
     }
 
@@ -1334,13 +2377,14 @@ mod tests {
             desc: TestDesc {
                 name: StaticTestName("whatever"),
                 ignore: false,
-                should_panic: ShouldPanic::Yes(None)
+                should_panic: ShouldPanic::Yes(None),
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move|| f())),
         };
         let (tx, rx) = channel();
         run_test(&TestOpts::new(), false, desc, tx);
-        let (_, res, _) = rx.recv().unwrap();
+        let (_, res, _, _) = rx.recv().unwrap();
         assert!(res == TrFailed);
     }
 
@@ -1356,6 +2400,36 @@ mod tests {
         assert!((opts.run_ignored));
     }
 
+    #[test]
+    fn test_timeout_reports_timed_out() {
+        fn f() { thread::sleep_ms(2_000); }
+        let desc = TestDescAndFn {
+            desc: TestDesc {
+                name: StaticTestName("whatever"),
+                ignore: false,
+                should_panic: ShouldPanic::No,
+                timeout: Some(1),
+            },
+            testfn: DynTestFn(Box::new(move|| f())),
+        };
+        let (tx, rx) = channel();
+        run_test(&TestOpts::new(), false, desc, tx);
+        let (_, res, _, _) = rx.recv().unwrap();
+        assert!(res == TrTimedOut);
+    }
+
+    #[test]
+    fn parse_test_timeout() {
+        let args = vec!("progname".to_string(),
+                        "--test-timeout".to_string(),
+                        "30".to_string());
+        let opts = match parse_opts(&args) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_test_timeout")
+        };
+        assert!(opts.test_timeout == Some(Duration::from_secs(30)));
+    }
+
     #[test]
     pub fn filter_for_ignored_option() {
         // When we run ignored tests the test filter should filter out all the
@@ -1371,6 +2445,7 @@ mod tests {
                     name: StaticTestName("1"),
                     ignore: true,
                     should_panic: ShouldPanic::No,
+                    timeout: None,
                 },
                 testfn: DynTestFn(Box::new(move|| {})),
             },
@@ -1379,6 +2454,7 @@ mod tests {
                     name: StaticTestName("2"),
                     ignore: false,
                     should_panic: ShouldPanic::No,
+                    timeout: None,
                 },
                 testfn: DynTestFn(Box::new(move|| {})),
             });
@@ -1390,6 +2466,102 @@ mod tests {
         assert!(filtered[0].desc.ignore == false);
     }
 
+    #[test]
+    pub fn exact_filter() {
+        let mut opts = TestOpts::new();
+        opts.run_tests = true;
+        opts.filter = Some("base".to_string());
+
+        let tests = || {
+            vec!["base", "base::test", "rebase"].into_iter().map(|name| {
+                TestDescAndFn {
+                    desc: TestDesc {
+                        name: StaticTestName(name),
+                        ignore: false,
+                        should_panic: ShouldPanic::No,
+                        timeout: None,
+                    },
+                    testfn: DynTestFn(Box::new(move|| {})),
+                }
+            }).collect::<Vec<_>>()
+        };
+
+        assert_eq!(filter_tests(&opts, tests()).len(), 3);
+
+        opts.filter_exact = true;
+        let filtered = filter_tests(&opts, tests());
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].desc.name.to_string(), "base");
+    }
+
+    fn named_tests(names: &[&'static str]) -> Vec<TestDescAndFn> {
+        names.iter().map(|&name| {
+            TestDescAndFn {
+                desc: TestDesc {
+                    name: StaticTestName(name),
+                    ignore: false,
+                    should_panic: ShouldPanic::No,
+                    timeout: None,
+                },
+                testfn: DynTestFn(Box::new(move|| {})),
+            }
+        }).collect()
+    }
+
+    #[test]
+    pub fn shard_tests() {
+        let names = ["a", "b", "c", "d", "e"];
+        let mut opts = TestOpts::new();
+        opts.run_tests = true;
+
+        let mut seen = Vec::new();
+        for i in 1..4 {
+            opts.shard = Some((i, 3));
+            let filtered = filter_tests(&opts, named_tests(&names));
+            assert!(filtered.len() == 1 || filtered.len() == 2);
+            seen.extend(filtered.into_iter().map(|t| t.desc.name.to_string()));
+        }
+        seen.sort();
+        assert_eq!(seen, names.iter().map(|n| n.to_string()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    pub fn shuffle_tests_is_reproducible() {
+        let names = ["a", "b", "c", "d", "e", "f", "g", "h"];
+        let mut opts = TestOpts::new();
+        opts.run_tests = true;
+        opts.shuffle_seed = Some(42);
+
+        let order = |opts: &TestOpts| {
+            filter_tests(opts, named_tests(&names)).into_iter()
+                                                   .map(|t| t.desc.name.to_string())
+                                                   .collect::<Vec<_>>()
+        };
+        let first = order(&opts);
+        assert_eq!(first, order(&opts));
+
+        let mut sorted = first.clone();
+        sorted.sort();
+        assert_eq!(sorted, names.iter().map(|n| n.to_string()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn parse_shard_flag() {
+        let args = vec!("progname".to_string(),
+                        "--shard".to_string(),
+                        "2/3".to_string());
+        let opts = match parse_opts(&args) {
+            Some(Ok(o)) => o,
+            _ => panic!("Malformed arg in parse_shard_flag")
+        };
+        assert!(opts.shard == Some((2, 3)));
+
+        let args = vec!("progname".to_string(),
+                        "--shard".to_string(),
+                        "0/3".to_string());
+        assert!(parse_opts(&args).unwrap().is_err());
+    }
+
     #[test]
     pub fn sort_tests() {
         let mut opts = TestOpts::new();
@@ -1415,6 +2587,7 @@ mod tests {
                         name: DynTestName((*name).clone()),
                         ignore: false,
                         should_panic: ShouldPanic::No,
+                        timeout: None,
                     },
                     testfn: DynTestFn(Box::new(testfn)),
                 };
@@ -1461,5 +2634,20 @@ mod tests {
 
         m1.insert_metric("in-both-want-upwards-and-improved", 1000.0, -10.0);
         m2.insert_metric("in-both-want-upwards-and-improved", 2000.0, -10.0);
+
+        let diff1 = m2.compare_to_old(&m1);
+
+        assert_eq!(*(diff1.get("in-both-noise").unwrap()), LikelyNoise);
+        assert_eq!(*(diff1.get("in-first-noise").unwrap()), MetricRemoved);
+        assert_eq!(*(diff1.get("in-second-noise").unwrap()), MetricAdded);
+        assert_eq!(*(diff1.get("in-both-want-downwards-but-regressed").unwrap()),
+                   Regression(100.0));
+        assert_eq!(*(diff1.get("in-both-want-downwards-and-improved").unwrap()),
+                   Improvement(50.0));
+        assert_eq!(*(diff1.get("in-both-want-upwards-but-regressed").unwrap()),
+                   Regression(50.0));
+        assert_eq!(*(diff1.get("in-both-want-upwards-and-improved").unwrap()),
+                   Improvement(100.0));
+        assert_eq!(diff1.len(), 7);
     }
 }
@@ -0,0 +1,21 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// compile-flags: -g -C codegen-units=3
+
+pub fn first(x: u32) -> u32 { x + 1 }
+
+pub mod second {
+    pub fn second<T: Clone>(x: &T) -> (T, T) { (x.clone(), x.clone()) }
+}
+
+pub mod third {
+    pub fn third() -> Vec<String> { vec!["third".to_string()] }
+}
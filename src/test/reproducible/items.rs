@@ -0,0 +1,49 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+// Many reachable items, so that their order in metadata is exercised.
+
+use std::collections::HashMap;
+
+pub trait Shape {
+    fn area(&self) -> f64;
+}
+
+pub struct Square(pub f64);
+pub struct Circle(pub f64);
+
+impl Shape for Square {
+    fn area(&self) -> f64 { self.0 * self.0 }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 { 3.14 * self.0 * self.0 }
+}
+
+pub fn total_area<T: Shape>(shapes: &[T]) -> f64 {
+    shapes.iter().map(|s| s.area()).fold(0.0, |a, b| a + b)
+}
+
+pub fn count_words(text: &str) -> HashMap<&str, usize> {
+    let mut counts = HashMap::new();
+    for word in text.split_whitespace() {
+        *counts.entry(word).or_insert(0) += 1;
+    }
+    counts
+}
+
+#[no_mangle]
+pub extern fn reproducible_a() -> u32 { 1 }
+#[no_mangle]
+pub extern fn reproducible_b() -> u32 { 2 }
+#[no_mangle]
+pub extern fn reproducible_c() -> u32 { 3 }
+#[no_mangle]
+pub extern fn reproducible_d() -> u32 { 4 }
@@ -0,0 +1,8 @@
+fn counter() -> Box<Fn() -> u32> {
+    let x = 1;
+    Box::new(|| x)
+}
+
+fn main() {
+    counter()();
+}
@@ -0,0 +1,6 @@
+fn main() {
+    let mut x = 1;
+    x = 2;
+    let mut v = Vec::new();
+    v.push(x);
+}
@@ -0,0 +1,6 @@
+fn main() {
+    let x = 1;
+    x = 2;
+    let v = Vec::new();
+    v.push(x);
+}
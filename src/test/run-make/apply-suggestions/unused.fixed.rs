@@ -0,0 +1,5 @@
+use std::collections::{HashMap, HashSet};
+
+fn main() {
+    let _ = HashSet::<u8>::new();
+}
@@ -0,0 +1,7 @@
+use std::fmt;
+use std::io::{Read, Write};
+use std::collections::{HashMap, HashSet};
+
+fn main() {
+    let _ = HashSet::<u8>::new();
+}
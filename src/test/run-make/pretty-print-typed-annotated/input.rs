@@ -0,0 +1,36 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+struct Meters(u32);
+
+impl Meters {
+    fn double(&self) -> Meters { Meters(self.0 * 2) }
+}
+
+trait Describe {
+    fn describe(&self) -> u32;
+}
+
+impl Describe for Meters {
+    fn describe(&self) -> u32 { self.0 }
+}
+
+fn describe_any<T: Describe>(t: &T) -> u32 {
+    t.describe()
+}
+
+pub fn main() {
+    let inferred = Meters(3);
+    let written: u32 = 4;
+    let doubled = inferred.double();
+    let add = |x, y| x + y;
+    let sum = add(doubled.describe(), written);
+    let _ = sum + describe_any(&doubled);
+}
@@ -0,0 +1,43 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+//! A crate with some of its items documented.
+
+/// Documented, with an example.
+///
+/// ```
+/// foo::f();
+/// ```
+pub fn f() {}
+
+pub fn g() {}
+
+/// A struct.
+pub struct S {
+    /// A field.
+    pub x: u32,
+    pub y: u32,
+}
+
+impl S {
+    /// Makes an `S`.
+    pub fn new() -> S { S { x: 0, y: 0 } }
+}
+
+impl Clone for S {
+    fn clone(&self) -> S { S::new() }
+}
+
+pub mod m {
+    pub fn h() {}
+}
+
+fn private() {}
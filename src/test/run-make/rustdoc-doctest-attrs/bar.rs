@@ -0,0 +1,25 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+/// ```compile_fail,E0308
+/// let a = vec![1];
+/// let b = a;
+/// let c = a;
+/// ```
+///
+/// ```compile_fail
+/// let a = 1;
+/// ```
+///
+/// ```
+/// assert_eq!(1, 2);
+/// ```
+pub fn bar() {}
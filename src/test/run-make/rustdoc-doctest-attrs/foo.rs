@@ -0,0 +1,30 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+/// Doubles `x`.
+///
+/// ```
+/// assert_eq!(foo::double(2), 4);
+/// ```
+///
+/// ```compile_fail,E0382
+/// let a = vec![1];
+/// let b = a;
+/// let c = a;
+/// ```
+///
+/// ```rustc-flag=--cfg=special
+/// #[cfg(not(special))]
+/// fn main() { panic!() }
+/// #[cfg(special)]
+/// fn main() {}
+/// ```
+pub fn double(x: i32) -> i32 { x * 2 }
@@ -0,0 +1,24 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+//! Links to [`Foo`] and [`Missing`].
+
+pub struct Foo;
+
+/// Does nothing, see [the docs](::nowhere::at_all).
+pub fn bar() {}
+
+pub mod uses {
+    use super::Foo as Renamed;
+
+    /// Returns a [`Renamed`] in an [`Option`].
+    pub fn baz() -> Option<Renamed> { None }
+}
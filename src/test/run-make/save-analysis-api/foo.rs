@@ -0,0 +1,96 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![feature(rustc_private, path, convert)]
+
+extern crate rustc;
+extern crate rustc_driver;
+extern crate rustc_lint;
+extern crate rustc_trans;
+extern crate syntax;
+
+use rustc::session::{build_session, Session};
+use rustc::session::config::{basic_options, build_configuration, Input, OutputTypeExe};
+use rustc_driver::Compilation;
+use rustc_driver::driver::{compile_input, CompileController};
+use rustc_trans::save::AnalysisIndex;
+use syntax::diagnostics::registry::Registry;
+
+use std::cell::Cell;
+use std::path::PathBuf;
+
+const SRC: &'static str = "fn double(x: u32) -> u32 {
+    x * 2
+}
+fn main() {
+    let y = double(21);
+}
+";
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    if args.len() < 4 {
+        panic!("expected rustc path");
+    }
+
+    let tmpdir = PathBuf::from(&args[1]);
+
+    let mut sysroot = PathBuf::from(&args[3]);
+    sysroot.pop();
+    sysroot.pop();
+
+    let sess = basic_sess(sysroot);
+    let cfg = build_configuration(&sess);
+    let called = Cell::new(false);
+    {
+        let mut control = CompileController::basic();
+        control.save_analysis(|index| {
+            check(&index);
+            called.set(true);
+        });
+        control.after_analysis.stop = Compilation::Stop;
+
+        compile_input(sess,
+                      cfg,
+                      &Input::Str(SRC.to_string()),
+                      &None,
+                      &Some(tmpdir.join("out")),
+                      None,
+                      control);
+    }
+    assert!(called.get());
+}
+
+fn check(index: &AnalysisIndex) {
+    let double = index.def_at("<anon>", 1, 4).unwrap();
+    assert_eq!(double.name, "double");
+    assert_eq!(index.id_at("<anon>", 5, 14), Some(double.id));
+
+    let refs = index.refs_to(double.id);
+    assert_eq!(refs.len(), 1);
+    assert_eq!(refs[0].span.line_start, 5);
+    assert_eq!(refs[0].span.column_start, 13);
+
+    assert_eq!(index.type_at("<anon>", 2, 5), Some("u32"));
+    assert_eq!(index.type_at("<anon>", 5, 20), Some("u32"));
+    assert_eq!(index.type_at("<anon>", 3, 1), None);
+}
+
+fn basic_sess(sysroot: PathBuf) -> Session {
+    let mut opts = basic_options();
+    opts.output_types = vec![OutputTypeExe];
+    opts.maybe_sysroot = Some(sysroot);
+
+    let descriptions = Registry::new(&rustc::DIAGNOSTICS);
+    let sess = build_session(opts, None, descriptions);
+    rustc_lint::register_builtins(&mut sess.lint_store.borrow_mut(), Some(&sess));
+    sess
+}
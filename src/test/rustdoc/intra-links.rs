@@ -0,0 +1,49 @@
+// Copyright 2015 The Rust Project Developers. See the COPYRIGHT
+// file at the top-level directory of this distribution and at
+// http://rust-lang.org/COPYRIGHT.
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#![crate_name = "foo"]
+
+//! Links to [`Foo`], [the constructor](Foo::new), [`Foo::x`], [`Enum::B`],
+//! [`Trait::go()`], [bar::baz] and [::Foo](::Foo).
+//!
+//! [`Nothing`] isn't an item, so isn't a link.
+
+// @has foo/index.html '//a/@href' '../foo/struct.Foo.html'
+// @has foo/index.html '//a/@href' '../foo/struct.Foo.html#method.new'
+// @has foo/index.html '//a/@href' '../foo/struct.Foo.html#structfield.x'
+// @has foo/index.html '//a/@href' '../foo/enum.Enum.html#variant.B'
+// @has foo/index.html '//a/@href' '../foo/trait.Trait.html#tymethod.go'
+// @has foo/index.html '//a/@href' '../foo/bar/fn.baz.html'
+// @has foo/index.html '//code' 'Nothing'
+// @!has foo/index.html '//a/@href' 'Nothing'
+
+pub struct Foo {
+    pub x: u32,
+}
+
+impl Foo {
+    pub fn new() -> Foo { Foo { x: 0 } }
+}
+
+pub enum Enum { A, B }
+
+pub trait Trait {
+    fn go(&self);
+}
+
+pub mod bar {
+    /// Makes a [`super::Foo`], not a [`self::Foo`].
+    // @has foo/bar/fn.baz.html '//a/@href' '../../foo/struct.Foo.html'
+    // @has foo/bar/fn.baz.html '//a/@href' '../../foo/bar/struct.Foo.html'
+    pub fn baz() {}
+
+    pub struct Foo;
+}